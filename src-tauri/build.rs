@@ -1,3 +1,5 @@
 fn main() {
+    // El servidor de ring sin interfaz no necesita nada de Tauri
+    #[cfg(feature = "desktop")]
     tauri_build::build()
 }
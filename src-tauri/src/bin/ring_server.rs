@@ -0,0 +1,32 @@
+//! Servidor de ring sin interfaz: `ring-server [ruta/a/ring-server.toml]`
+
+use std::path::PathBuf;
+use tracing::error;
+
+use beat_hard_combat_lib::headless::{self, config::HeadlessConfig};
+
+/// Archivo de configuración si no se indica ninguno
+const DEFAULT_CONFIG_PATH: &str = "ring-server.toml";
+
+#[tokio::main]
+async fn main() {
+    let config_path = std::env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+
+    // Sin configuración todavía no hay logging: el error va a stderr tal cual
+    let config = match HeadlessConfig::from_file(&config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("ring-server: {}", e);
+            std::process::exit(1);
+        }
+    };
+    headless::init_json_tracing(&config.log_filter);
+
+    if let Err(e) = headless::run(&config_path, config).await {
+        error!(error = %e, "❌ El servidor de ring se detuvo por un error");
+        std::process::exit(1);
+    }
+}
@@ -3,5 +3,10 @@
 pub mod types;
 pub mod detection;
 pub mod connection;
+pub mod actor;
 pub mod state;
+pub mod transport;
+#[cfg(feature = "desktop")]
 pub mod commands;
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,326 @@
+//! Pipeline de dispositivos basado en actores
+//!
+//! Cada dispositivo conectado es un actor dueño de su detector: lee las
+//! notificaciones de un canal acotado y entrega lo que detecta a una única
+//! tarea de difusión. Esa tarea se encarga del registro del combate, el emit a
+//! Tauri, las estadísticas, el marcador y el WebSocket, de modo que un
+//! consumidor lento no frena la lectura de los demás sensores. Los golpes y
+//! los movimientos defensivos nunca se descartan: si la cola de difusión está
+//! llena, el actor espera a que haya hueco (y su propia cola frena al
+//! transporte). Solo la salud del dispositivo se sustituye por la siguiente.
+//!
+//! Los consumidores usan locks de `std`, así que la tarea de difusión corre en
+//! un hilo propio y no ocupa un hilo del ejecutor async.
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::sync::mpsc::error::TrySendError;
+use tracing::{debug, error, info, warn};
+
+use crate::ble::detection::{check_and_update_max_stats, parse_imu_data, SimpleEventDetector};
+use crate::ble::state::BleManager;
+use crate::ble::types::{DefensiveMotionEvent, DeviceHealth, ImuData, SimpleCombatEvent};
+use crate::bout::event_log::append_combat_event;
+use crate::bout::turns::{
+    announce_defensive_motion, announce_foul, announce_turn, check_strike, defending_turn, StrikeCheck,
+};
+use crate::bout::types::TurnEndReason;
+use crate::broadcast_ws::protocol::ServerMessage;
+use crate::scoring::engine::apply_scoring_event;
+
+/// Notificaciones en cola entre el transporte y el actor de cada dispositivo
+pub const NOTIFICATION_BUFFER: usize = 64;
+
+/// Detecciones en cola entre los actores y la tarea de difusión
+pub const FANOUT_BUFFER: usize = 256;
+
+// Lo que un actor entrega a la tarea de difusión
+#[derive(Debug)]
+pub enum DeviceOutput {
+    Strike(SimpleCombatEvent),
+    DefensiveMotion(DefensiveMotionEvent),
+    Health(DeviceHealth),
+}
+
+// Actor de un dispositivo: único dueño de su detector
+pub struct DeviceActor {
+    ble: BleManager,
+    device_id: String,
+    detector: SimpleEventDetector,
+    inbox: mpsc::Receiver<Vec<u8>>,
+    outbox: mpsc::Sender<DeviceOutput>,
+    saturated: bool,                     // La cola llegó a su capacidad y el transporte está esperando
+    pending_health: Option<DeviceHealth>, // Última salud que no cupo en la difusión; se sustituye por la siguiente
+    stalled: bool,                        // La difusión está llena y el actor espera para entregar
+}
+
+impl DeviceActor {
+    pub fn new(ble: &BleManager, device_id: &str, detector: SimpleEventDetector, inbox: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self {
+            ble: ble.clone(),
+            device_id: device_id.to_string(),
+            detector,
+            inbox,
+            outbox: ble.fanout_sender(),
+            saturated: false,
+            pending_health: None,
+            stalled: false,
+        }
+    }
+
+    /// Procesa notificaciones hasta que el transporte cierra el canal
+    pub async fn run(mut self) {
+        info!(device_id = %self.device_id, ring_id = %self.detector.ring_id(), "🎭 Actor de dispositivo iniciado");
+
+        while let Some(data_bytes) = self.inbox.recv().await {
+            // Notificaciones que siguen esperando detrás de esta
+            let queue_depth = self.inbox.len();
+            self.watch_saturation(queue_depth);
+
+            let imu_data = match parse_imu_data(&data_bytes) {
+                Ok(imu_data) => imu_data,
+                Err(e) => {
+                    warn!(device_id = %self.device_id, error = %e, "⚠️ Error procesando notificación");
+                    continue;
+                }
+            };
+
+            // Actualizar batería, actividad y cola del dispositivo
+            if let Some(health) = self.ble.record_device_packet(&self.device_id, imu_data.battery_level, queue_depth) {
+                self.pending_health = Some(health);
+            }
+            self.flush_health();
+
+            if let Some(output) = self.detect(&imu_data) {
+                self.deliver(output).await;
+            }
+        }
+
+        debug!(device_id = %self.device_id, "🎭 Actor de dispositivo terminado");
+    }
+
+    /// Pasa el detector por los datos IMU, sin tocar estado compartido salvo los turnos
+    fn detect(&mut self, imu_data: &ImuData) -> Option<DeviceOutput> {
+        // Los golpes siempre se evalúan: fuera de turno se difunden como falta
+        if let Some(event) = self.detector.detect_event(imu_data) {
+            return Some(DeviceOutput::Strike(event));
+        }
+
+        // Durante el turno rival, las manos del defensor delatan bloqueos y encogimientos
+        let fighter_id = self.detector.fighter_id()?;
+        let turn = defending_turn(self.ble.hub(), self.detector.ring_id(), &fighter_id)?;
+        self.detector.detect_defensive_motion(imu_data, &self.ble.defense_detection_config(), &turn)
+            .map(DeviceOutput::DefensiveMotion)
+    }
+
+    /// Entrega una detección a la tarea de difusión; si la cola está llena, espera a que haya hueco
+    async fn deliver(&mut self, output: DeviceOutput) {
+        let output = match self.outbox.try_send(output) {
+            Ok(()) => {
+                self.stalled = false;
+                return;
+            }
+            Err(TrySendError::Full(output)) => output,
+            Err(TrySendError::Closed(_)) => {
+                warn!(device_id = %self.device_id, "⚠️ Tarea de difusión no disponible, detección descartada");
+                return;
+            }
+        };
+
+        let stalled = self.ble.record_stalled_detection();
+        if !self.stalled {
+            warn!(device_id = %self.device_id, stalled_total = stalled, "🐢 Difusión saturada, esperando para entregar la detección");
+        }
+        self.stalled = true;
+        if self.outbox.send(output).await.is_err() {
+            warn!(device_id = %self.device_id, "⚠️ Tarea de difusión no disponible, detección descartada");
+        }
+    }
+
+    /// Envía la salud pendiente si hay hueco; si no, espera a la siguiente, que la sustituye
+    fn flush_health(&mut self) {
+        let Some(health) = self.pending_health.take() else {
+            return;
+        };
+        match self.outbox.try_send(DeviceOutput::Health(health)) {
+            Ok(()) | Err(TrySendError::Closed(_)) => {}
+            Err(TrySendError::Full(DeviceOutput::Health(health))) => self.pending_health = Some(health),
+            Err(TrySendError::Full(_)) => {}
+        }
+    }
+
+    /// Avisa una sola vez cada vez que la cola se llena
+    fn watch_saturation(&mut self, queue_depth: usize) {
+        let saturated = queue_depth + 1 >= NOTIFICATION_BUFFER;
+        if saturated && !self.saturated {
+            warn!(device_id = %self.device_id, queue_depth = queue_depth, "🐢 Cola del actor llena, el sensor va por delante del detector");
+        }
+        self.saturated = saturated;
+    }
+}
+
+/// Tarea de difusión: reparte en orden lo que detectan los actores de todos los dispositivos
+pub async fn run_fanout(ble: BleManager) {
+    let Some(outputs) = ble.take_fanout_receiver() else {
+        warn!("La tarea de difusión de dispositivos ya está en marcha");
+        return;
+    };
+
+    // Los consumidores toman locks de `std`: se atienden desde un hilo propio que
+    // no retiene el apagado del runtime, pero puede lanzar tareas en él
+    let runtime = tokio::runtime::Handle::current();
+    let (done_tx, done_rx) = oneshot::channel();
+    let thread = std::thread::Builder::new()
+        .name("ble-fanout".to_string())
+        .spawn(move || {
+            let _runtime = runtime.enter();
+            fanout_loop(ble, outputs);
+            let _ = done_tx.send(());
+        });
+
+    match thread {
+        Ok(_) => {
+            let _ = done_rx.await;
+        }
+        Err(e) => error!(error = %e, "❌ No se pudo lanzar el hilo de difusión de dispositivos"),
+    }
+}
+
+/// Bucle de la tarea de difusión; termina cuando se cierra el canal de los actores
+fn fanout_loop(ble: BleManager, mut outputs: mpsc::Receiver<DeviceOutput>) {
+    info!("📡 Tarea de difusión de dispositivos iniciada");
+    while let Some(output) = outputs.blocking_recv() {
+        match output {
+            DeviceOutput::Strike(event) => publish_combat_event(&ble, event),
+            DeviceOutput::DefensiveMotion(motion) => announce_defensive_motion(ble.hub(), &motion),
+            DeviceOutput::Health(health) => {
+                ble.hub().broadcast(&health.ring_id.clone(), &ServerMessage::DeviceStatus { connected: true, data: health });
+            }
+        }
+    }
+}
+
+/// Valida un golpe contra el turno y lo hace llegar a todos los consumidores
+fn publish_combat_event(ble: &BleManager, event: SimpleCombatEvent) {
+    let hub = ble.hub();
+
+    // En el modo por turnos solo cuenta el atacante del turno
+    let next_turn = match check_strike(hub, &event) {
+        StrikeCheck::Foul(foul) => {
+            announce_foul(hub, &foul);
+            return;
+        }
+        StrikeCheck::Valid(next_turn) => next_turn,
+        StrikeCheck::Free => None,
+    };
+
+    // Registrar el evento en el log del combate
+    append_combat_event(&event);
+
+    // Emitir evento al frontend
+    hub.emit_frontend("simple-combat-event", &event);
+
+    // Verificar y actualizar estadísticas máximas
+    check_and_update_max_stats(&event, ble);
+
+    // Aplicar reglamento de puntuación y actualizar marcador
+    apply_scoring_event(&event, hub);
+
+    // Broadcast via WebSocket con formato completo
+    hub.broadcast(&event.ring_id, &ServerMessage::CombatEvent { data: event.clone() });
+
+    info!(
+        ring_id = %event.ring_id,
+        event_type = %event.event_type,
+        limb = %event.limb_name,
+        fighter = %event.fighter_id,
+        confidence = %event.confidence,
+        "📡 Evento emitido al frontend y WebSocket"
+    );
+
+    // El cambio de turno se anuncia después del golpe que lo provocó
+    if let Some(next_turn) = next_turn {
+        announce_turn(hub, &next_turn, TurnEndReason::Strike);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use tokio::sync::broadcast;
+
+    use super::*;
+    use crate::ble::connection::connect_device;
+    use crate::ble::testing::{imu_packet, mock_ble, MockTransport};
+    use crate::ble::types::CompetitorInfo;
+    use crate::bout::turns::{start_turn_mode, stop_turn_mode};
+    use crate::broadcast_ws::channel::BroadcastFrame;
+
+    // Bofetada clara para una mano: 2 g y 10 °/s con la escala por defecto
+    fn slap_packet() -> Vec<u8> {
+        imu_packet(87, [2000, 0, 0], [2500, 0, 0])
+    }
+
+    fn competitor(id: u8, name: &str) -> CompetitorInfo {
+        CompetitorInfo { id, name: name.to_string(), weight: 80.0 }
+    }
+
+    // Espera el primer frame del tipo pedido en el feed del ring
+    async fn next_frame(rx: &mut broadcast::Receiver<std::sync::Arc<BroadcastFrame>>, message_type: &str) -> serde_json::Value {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let frame = rx.recv().await.expect("feed del ring cerrado");
+                if frame.message_type == message_type {
+                    return serde_json::from_str(&frame.payload).expect("frame con JSON válido");
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("no llegó ningún '{}'", message_type))
+    }
+
+    #[tokio::test]
+    async fn strike_from_mock_sensor_reaches_hub_and_frontend() {
+        let ring_id = "test-actor-strike";
+        let transport = MockTransport::default()
+            .with_device("dev-left-hand", "BH-ManoIzquierda", vec![slap_packet()]);
+        let (ble, emitter) = mock_ble(transport);
+        let mut feed = ble.hub().ring(ring_id).channel().subscribe();
+        tokio::spawn(run_fanout(ble.clone()));
+
+        connect_device(&ble, ring_id, "dev-left-hand".to_string(), Some(competitor(1, "Ana"))).await
+            .expect("conexión simulada");
+
+        let frame = next_frame(&mut feed, "combat_event").await;
+        assert_eq!(frame["data"]["ring_id"], ring_id);
+        assert_eq!(frame["data"]["fighter_id"], "fighter_1");
+        assert_eq!(frame["data"]["event_type"], "slap");
+
+        let emitted = emitter.events("simple-combat-event");
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0]["competitor_name"], "Ana");
+        assert_eq!(ble.ring_max_stats(ring_id).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn out_of_turn_strike_is_broadcast_as_foul() {
+        let ring_id = "test-actor-foul";
+        let transport = MockTransport::default()
+            .with_device("dev-right-hand", "BH-ManoDerecha", vec![slap_packet()]);
+        let (ble, emitter) = mock_ble(transport);
+        let mut feed = ble.hub().ring(ring_id).channel().subscribe();
+        tokio::spawn(run_fanout(ble.clone()));
+
+        let fighters = ["fighter_1".to_string(), "fighter_2".to_string()];
+        start_turn_mode(ble.hub(), ring_id, fighters, "fighter_1", 1, None).expect("modo por turnos");
+
+        connect_device(&ble, ring_id, "dev-right-hand".to_string(), Some(competitor(2, "Bea"))).await
+            .expect("conexión simulada");
+
+        let frame = next_frame(&mut feed, "turn_foul").await;
+        assert_eq!(frame["data"]["fighter_id"], "fighter_2");
+        assert_eq!(frame["data"]["expected_striker"], "fighter_1");
+        assert!(emitter.events("simple-combat-event").is_empty());
+
+        stop_turn_mode(ble.hub(), ring_id);
+    }
+}
@@ -1,135 +1,54 @@
 //! Comandos Tauri para el sistema BLE
 
-use std::time::Duration;
-use std::sync::Arc;
-use tauri::{AppHandle};
-use tracing::{info, debug, error, instrument};
+use tauri::State;
+use tracing::{info, debug, instrument};
 
-use crate::ble::types::{BleDevice, LimbType, BleResult};
-use crate::ble::detection::{determine_limb_type_by_pattern, SimpleEventDetector};
+use crate::ble::types::{BleDevice, DefenseDetectionConfig};
 use crate::ble::connection::{
-    connect_to_device_with_competitor as connect_device_internal, 
-    setup_basic_detector, disconnect_device, disconnect_all_devices
+    connect_to_device_with_competitor as connect_device_internal,
+    connect_to_device_basic as connect_basic_internal, disconnect_device, disconnect_all_devices, scan_devices
 };
-use crate::ble::state::{
-    get_ble_adapter, get_connected_devices_state, get_device_references_state,
-    get_device_tasks_state, get_max_stats_store, register_device_without_competitor,
-    cleanup_ble_system, get_system_status
-};
-use crate::broadcast_ws::ws_broadcast;
+use crate::ble::state::BleManager;
+use crate::bout::ring::ring_or_default;
 
 /// Función para escanear dispositivos BLE disponibles
 /// Mantiene exactamente el mismo algoritmo que simple_ble.rs
 #[tauri::command]
-#[instrument]
-pub async fn scan_available_devices() -> Result<Vec<BleDevice>, String> {
-    info!("🔍 Iniciando escaneo de dispositivos BLE...");
-    
-    // Obtener adaptador singleton
-    let adapter = get_ble_adapter().await?;
-    
-    // Esperar a que el adaptador esté disponible
-    adapter.wait_available().await
-        .map_err(|e| format!("Error esperando adaptador: {}", e))?;
-    
-    let mut scan = adapter.scan(&[]).await
-        .map_err(|e| format!("Error iniciando escaneo: {}", e))?;
-    
-    let mut devices = Vec::with_capacity(8); // Pre-allocar para hasta 8 dispositivos (2 peleadores x 4 extremidades)
-    let mut seen_devices = std::collections::HashSet::with_capacity(8);
-    
-    // Usar timeout con pin más eficiente
-    let scan_timeout = tokio::time::sleep(Duration::from_secs(2));
-    tokio::pin!(scan_timeout);
-    
-    loop {
-        tokio::select! {
-            _ = &mut scan_timeout => {
-                info!(found_devices = devices.len(), "⏰ Escaneo BLE completado por timeout");
-                break;
-            }
-            discovered = scan.next() => {
-                match discovered {
-                    Some(discovered_device) => {
-                        let adv_data = &discovered_device.adv_data;
-                        
-                        // Verificar primero si es un dispositivo BH
-                        let local_name = match &adv_data.local_name {
-                            Some(name) if name.contains("BH-") => name,
-                            _ => continue,
-                        };
-                        
-                        let device = discovered_device.device;
-                        let device_address = device.id().to_string();
-                        let device_id = device_address.clone();
-                        
-                        // Evitar duplicados usando HashSet
-                        if !seen_devices.insert(device_id.clone()) {
-                            continue;
-                        }
-                        
-                        // Determinar tipo de extremidad y nombre traducido
-                        let limb_type = determine_limb_type_by_pattern(local_name);
-                        let limb_name = limb_type.name().to_string();
-                        
-                        let ble_device = BleDevice {
-                            id: device_id,
-                            name: local_name.clone(),
-                            address: device_address,
-                            limb_type: Some(limb_type.ble_name_pattern().to_string()),
-                            limb_name: Some(limb_name),
-                            rssi: discovered_device.rssi,
-                            is_connectable: true,
-                        };
-                        
-                        devices.push(ble_device);
-                        
-                        // Limitar a 8 dispositivos para evitar overflow
-                        if devices.len() >= 8 {
-                            info!("🔍 Límite de 8 dispositivos alcanzado, terminando escaneo");
-                            break;
-                        }
-                    }
-                    None => break,
-                }
-            }
-        }
-    }
-    
-    info!(found_devices = devices.len(), "✅ Escaneo BLE completado");
-    Ok(devices)
+#[instrument(skip(ble))]
+pub async fn scan_available_devices(ble: State<'_, BleManager>) -> Result<Vec<BleDevice>, String> {
+    scan_devices(&ble).await
 }
 
 /// Función para obtener lista de dispositivos conectados
 /// Mantiene exactamente el mismo algoritmo que simple_ble.rs
 #[tauri::command]
-pub async fn get_connected_devices() -> Result<Vec<String>, String> {
-    let connected_devices = get_connected_devices_state();
-    let devices = connected_devices.lock().unwrap();
-    Ok(devices.keys().cloned().collect())
+pub async fn get_connected_devices(ble: State<'_, BleManager>) -> Result<Vec<String>, String> {
+    Ok(ble.connected_device_ids())
 }
 
 /// Conecta a un dispositivo BLE con información del competidor
 /// Mantiene exactamente el mismo algoritmo que simple_ble.rs
 #[tauri::command]
-pub async fn connect_to_device_with_competitor<R: tauri::Runtime>(
+pub async fn connect_to_device_with_competitor(
+    ble: State<'_, BleManager>,
+    ring_id: Option<String>,
     device_id: String,
     competitor_id: u8,
     competitor_name: String,
     competitor_weight: f32,
-    app_handle: AppHandle<R>,
 ) -> Result<String, String> {
+    let ring_id = ring_or_default(ring_id)?;
     info!(
         device_id = %device_id,
+        ring_id = %ring_id,
         competitor_name = %competitor_name,
         competitor_weight = competitor_weight,
         "🔗 Comando: Conectar dispositivo con competidor"
     );
     
-    let app_handle_arc = Arc::new(app_handle);
-    
     connect_device_internal(
-        app_handle_arc,
+        &ble,
+        &ring_id,
         device_id.clone(),
         competitor_id,
         competitor_name.clone(),
@@ -141,36 +60,15 @@ pub async fn connect_to_device_with_competitor<R: tauri::Runtime>(
 
 /// Conecta a un dispositivo BLE sin información del competidor
 #[tauri::command]
-pub async fn connect_to_device_basic<R: tauri::Runtime>(
+pub async fn connect_to_device_basic(
+    ble: State<'_, BleManager>,
+    ring_id: Option<String>,
     device_id: String,
-    app_handle: AppHandle<R>,
 ) -> Result<String, String> {
-    info!(device_id = %device_id, "🔗 Comando: Conectar dispositivo básico");
-    
-    // Buscar dispositivo
-    let (target_device, device_name) = find_ble_device_by_id(&device_id).await?;
-    
-    // Determinar tipo de extremidad
-    let limb_type = determine_limb_type_by_pattern(&device_name);
+    let ring_id = ring_or_default(ring_id)?;
+    info!(device_id = %device_id, ring_id = %ring_id, "🔗 Comando: Conectar dispositivo básico");
     
-    // Registrar dispositivo sin competidor
-    register_device_without_competitor(&device_id, &device_name);
-    
-    // Configurar detector básico
-    let detector = setup_basic_detector(limb_type);
-    
-    // Almacenar referencia del dispositivo
-    let device_references = get_device_references_state();
-    let mut references = device_references.lock().unwrap();
-    references.insert(device_id.clone(), target_device.clone());
-    drop(references);
-    
-    // Lanzar tarea de manejo
-    let app_handle_arc = Arc::new(app_handle);
-    let task = spawn_device_handler(target_device, limb_type, detector, app_handle_arc, device_id.clone());
-    let device_tasks = get_device_tasks_state();
-    let mut tasks = device_tasks.lock().unwrap();
-    tasks.insert(device_id.clone(), task);
+    connect_basic_internal(&ble, &ring_id, device_id.clone()).await?;
     
     Ok(format!("Dispositivo {} conectado exitosamente", device_id))
 }
@@ -178,20 +76,20 @@ pub async fn connect_to_device_basic<R: tauri::Runtime>(
 /// Desconecta un dispositivo BLE específico
 /// Mantiene exactamente el mismo algoritmo que simple_ble.rs
 #[tauri::command]
-pub async fn disconnect_from_device(device_id: String) -> Result<String, String> {
+pub async fn disconnect_from_device(ble: State<'_, BleManager>, device_id: String) -> Result<String, String> {
     info!(device_id = %device_id, "🔌 Comando: Desconectar dispositivo");
     
-    disconnect_device(&device_id).await?;
+    disconnect_device(&ble, &device_id).await?;
     
     Ok(format!("Dispositivo {} desconectado exitosamente", device_id))
 }
 
 /// Desconecta todos los dispositivos BLE
 #[tauri::command]
-pub async fn disconnect_all_ble_devices() -> Result<String, String> {
+pub async fn disconnect_all_ble_devices(ble: State<'_, BleManager>) -> Result<String, String> {
     info!("🔌 Comando: Desconectar todos los dispositivos");
     
-    disconnect_all_devices().await?;
+    disconnect_all_devices(&ble).await?;
     
     Ok("Todos los dispositivos desconectados exitosamente".to_string())
 }
@@ -199,22 +97,30 @@ pub async fn disconnect_all_ble_devices() -> Result<String, String> {
 /// Comando para obtener estadísticas máximas actuales
 /// Mantiene exactamente el mismo algoritmo que simple_ble.rs
 #[tauri::command]
-pub fn get_current_max_stats(fighter_id: Option<String>) -> Result<serde_json::Value, String> {
-    let store = get_max_stats_store();
-    let stats_map = store.lock()
-        .map_err(|e| format!("Error accediendo al store: {}", e))?;
+pub fn get_current_max_stats(
+    ble: State<'_, BleManager>,
+    ring_id: Option<String>,
+    fighter_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    current_max_stats(&ble, &ring_or_default(ring_id)?, fighter_id)
+}
+
+fn current_max_stats(ble: &BleManager, ring_id: &str, fighter_id: Option<String>) -> Result<serde_json::Value, String> {
+    let stats_map = ble.max_stats();
 
     match fighter_id {
         Some(id) => {
-            if let Some(stats) = stats_map.get(&id) {
+            if let Some(stats) = stats_map.get(&(ring_id.to_string(), id.clone())) {
                 Ok(serde_json::to_value(stats).unwrap())
             } else {
                 Err(format!("No se encontraron estadísticas para el peleador {}", id))
             }
         }
         None => {
-            // Devolver todas las estadísticas
-            let all_stats: Vec<_> = stats_map.values().collect();
+            // Devolver todas las estadísticas del ring
+            let all_stats: Vec<_> = stats_map.values()
+                .filter(|stats| stats.ring_id == ring_id)
+                .collect();
             Ok(serde_json::to_value(all_stats).unwrap())
         }
     }
@@ -223,49 +129,33 @@ pub fn get_current_max_stats(fighter_id: Option<String>) -> Result<serde_json::V
 /// Comando para resetear estadísticas máximas
 /// Mantiene exactamente el mismo algoritmo que simple_ble.rs
 #[tauri::command]
-pub fn reset_max_stats() -> Result<String, String> {
-    let store = get_max_stats_store();
-    let mut stats_map = store.lock()
-        .map_err(|e| format!("Error accediendo al store: {}", e))?;
-
-    stats_map.clear();
-
-    // Notificar reset por WebSocket
-    let reset_message = serde_json::json!({
-        "type": "max_stats_reset",
-        "timestamp": std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64
-    });
-    ws_broadcast(&reset_message);
-
-    info!("🔄 Estadísticas máximas reseteadas");
+pub fn reset_max_stats(ble: State<'_, BleManager>, ring_id: Option<String>) -> Result<String, String> {
+    ble.reset_ring_max_stats(&ring_or_default(ring_id)?);
     Ok("Estadísticas máximas reseteadas exitosamente".to_string())
 }
 
 /// Comando para obtener información del sistema BLE
 #[tauri::command]
-pub async fn get_system_info() -> Result<serde_json::Value, String> {
+pub async fn get_system_info(ble: State<'_, BleManager>) -> Result<serde_json::Value, String> {
     debug!("ℹ️ Obteniendo información del sistema BLE");
-    Ok(get_system_status())
+    Ok(ble.system_status())
 }
 
 /// Comando para limpiar completamente el sistema BLE
 #[tauri::command]
-pub async fn cleanup_ble_system_command() -> Result<String, String> {
+pub async fn cleanup_ble_system_command(ble: State<'_, BleManager>) -> Result<String, String> {
     info!("🧹 Comando: Limpiar sistema BLE");
     
-    cleanup_ble_system().await;
+    ble.cleanup_ble_system().await;
     
     Ok("Sistema BLE limpiado exitosamente".to_string())
 }
 
 /// Comando para obtener estadísticas de combate
 #[tauri::command]
-pub async fn get_combat_stats() -> Result<serde_json::Value, String> {
-    let system_status = get_system_status();
-    let max_stats = get_current_max_stats(None)?;
+pub async fn get_combat_stats(ble: State<'_, BleManager>, ring_id: Option<String>) -> Result<serde_json::Value, String> {
+    let system_status = ble.system_status();
+    let max_stats = current_max_stats(&ble, &ring_or_default(ring_id)?, None)?;
     
     Ok(serde_json::json!({
         "system": system_status,
@@ -273,73 +163,19 @@ pub async fn get_combat_stats() -> Result<serde_json::Value, String> {
     }))
 }
 
-// ============================================================================
-// FUNCIONES AUXILIARES INTERNAS
-// ============================================================================
-
-use futures::StreamExt;
-use tokio::task::JoinHandle;
-use std::sync::Mutex;
-
-/// Busca un dispositivo BLE por su ID (función auxiliar interna)
-async fn find_ble_device_by_id(device_id: &str) -> BleResult<(bluest::Device, String)> {
-    debug!(device_id = %device_id, "🔍 Buscando dispositivo BLE");
-    
-    let adapter = get_ble_adapter().await?;
-    adapter.wait_available().await
-        .map_err(|e| format!("Error esperando adaptador: {}", e))?;
-    
-    let mut scan = adapter.scan(&[]).await
-        .map_err(|e| format!("Error iniciando escaneo: {}", e))?;
-    
-    let scan_timeout = tokio::time::sleep(Duration::from_secs(10));
-    tokio::pin!(scan_timeout);
-    
-    loop {
-        tokio::select! {
-            _ = &mut scan_timeout => {
-                return Err(format!("Dispositivo {} no encontrado en 10 segundos", device_id));
-            }
-            discovered = scan.next() => {
-                match discovered {
-                    Some(discovered_device) => {
-                        let device = discovered_device.device;
-                        let adv_data = &discovered_device.adv_data;
-                        
-                        if let Some(local_name) = &adv_data.local_name {
-                            if local_name.contains("BH-") && device.id().to_string() == device_id {
-                                info!(device_id = %device_id, device_name = %local_name, "✅ Dispositivo encontrado");
-                                return Ok((device, local_name.clone()));
-                            }
-                        }
-                    }
-                    None => break,
-                }
-            }
-        }
-    }
-    
-    Err(format!("Dispositivo {} no encontrado", device_id))
+/// Obtiene la sensibilidad de la detección de bloqueos y encogimientos
+#[tauri::command]
+pub fn get_defense_detection(ble: State<'_, BleManager>) -> Result<DefenseDetectionConfig, String> {
+    Ok(ble.defense_detection_config())
 }
 
-/// Lanza una tarea para manejar un dispositivo BLE (función auxiliar interna)
-fn spawn_device_handler<R: tauri::Runtime>(
-    device: bluest::Device,
-    limb_type: LimbType,
-    detector: Arc<Mutex<SimpleEventDetector>>,
-    app_handle: Arc<AppHandle<R>>,
-    device_id: String,
-) -> JoinHandle<()> {
-    tokio::spawn(async move {
-        info!(device_id = %device_id, limb_type = ?limb_type, "🚀 Iniciando manejo de dispositivo");
-        
-        // Usar la función pública de connection
-        if let Err(e) = crate::ble::connection::handle_simple_peripheral(device, limb_type, detector, app_handle).await {
-            error!(device_id = %device_id, error = %e, "❌ Error en manejo de dispositivo");
-        }
-        
-        // Limpiar al terminar
-        crate::ble::state::unregister_connected_device(&device_id);
-        info!(device_id = %device_id, "🔌 Manejo de dispositivo terminado");
-    })
+/// Ajusta la sensibilidad de la detección de bloqueos y encogimientos
+#[tauri::command]
+pub fn update_defense_detection(
+    ble: State<'_, BleManager>,
+    config: DefenseDetectionConfig,
+) -> Result<DefenseDetectionConfig, String> {
+    info!(sensitivity = config.sensitivity, enabled = config.enabled, "🛡️ Comando: Ajustar detección defensiva");
+    ble.set_defense_detection_config(config.clone())?;
+    Ok(config)
 }
@@ -1,31 +1,40 @@
 //! Detector de eventos de combate
 
 use std::time::{SystemTime, UNIX_EPOCH};
-use tracing::{info, debug, error};
-use tauri::{AppHandle, Emitter};
+use tracing::{info, debug};
 
 use crate::ble::types::{
     ImuData, LimbType, SimpleCombatEvent, SimpleDetectionConfig, 
-    CompetitorInfo, CompetitorMaxStats, BleResult
+    CompetitorInfo, CompetitorMaxStats, BleResult,
+    DefenseDetectionConfig, DefensiveMotionEvent,
 };
-use crate::ble::state::get_max_stats_store;
-use crate::broadcast_ws::ws_broadcast;
+use crate::bout::types::TurnState;
+use crate::ble::state::BleManager;
+use crate::bout::state::next_event_sequence;
+use crate::broadcast_ws::protocol::ServerMessage;
+use crate::webhooks::notify_new_max_record;
 
 // Detector ultra-simple para sistema por turnos
 pub struct SimpleEventDetector {
     config: SimpleDetectionConfig,
+    ring_id: String,                // Ring al que pertenece el sensor
     competitor_info: Option<CompetitorInfo>,
     limb_type: LimbType,
     last_event_time: u64, // Timestamp del último evento detectado
+    last_defense_time: u64, // Timestamp del último movimiento defensivo detectado
+    pending_defense: Option<DefensiveMotionEvent>, // Movimiento a la espera de un golpe cercano del atacante
 }
 
 impl SimpleEventDetector {
-    pub fn new(limb_type: LimbType) -> Self {
+    pub fn new(ring_id: &str, limb_type: LimbType) -> Self {
         Self {
             config: SimpleDetectionConfig::default(),
+            ring_id: ring_id.to_string(),
             competitor_info: None,
             limb_type,
             last_event_time: 0,
+            last_defense_time: 0,
+            pending_defense: None,
         }
     }
 
@@ -34,6 +43,122 @@ impl SimpleEventDetector {
         self.competitor_info = Some(info);
     }
 
+    pub fn ring_id(&self) -> &str {
+        &self.ring_id
+    }
+
+    /// Peleador al que pertenece el sensor, si ya tiene competidor asignado
+    pub fn fighter_id(&self) -> Option<String> {
+        self.competitor_info.as_ref().map(|competitor| format!("fighter_{}", competitor.id))
+    }
+
+    /// Busca bloqueos o encogimientos en la mano del defensor durante el turno rival
+    ///
+    /// Solo cuentan los que caen cerca de un golpe válido del atacante; como la
+    /// reacción puede llegar antes que el golpe, el movimiento se retiene hasta
+    /// que llega el golpe o pasa la ventana.
+    pub fn detect_defensive_motion(
+        &mut self,
+        data: &ImuData,
+        defense: &DefenseDetectionConfig,
+        turn: &TurnState,
+    ) -> Option<DefensiveMotionEvent> {
+        let competitor = self.competitor_info.as_ref()?;
+        if !defense.enabled || !matches!(self.limb_type, LimbType::LeftHand | LimbType::RightHand) {
+            return None;
+        }
+
+        let acc_x = data.acc_x as f32 / self.config.acc_scale;
+        let acc_y = data.acc_y as f32 / self.config.acc_scale;
+        let acc_z = data.acc_z as f32 / self.config.acc_scale;
+        let gyro_x = data.gyro_x as f32 / self.config.gyro_scale;
+        let gyro_y = data.gyro_y as f32 / self.config.gyro_scale;
+        let gyro_z = data.gyro_z as f32 / self.config.gyro_scale;
+
+        // En reposo el acelerómetro marca 1g; lo que importa es la desviación
+        let acc_deviation = ((acc_x * acc_x + acc_y * acc_y + acc_z * acc_z).sqrt() - 1.0).abs();
+        let gyro_magnitude = (gyro_x * gyro_x + gyro_y * gyro_y + gyro_z * gyro_z).sqrt();
+
+        let sensitivity = defense.sensitivity;
+        let cooled_down = data.timestamp.saturating_sub(self.last_defense_time) >= defense.cooldown_ms;
+        let motion_type = if !cooled_down || acc_deviation >= defense.strike_min_acc {
+            None
+        } else if acc_deviation >= defense.block_min_acc / sensitivity
+            && gyro_magnitude >= defense.block_min_gyro / sensitivity {
+            Some("block")
+        } else if acc_deviation >= defense.flinch_min_acc / sensitivity
+            || gyro_magnitude >= defense.flinch_min_gyro / sensitivity {
+            Some("flinch")
+        } else {
+            None
+        };
+
+        if let Some(motion_type) = motion_type {
+            let fighter_id = format!("fighter_{}", competitor.id);
+            let magnitude = (acc_deviation / (defense.flinch_min_acc / sensitivity))
+                .max(gyro_magnitude / (defense.flinch_min_gyro / sensitivity));
+            // Atacante: el del turno, o el que acaba de cerrarlo con su golpe
+            let striker = turn.fighters.iter()
+                .find(|fighter| **fighter != fighter_id)
+                .cloned()
+                .unwrap_or_else(|| turn.striker.clone());
+
+            // Se queda el más intenso de los que esperan al mismo golpe
+            if self.pending_defense.as_ref().is_none_or(|pending| magnitude > pending.magnitude) {
+                self.pending_defense = Some(DefensiveMotionEvent {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    ring_id: self.ring_id.clone(),
+                    motion_type: motion_type.to_string(),
+                    fighter_id,
+                    competitor_name: competitor.name.clone(),
+                    limb_type: self.limb_type,
+                    limb_name: self.limb_type.name().to_string(),
+                    striker,
+                    turn_number: turn.turn_number,
+                    acc_deviation,
+                    gyro_magnitude,
+                    magnitude,
+                    timestamp: data.timestamp,
+                });
+            }
+        }
+
+        self.resolve_pending_defense(data.timestamp, defense, turn)
+    }
+
+    /// Entrega el movimiento retenido si hubo un golpe del atacante dentro de su ventana
+    fn resolve_pending_defense(
+        &mut self,
+        now: u64,
+        defense: &DefenseDetectionConfig,
+        turn: &TurnState,
+    ) -> Option<DefensiveMotionEvent> {
+        let pending = self.pending_defense.as_ref()?;
+        let near_strike = turn.last_struck.as_deref() == Some(pending.fighter_id.as_str())
+            && turn.last_strike_at.is_some_and(|strike_at| strike_at.abs_diff(pending.timestamp) <= defense.strike_window_ms);
+
+        if !near_strike {
+            if now.saturating_sub(pending.timestamp) > defense.strike_window_ms {
+                debug!(fighter_id = %pending.fighter_id, "Movimiento defensivo sin golpe cercano del atacante, descartado");
+                self.pending_defense = None;
+            }
+            return None;
+        }
+
+        let motion = self.pending_defense.take()?;
+        self.last_defense_time = motion.timestamp;
+        info!(
+            motion_type = %motion.motion_type,
+            fighter_id = %motion.fighter_id,
+            limb = %motion.limb_name,
+            acc_deviation = motion.acc_deviation,
+            gyro_magnitude = motion.gyro_magnitude,
+            magnitude = motion.magnitude,
+            "🛡️ Movimiento defensivo detectado"
+        );
+        Some(motion)
+    }
+
     pub fn detect_event(&mut self, data: &ImuData) -> Option<SimpleCombatEvent> {
         let competitor = self.competitor_info.as_ref()?;
         // let limb_type = LimbType::from_id(data.limb_id)?;
@@ -104,8 +229,12 @@ impl SimpleEventDetector {
         let fighter_id = format!("fighter_{}", competitor.id);
 
         let event = SimpleCombatEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            sequence: next_event_sequence(),
+            ring_id: self.ring_id.clone(),
             event_type: event_type.to_string(),
             limb_name: limb_type.name().to_string(),
+            limb_type,
             fighter_id,
             competitor_name: competitor.name.clone(),
             velocity: Some(velocity),
@@ -116,6 +245,7 @@ impl SimpleEventDetector {
         };
 
         info!(
+            ring_id = %event.ring_id,
             event_type = %event.event_type,
             limb = %event.limb_name,
             competitor = %event.competitor_name,
@@ -183,21 +313,13 @@ pub fn parse_imu_data(data: &[u8]) -> BleResult<ImuData> {
 }
 
 /// Función para detectar y actualizar nuevos máximos
-pub fn check_and_update_max_stats<R: tauri::Runtime>(
-    event: &SimpleCombatEvent,
-    app_handle: &AppHandle<R>,
-) {
-    let store = get_max_stats_store();
-    let mut stats_map = match store.lock() {
-        Ok(map) => map,
-        Err(e) => {
-            error!(error = %e, "Error accediendo al store de estadísticas");
-            return;
-        }
-    };
+pub fn check_and_update_max_stats(event: &SimpleCombatEvent, ble: &BleManager) {
+    let mut stats_map = ble.max_stats();
 
-    // Obtener o crear estadísticas del peleador
-    let stats = stats_map.entry(event.fighter_id.clone()).or_insert(CompetitorMaxStats {
+    // Obtener o crear estadísticas del peleador en su ring
+    let key = (event.ring_id.clone(), event.fighter_id.clone());
+    let stats = stats_map.entry(key).or_insert(CompetitorMaxStats {
+        ring_id: event.ring_id.clone(),
         fighter_id: event.fighter_id.clone(),
         competitor_name: event.competitor_name.clone(),
         max_force: 0.0,
@@ -244,32 +366,59 @@ pub fn check_and_update_max_stats<R: tauri::Runtime>(
         // 1. Enviar evento al frontend
         let record_event = serde_json::json!({
             "type": "new_max_record",
+            "ring_id": event.ring_id,
             "fighter_id": event.fighter_id,
             "records": new_records,
             "stats": stats_clone,
             "triggering_event": event
         });
 
-        if let Err(e) = app_handle.emit("new-max-record", &record_event) {
-            error!(error = %e, "Error emitiendo evento de nuevo récord");
-        }
+        ble.hub().emit_frontend("new-max-record", &record_event);
 
         // 2. Enviar por WebSocket
-        let ws_message = serde_json::json!({
-            "type": "max_stats_update",
-            "fighter_id": event.fighter_id,
-            "data": stats_clone,
-            "new_records": new_records,
-            "timestamp": event.timestamp,
-            "view_type": "stats"
+        ble.hub().broadcast(&event.ring_id, &ServerMessage::MaxStatsUpdate {
+            fighter_id: event.fighter_id.clone(),
+            data: stats_clone.clone(),
+            new_records: new_records.iter().map(|record| record.to_string()).collect(),
         });
-        ws_broadcast(&ws_message);
+
+        // 3. Notificar a los webhooks suscritos
+        notify_new_max_record(&stats_clone, &new_records);
 
         info!(fighter_id = %event.fighter_id, records = ?new_records, 
               "📡 Nuevos récords enviados por WebSocket y evento");
     }
 }
 
+/// Recalcula las estadísticas máximas de un peleador a partir de sus eventos vigentes
+pub fn rebuild_max_stats(
+    ble: &BleManager,
+    ring_id: &str,
+    fighter_id: &str,
+    competitor_name: &str,
+    events: &[SimpleCombatEvent],
+) -> BleResult<CompetitorMaxStats> {
+    let mut stats = CompetitorMaxStats {
+        ring_id: ring_id.to_string(),
+        fighter_id: fighter_id.to_string(),
+        competitor_name: competitor_name.to_string(),
+        max_force: 0.0,
+        max_velocity: 0.0,
+        max_acceleration: 0.0,
+    };
+
+    for event in events {
+        stats.max_force = stats.max_force.max(event.force.unwrap_or(0.0));
+        stats.max_velocity = stats.max_velocity.max(event.velocity.unwrap_or(0.0));
+        stats.max_acceleration = stats.max_acceleration.max(event.acceleration.unwrap_or(0.0));
+    }
+
+    ble.max_stats().insert((ring_id.to_string(), fighter_id.to_string()), stats.clone());
+
+    info!(ring_id = %ring_id, fighter_id = %fighter_id, max_force = stats.max_force, "♻️ Estadísticas máximas recalculadas");
+    Ok(stats)
+}
+
 /// Determina el tipo de extremidad
 pub fn determine_limb_type_by_pattern(device_name: &str) -> LimbType {
     [LimbType::LeftHand, LimbType::RightHand, LimbType::LeftFoot, LimbType::RightFoot]
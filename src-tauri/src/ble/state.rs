@@ -1,229 +1,390 @@
-//! Gestión de estado global del sistema BLE
+//! Estado del sistema BLE
+//!
+//! El estado de los dispositivos vive en `BleManager`, que la app guarda en
+//! `tauri::State`. Recibe el transporte BLE y el centro de difusión al
+//! construirse, así que el pipeline no depende del hardware ni de Tauri (ver
+//! `ble::testing`). Los turnos viven en el centro de difusión; el marcador,
+//! el registro del combate, el juez y el control de acceso siguen siendo
+//! almacenes globales del proceso, separados por `ring_id`.
+//!
+//! Los locks son de `std` y nunca se mantienen a través de un `.await`; si un
+//! hilo entra en pánico con uno tomado, el resto recupera el dato en vez de
+//! dejar caídos todos los comandos BLE.
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
-use bluest::{Adapter, Device};
-use once_cell::sync::Lazy;
 use tracing::{info, debug};
 
-use crate::ble::types::{CompetitorMaxStats, BleResult};
+use crate::ble::actor::{DeviceOutput, FANOUT_BUFFER, NOTIFICATION_BUFFER};
+use crate::ble::transport::BleTransport;
+use crate::bout::event_log::mark_stats_reset;
+use crate::bout::ring::default_ring_id;
+use crate::ble::types::{CompetitorInfo, CompetitorMaxStats, DefenseDetectionConfig, DeviceAssignment, DeviceHealth, BleResult};
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::broadcast_ws::protocol::ServerMessage;
 
-// ============================================================================
-// Type Aliases - Simplifica tipos complejos para evitar warnings de Clippy
-// ============================================================================
+/// Tiempo sin datos tras el cual un dispositivo se considera inactivo
+const DEVICE_STALE_MS: u64 = 5000;
 
-/// Store thread-safe de estadísticas máximas por competidor
-type MaxStatsStore = Arc<Mutex<HashMap<String, CompetitorMaxStats>>>;
+// Estado BLE compartido (clonarlo comparte el mismo estado)
+#[derive(Clone)]
+pub struct BleManager {
+    inner: Arc<BleInner>,
+}
+
+struct BleInner {
+    transport: Arc<dyn BleTransport>,
+    hub: BroadcastHub,
+    max_stats: Mutex<HashMap<(String, String), CompetitorMaxStats>>, // (ring_id, fighter_id) -> máximos
+    connected_devices: Mutex<HashMap<String, String>>,            // device_id -> device_name
+    device_tasks: Mutex<HashMap<String, JoinHandle<()>>>,         // device_id -> tarea de manejo
+    device_health: Mutex<HashMap<String, DeviceHealth>>,          // device_id -> salud
+    device_assignments: Mutex<HashMap<String, DeviceAssignment>>, // Solo se quita al desconectar a mano
+    defense_config: Mutex<DefenseDetectionConfig>,                // Sensibilidad de la detección defensiva
+    fanout_tx: mpsc::Sender<DeviceOutput>,                        // Detecciones de los actores hacia la difusión
+    fanout_rx: Mutex<Option<mpsc::Receiver<DeviceOutput>>>,       // Lo toma la tarea de difusión al arrancar
+    stalled_detections: AtomicU64,                                // Detecciones que esperaron con la difusión llena
+    shutting_down: AtomicBool,                                    // La app se está cerrando: no se lanzan más tareas
+}
 
-/// Mapa thread-safe de dispositivos conectados (device_id -> device_name)
-type ConnectedDevicesMap = Arc<Mutex<HashMap<String, String>>>;
+impl BleManager {
+    pub fn new(transport: Arc<dyn BleTransport>, hub: BroadcastHub) -> Self {
+        let (fanout_tx, fanout_rx) = mpsc::channel(FANOUT_BUFFER);
+        Self {
+            inner: Arc::new(BleInner {
+                transport,
+                hub,
+                max_stats: Mutex::new(HashMap::new()),
+                connected_devices: Mutex::new(HashMap::new()),
+                device_tasks: Mutex::new(HashMap::new()),
+                device_health: Mutex::new(HashMap::new()),
+                device_assignments: Mutex::new(HashMap::new()),
+                defense_config: Mutex::new(DefenseDetectionConfig::default()),
+                fanout_tx,
+                fanout_rx: Mutex::new(Some(fanout_rx)),
+                stalled_detections: AtomicU64::new(0),
+                shutting_down: AtomicBool::new(false),
+            }),
+        }
+    }
 
-/// Mapa thread-safe de referencias a dispositivos BLE
-type DeviceReferencesMap = Arc<Mutex<HashMap<String, Device>>>;
+    pub fn transport(&self) -> &dyn BleTransport {
+        self.inner.transport.as_ref()
+    }
 
-/// Mapa thread-safe de tareas de manejo de dispositivos
-type DeviceTasksMap = Arc<Mutex<HashMap<String, JoinHandle<()>>>>;
+    pub fn hub(&self) -> &BroadcastHub {
+        &self.inner.hub
+    }
 
-/// Adaptador BLE singleton thread-safe
-type BleAdapterSingleton = Arc<Mutex<Option<Adapter>>>;
+    /// Canal por el que los actores entregan sus detecciones
+    pub(crate) fn fanout_sender(&self) -> mpsc::Sender<DeviceOutput> {
+        self.inner.fanout_tx.clone()
+    }
 
-// ============================================================================
-// Estado Global
-// ============================================================================
+    /// Receptor de detecciones; solo la primera tarea de difusión lo obtiene
+    pub(crate) fn take_fanout_receiver(&self) -> Option<mpsc::Receiver<DeviceOutput>> {
+        self.inner.fanout_rx.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
 
-// Store global para estadísticas máximas (usando fighter_id como clave)
-static MAX_STATS_STORE: Lazy<MaxStatsStore> = 
-    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+    /// Cuenta una detección que tuvo que esperar porque la difusión iba llena y devuelve el total
+    pub(crate) fn record_stalled_detection(&self) -> u64 {
+        self.inner.stalled_detections.fetch_add(1, Ordering::Relaxed) + 1
+    }
 
-// Estado global de dispositivos conectados (device_id -> device_name)
-static CONNECTED_DEVICES: OnceLock<ConnectedDevicesMap> = OnceLock::new();
+    /// Estadísticas máximas por competidor (usando ring y fighter_id como clave)
+    pub fn max_stats(&self) -> MutexGuard<'_, HashMap<(String, String), CompetitorMaxStats>> {
+        self.inner.max_stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
 
-// Referencias a dispositivos BLE (device_id -> Device)
-static DEVICE_REFERENCES: OnceLock<DeviceReferencesMap> = OnceLock::new();
+    /// Estadísticas máximas de los competidores de un ring
+    pub fn ring_max_stats(&self, ring_id: &str) -> Vec<CompetitorMaxStats> {
+        self.max_stats().values()
+            .filter(|stats| stats.ring_id == ring_id)
+            .cloned()
+            .collect()
+    }
 
-// Tareas de manejo de dispositivos (device_id -> JoinHandle)
-static DEVICE_TASKS: OnceLock<DeviceTasksMap> = OnceLock::new();
+    /// Olvida las estadísticas máximas de un ring, también las que se recalcularían del registro
+    pub fn reset_ring_max_stats(&self, ring_id: &str) {
+        self.max_stats().retain(|(ring, _), _| ring != ring_id);
+        mark_stats_reset(ring_id);
 
-// Adaptador BLE singleton
-static BLE_ADAPTER: OnceLock<BleAdapterSingleton> = OnceLock::new();
+        // Notificar reset por WebSocket
+        self.hub().broadcast(ring_id, &ServerMessage::MaxStatsReset);
+        info!(ring_id = %ring_id, "🔄 Estadísticas máximas reseteadas");
+    }
 
-/// Función para obtener el store de estadísticas máximas
-pub fn get_max_stats_store() -> MaxStatsStore {
-    MAX_STATS_STORE.clone()
-}
+    /// Identificadores de los dispositivos conectados
+    pub fn connected_device_ids(&self) -> Vec<String> {
+        self.connected_devices().keys().cloned().collect()
+    }
 
-/// Función para obtener el estado de dispositivos conectados
-pub fn get_connected_devices_state() -> ConnectedDevicesMap {
-    CONNECTED_DEVICES.get_or_init(|| Arc::new(Mutex::new(HashMap::new()))).clone()
-}
+    /// Dispositivos conectados o con tarea de manejo, aunque aún no hayan registrado la conexión
+    pub fn active_device_ids(&self) -> Vec<String> {
+        let mut device_ids: HashSet<String> = self.device_tasks().keys().cloned().collect();
+        device_ids.extend(self.connected_devices().keys().cloned());
+        device_ids.into_iter().collect()
+    }
 
-/// Función para obtener las referencias de dispositivos BLE
-pub fn get_device_references_state() -> DeviceReferencesMap {
-    DEVICE_REFERENCES.get_or_init(|| Arc::new(Mutex::new(HashMap::new()))).clone()
-}
+    /// Marca el cierre de la app: las conexiones que terminen después ya no lanzan su tarea
+    pub fn begin_shutdown(&self) {
+        self.inner.shutting_down.store(true, Ordering::SeqCst);
+    }
 
-/// Función para obtener las tareas de dispositivos
-pub fn get_device_tasks_state() -> DeviceTasksMap {
-    DEVICE_TASKS.get_or_init(|| Arc::new(Mutex::new(HashMap::new()))).clone()
-}
+    pub fn is_shutting_down(&self) -> bool {
+        self.inner.shutting_down.load(Ordering::SeqCst)
+    }
+
+    fn connected_devices(&self) -> MutexGuard<'_, HashMap<String, String>> {
+        self.inner.connected_devices.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn device_health(&self) -> MutexGuard<'_, HashMap<String, DeviceHealth>> {
+        self.inner.device_health.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn device_tasks(&self) -> MutexGuard<'_, HashMap<String, JoinHandle<()>>> {
+        self.inner.device_tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn device_assignments_map(&self) -> MutexGuard<'_, HashMap<String, DeviceAssignment>> {
+        self.inner.device_assignments.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Guarda la tarea de manejo de un dispositivo, cancelando la de una conexión anterior
+    ///
+    /// Si la app ya se está cerrando, la tarea se cancela en lugar de guardarse.
+    pub fn insert_device_task(&self, device_id: &str, task: JoinHandle<()>) {
+        if self.is_shutting_down() {
+            task.abort();
+            self.unregister_connected_device(device_id);
+            debug!(device_id = %device_id, "🧹 Conexión terminada durante el cierre, tarea cancelada");
+            return;
+        }
 
-/// Función para obtener el adaptador BLE singleton
-pub async fn get_ble_adapter() -> BleResult<Adapter> {
-    let adapter_lock = BLE_ADAPTER.get_or_init(|| Arc::new(Mutex::new(None)));
-    
-    // Verificar si ya tenemos un adaptador válido
-    {
-        let adapter_guard = adapter_lock.lock().unwrap();
-        if let Some(ref adapter) = *adapter_guard {
-            debug!("♻️ Reutilizando adaptador BLE existente");
-            return Ok(adapter.clone());
+        let previous = self.device_tasks().insert(device_id.to_string(), task);
+        if let Some(previous) = previous {
+            previous.abort();
+            debug!(device_id = %device_id, "🧹 Tarea anterior del dispositivo cancelada");
         }
     }
-    
-    // Crear nuevo adaptador si no existe
-    debug!("🔧 Creando nuevo adaptador BLE");
-    let adapter = Adapter::default().await
-        .ok_or_else(|| "Error obteniendo adaptador BLE".to_string())?;
-    
-    // Almacenar en el singleton
-    {
-        let mut adapter_guard = adapter_lock.lock().unwrap();
-        *adapter_guard = Some(adapter.clone());
-    }
-    
-    info!("✅ Adaptador BLE inicializado correctamente");
-    Ok(adapter)
-}
 
-/// Registra un dispositivo como conectado con información del competidor
-pub fn register_connected_device(device_id: &str, device_name: &str, competitor_name: &str) {
-    let connected_devices = get_connected_devices_state();
-    let mut devices = connected_devices.lock().unwrap();
-    devices.insert(device_id.to_string(), device_name.to_string());
-    
-    info!(
-        device_id = %device_id,
-        device_name = %device_name,
-        competitor = %competitor_name,
-        "📱 Dispositivo registrado como conectado"
-    );
-}
+    /// Recuerda a qué competidor se asignó un dispositivo
+    pub fn assign_device_to_competitor(&self, device_id: &str, ring_id: &str, competitor: &CompetitorInfo) {
+        self.device_assignments_map().insert(device_id.to_string(), DeviceAssignment {
+            device_id: device_id.to_string(),
+            ring_id: ring_id.to_string(),
+            competitor: competitor.clone(),
+        });
+    }
 
-/// Registra un dispositivo sin información de competidor
-pub fn register_device_without_competitor(device_id: &str, device_name: &str) {
-    let connected_devices = get_connected_devices_state();
-    let mut devices = connected_devices.lock().unwrap();
-    devices.insert(device_id.to_string(), device_name.to_string());
-    
-    info!(
-        device_id = %device_id,
-        device_name = %device_name,
-        "📱 Dispositivo registrado sin competidor"
-    );
-}
+    /// Olvida la asignación de un dispositivo desconectado por el operador
+    pub fn release_device_assignment(&self, device_id: &str) {
+        self.device_assignments_map().remove(device_id);
+    }
+
+    /// Copia de las asignaciones actuales, ordenadas por dispositivo
+    pub fn device_assignments(&self) -> Vec<DeviceAssignment> {
+        let mut result: Vec<DeviceAssignment> = self.device_assignments_map().values().cloned().collect();
+        result.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        result
+    }
+
+    /// Configuración actual de la detección de movimientos defensivos
+    pub fn defense_detection_config(&self) -> DefenseDetectionConfig {
+        self.inner.defense_config.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Cambia la configuración de la detección de movimientos defensivos
+    pub fn set_defense_detection_config(&self, config: DefenseDetectionConfig) -> BleResult<()> {
+        config.validate()?;
+        *self.inner.defense_config.lock().unwrap_or_else(|e| e.into_inner()) = config;
+        Ok(())
+    }
+
+    /// Registra un dispositivo como conectado con información del competidor
+    pub fn register_connected_device(&self, device_id: &str, device_name: &str, ring_id: &str, competitor_name: &str) {
+        self.connected_devices().insert(device_id.to_string(), device_name.to_string());
+        self.reset_device_health(device_id, device_name, ring_id);
 
-/// Desregistra un dispositivo conectado
-pub fn unregister_connected_device(device_id: &str) {
-    let connected_devices = get_connected_devices_state();
-    let mut devices = connected_devices.lock().unwrap();
-    
-    if let Some(device_name) = devices.remove(device_id) {
         info!(
             device_id = %device_id,
             device_name = %device_name,
-            "📱 Dispositivo desregistrado"
+            ring_id = %ring_id,
+            competitor = %competitor_name,
+            "📱 Dispositivo registrado como conectado"
         );
     }
-}
 
-/// Cancela y limpia la tarea de un dispositivo específico
-pub async fn cleanup_device_task(device_id: &str) {
-    let device_tasks = get_device_tasks_state();
-    let mut tasks = device_tasks.lock().unwrap();
-    
-    if let Some(task) = tasks.remove(device_id) {
-        task.abort();
-        info!(device_id = %device_id, "🧹 Tarea de dispositivo cancelada");
+    /// Registra un dispositivo sin información de competidor
+    pub fn register_device_without_competitor(&self, device_id: &str, device_name: &str, ring_id: &str) {
+        self.connected_devices().insert(device_id.to_string(), device_name.to_string());
+        self.reset_device_health(device_id, device_name, ring_id);
+
+        info!(
+            device_id = %device_id,
+            device_name = %device_name,
+            ring_id = %ring_id,
+            "📱 Dispositivo registrado sin competidor"
+        );
     }
-}
 
-/// Cancela todas las tareas de dispositivos
-pub async fn cleanup_all_device_tasks() {
-    let device_tasks = get_device_tasks_state();
-    let mut tasks = device_tasks.lock().unwrap();
-    
-    let task_count = tasks.len();
-    for (device_id, task) in tasks.drain() {
-        task.abort();
-        debug!(device_id = %device_id, "🧹 Tarea cancelada");
+    /// Desregistra un dispositivo conectado
+    pub fn unregister_connected_device(&self, device_id: &str) {
+        let health = self.device_health().remove(device_id);
+        if let Some(mut health) = health {
+            health.online = false;
+            self.hub().broadcast(&health.ring_id.clone(), &ServerMessage::DeviceStatus { connected: false, data: health });
+        }
+
+        let device_name = self.connected_devices().remove(device_id);
+        if let Some(device_name) = device_name {
+            info!(
+                device_id = %device_id,
+                device_name = %device_name,
+                "📱 Dispositivo desregistrado"
+            );
+        }
+    }
+
+    /// Inicia el registro de salud de un dispositivo recién conectado
+    fn reset_device_health(&self, device_id: &str, device_name: &str, ring_id: &str) {
+        let entry = DeviceHealth {
+            device_id: device_id.to_string(),
+            device_name: device_name.to_string(),
+            ring_id: ring_id.to_string(),
+            connected_at: now_millis(),
+            last_seen: None,
+            battery_level: None,
+            packets_received: 0,
+            online: false,
+            queue_depth: 0,
+            max_queue_depth: 0,
+        };
+
+        self.device_health().insert(device_id.to_string(), entry.clone());
+        self.hub().broadcast(ring_id, &ServerMessage::DeviceStatus { connected: true, data: entry });
     }
-    
-    if task_count > 0 {
-        info!(task_count = task_count, "🧹 Todas las tareas de dispositivos canceladas");
+
+    /// Registra un paquete IMU recibido y la cola de su actor; devuelve la salud si hay que anunciarla
+    pub fn record_device_packet(&self, device_id: &str, battery_level: u8, queue_depth: usize) -> Option<DeviceHealth> {
+        let mut health = self.device_health();
+        let entry = health.get_mut(device_id)?;
+
+        // Solo se anuncia el primer paquete y los cambios de batería
+        let changed = entry.battery_level != Some(battery_level);
+        entry.last_seen = Some(now_millis());
+        entry.battery_level = Some(battery_level);
+        entry.packets_received += 1;
+        entry.online = true;
+        entry.queue_depth = queue_depth;
+        entry.max_queue_depth = entry.max_queue_depth.max(queue_depth);
+        changed.then(|| entry.clone())
     }
-}
 
-/// Limpia completamente el estado del sistema BLE
-pub async fn cleanup_ble_system() {
-    // Cancelar todas las tareas
-    cleanup_all_device_tasks().await;
-    
-    // Limpiar dispositivos conectados
-    {
-        let connected_devices = get_connected_devices_state();
-        let mut devices = connected_devices.lock().unwrap();
-        devices.clear();
-    }
-    
-    // Limpiar referencias de dispositivos
-    {
-        let device_references = get_device_references_state();
-        let mut references = device_references.lock().unwrap();
-        references.clear();
-    }
-    
-    // Limpiar estadísticas máximas
-    {
-        let max_stats = get_max_stats_store();
-        let mut stats = max_stats.lock().unwrap();
-        stats.clear();
-    }
-    
-    // Reiniciar el adaptador BLE para asegurar que esté en estado limpio
-    {
-        let adapter_lock = BLE_ADAPTER.get_or_init(|| Arc::new(Mutex::new(None)));
-        let mut adapter_guard = adapter_lock.lock().unwrap();
-        *adapter_guard = None; // Forzar reinicialización del adaptador
-    }
-    
-    info!("🧹 Sistema BLE completamente limpiado");
+    /// Salud de los dispositivos conectados, con la actividad calculada al momento
+    pub fn connected_devices_health(&self) -> Vec<DeviceHealth> {
+        let devices = self.connected_devices();
+        let health = self.device_health();
+        let now = now_millis();
+
+        let mut result: Vec<DeviceHealth> = devices.iter()
+            .map(|(device_id, device_name)| {
+                let mut entry = health.get(device_id).cloned().unwrap_or_else(|| DeviceHealth {
+                    device_id: device_id.clone(),
+                    device_name: device_name.clone(),
+                    ring_id: default_ring_id(),
+                    connected_at: now,
+                    last_seen: None,
+                    battery_level: None,
+                    packets_received: 0,
+                    online: false,
+                    queue_depth: 0,
+                    max_queue_depth: 0,
+                });
+                entry.online = entry.last_seen
+                    .is_some_and(|last_seen| now.saturating_sub(last_seen) <= DEVICE_STALE_MS);
+                entry
+            })
+            .collect();
+        result.sort_by(|a, b| a.device_name.cmp(&b.device_name));
+        result
+    }
+
+    /// Cancela la tarea de un dispositivo específico y espera a que suelte la conexión
+    pub async fn cleanup_device_task(&self, device_id: &str) {
+        let task = self.device_tasks().remove(device_id);
+
+        if let Some(task) = task {
+            task.abort();
+            let _ = task.await;
+            info!(device_id = %device_id, "🧹 Tarea de dispositivo cancelada");
+        }
+    }
+
+    /// Cancela todas las tareas de dispositivos
+    pub async fn cleanup_all_device_tasks(&self) {
+        let tasks: Vec<(String, JoinHandle<()>)> = self.device_tasks().drain().collect();
+
+        let task_count = tasks.len();
+        for (device_id, task) in tasks {
+            task.abort();
+            let _ = task.await;
+            debug!(device_id = %device_id, "🧹 Tarea cancelada");
+        }
+
+        if task_count > 0 {
+            info!(task_count = task_count, "🧹 Todas las tareas de dispositivos canceladas");
+        }
+    }
+
+    /// Limpia completamente el estado del sistema BLE
+    pub async fn cleanup_ble_system(&self) {
+        // Cancelar todas las tareas
+        self.cleanup_all_device_tasks().await;
+
+        // Limpiar dispositivos conectados
+        self.connected_devices().clear();
+        self.device_health().clear();
+        self.device_assignments_map().clear();
+
+        // Limpiar estadísticas máximas, también las que se recalcularían del registro
+        let rings: HashSet<String> = self.max_stats().drain().map(|((ring_id, _), _)| ring_id).collect();
+        rings.iter().for_each(|ring_id| mark_stats_reset(ring_id));
+
+        // Olvidar referencias y reiniciar el adaptador para asegurar un estado limpio
+        self.transport().reset().await;
+
+        info!("🧹 Sistema BLE completamente limpiado");
+    }
+
+    /// Obtiene información del estado actual del sistema BLE
+    pub fn system_status(&self) -> serde_json::Value {
+        let connected_count = self.connected_devices().len();
+        let tasks_count = self.device_tasks().len();
+        let stats_count = self.max_stats().len();
+        let queued_notifications: usize = self.device_health().values().map(|health| health.queue_depth).sum();
+
+        serde_json::json!({
+            "connected_devices": connected_count,
+            "active_tasks": tasks_count,
+            "tracked_competitors": stats_count,
+            "queued_notifications": queued_notifications,
+            "notification_buffer": NOTIFICATION_BUFFER,
+            "pending_detections": FANOUT_BUFFER - self.inner.fanout_tx.capacity(),
+            "stalled_detections": self.inner.stalled_detections.load(Ordering::Relaxed),
+            "broadcast_clients": self.hub().clients().status(),
+            "system_initialized": true
+        })
+    }
 }
 
-/// Obtiene información del estado actual del sistema BLE
-pub fn get_system_status() -> serde_json::Value {
-    let connected_count = {
-        let connected_devices = get_connected_devices_state();
-        let devices = connected_devices.lock().unwrap();
-        devices.len()
-    };
-    
-    let tasks_count = {
-        let device_tasks = get_device_tasks_state();
-        let tasks = device_tasks.lock().unwrap();
-        tasks.len()
-    };
-    
-    let stats_count = {
-        let max_stats = get_max_stats_store();
-        let stats = max_stats.lock().unwrap();
-        stats.len()
-    };
-    
-    serde_json::json!({
-        "connected_devices": connected_count,
-        "active_tasks": tasks_count,
-        "tracked_competitors": stats_count,
-        "system_initialized": true
-    })
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
 }
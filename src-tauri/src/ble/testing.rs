@@ -0,0 +1,122 @@
+//! Dobles de prueba para el pipeline BLE
+//!
+//! `MockTransport` entrega paquetes guionizados en lugar de hablar con el
+//! hardware y `RecordingEmitter` guarda lo que se emitiría al frontend, de
+//! modo que un test puede montar `BleManager` y `BroadcastHub` completos.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures::future::BoxFuture;
+use tokio::sync::mpsc;
+
+use crate::ble::state::BleManager;
+use crate::ble::types::{BleResult, DiscoveredDevice};
+use crate::ble::transport::BleTransport;
+use crate::broadcast_ws::hub::{BroadcastHub, FrontendEmitter};
+
+/// Transporte simulado: cada dispositivo tiene un nombre y una lista de paquetes
+#[derive(Default)]
+pub struct MockTransport {
+    devices: Mutex<HashMap<String, MockDevice>>, // device_id -> dispositivo simulado
+}
+
+#[derive(Clone, Default)]
+struct MockDevice {
+    name: String,
+    packets: Vec<Vec<u8>>,
+}
+
+impl MockTransport {
+    /// Añade un dispositivo que enviará `packets` en orden al conectarse
+    pub fn with_device(self, device_id: &str, name: &str, packets: Vec<Vec<u8>>) -> Self {
+        self.devices.lock().unwrap_or_else(|e| e.into_inner())
+            .insert(device_id.to_string(), MockDevice { name: name.to_string(), packets });
+        self
+    }
+
+    fn device(&self, device_id: &str) -> BleResult<MockDevice> {
+        self.devices.lock().unwrap_or_else(|e| e.into_inner())
+            .get(device_id)
+            .cloned()
+            .ok_or_else(|| format!("Dispositivo simulado no encontrado: {}", device_id))
+    }
+}
+
+impl BleTransport for MockTransport {
+    fn scan(&self, _timeout: Duration, max_devices: usize) -> BoxFuture<'_, BleResult<Vec<DiscoveredDevice>>> {
+        Box::pin(async move {
+            let devices = self.devices.lock().unwrap_or_else(|e| e.into_inner());
+            Ok(devices.iter()
+                .take(max_devices)
+                .map(|(id, device)| DiscoveredDevice { id: id.clone(), name: device.name.clone(), rssi: None })
+                .collect())
+        })
+    }
+
+    fn find_device<'a>(&'a self, device_id: &'a str) -> BoxFuture<'a, BleResult<String>> {
+        Box::pin(async move { self.device(device_id).map(|device| device.name) })
+    }
+
+    fn stream_notifications<'a>(
+        &'a self,
+        device_id: &'a str,
+        packets: mpsc::Sender<Vec<u8>>,
+    ) -> BoxFuture<'a, BleResult<()>> {
+        Box::pin(async move {
+            // Al terminar el guion se suelta el emisor, como si el sensor se desconectara
+            for packet in self.device(device_id)?.packets {
+                packets.send(packet).await.map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn disconnect<'a>(&'a self, _device_id: &'a str) -> BoxFuture<'a, BleResult<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn reset(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// Emisor que guarda los eventos del frontend en vez de enviarlos
+#[derive(Default)]
+pub struct RecordingEmitter {
+    events: Mutex<Vec<(String, serde_json::Value)>>,
+}
+
+impl RecordingEmitter {
+    /// Cargas emitidas con un nombre de evento, en orden
+    pub fn events(&self, event: &str) -> Vec<serde_json::Value> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(name, _)| name == event)
+            .map(|(_, payload)| payload.clone())
+            .collect()
+    }
+}
+
+impl FrontendEmitter for RecordingEmitter {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).push((event.to_string(), payload));
+        Ok(())
+    }
+}
+
+/// Monta un `BleManager` sobre el transporte simulado y un emisor que graba
+pub fn mock_ble(transport: MockTransport) -> (BleManager, Arc<RecordingEmitter>) {
+    let emitter = Arc::new(RecordingEmitter::default());
+    let hub = BroadcastHub::new(emitter.clone(), "static");
+    (BleManager::new(Arc::new(transport), hub), emitter)
+}
+
+/// Paquete IMU con el formato del firmware: extremidad, batería, acelerómetro y giroscopio en little endian
+pub fn imu_packet(battery_level: u8, acc: [i16; 3], gyro: [i16; 3]) -> Vec<u8> {
+    let mut packet = vec![0, battery_level];
+    for value in acc.iter().chain(gyro.iter()) {
+        packet.extend_from_slice(&value.to_le_bytes());
+    }
+    packet
+}
@@ -0,0 +1,300 @@
+//! Transporte BLE: el único punto que habla con el hardware
+//!
+//! `BleManager` solo conoce el trait `BleTransport`, así que el pipeline de
+//! detección puede alimentarse con un transporte simulado (tests, demos sin
+//! sensores). `BluestTransport` es la implementación real sobre bluest.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+use bluest::{Adapter, Characteristic, Device};
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use tokio::sync::mpsc;
+use tracing::{info, debug, error, instrument};
+
+use crate::ble::types::{BleResult, DiscoveredDevice};
+
+/// Tiempo máximo buscando un dispositivo concreto
+const FIND_DEVICE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Acceso al hardware BLE
+pub trait BleTransport: Send + Sync {
+    /// Escanea dispositivos BH durante `timeout` o hasta encontrar `max_devices`
+    fn scan(&self, timeout: Duration, max_devices: usize) -> BoxFuture<'_, BleResult<Vec<DiscoveredDevice>>>;
+
+    /// Localiza un dispositivo y devuelve su nombre anunciado
+    fn find_device<'a>(&'a self, device_id: &'a str) -> BoxFuture<'a, BleResult<String>>;
+
+    /// Conecta y reenvía cada notificación hasta que se cierra la conexión
+    fn stream_notifications<'a>(
+        &'a self,
+        device_id: &'a str,
+        packets: mpsc::Sender<Vec<u8>>,
+    ) -> BoxFuture<'a, BleResult<()>>;
+
+    /// Cierra la conexión a nivel de enlace para que el sensor vuelva a anunciarse
+    fn disconnect<'a>(&'a self, device_id: &'a str) -> BoxFuture<'a, BleResult<()>>;
+
+    /// Olvida adaptador y dispositivos para empezar desde cero
+    fn reset(&self) -> BoxFuture<'_, ()>;
+}
+
+/// Transporte real sobre bluest
+#[derive(Default)]
+pub struct BluestTransport {
+    adapter: Mutex<Option<Adapter>>,
+    devices: Mutex<HashMap<String, Device>>, // Referencias para reconexión rápida
+}
+
+impl BluestTransport {
+    /// Adaptador BLE compartido, creado la primera vez que se necesita
+    async fn adapter(&self) -> BleResult<Adapter> {
+        // Verificar si ya tenemos un adaptador válido
+        if let Some(adapter) = self.adapter.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+            debug!("♻️ Reutilizando adaptador BLE existente");
+            return Ok(adapter);
+        }
+
+        // Crear nuevo adaptador si no existe
+        debug!("🔧 Creando nuevo adaptador BLE");
+        let adapter = Adapter::default().await
+            .ok_or_else(|| "Error obteniendo adaptador BLE".to_string())?;
+        *self.adapter.lock().unwrap_or_else(|e| e.into_inner()) = Some(adapter.clone());
+
+        info!("✅ Adaptador BLE inicializado correctamente");
+        Ok(adapter)
+    }
+
+    /// Adaptador listo para escanear o conectar
+    async fn available_adapter(&self) -> BleResult<Adapter> {
+        let adapter = self.adapter().await?;
+        adapter.wait_available().await
+            .map_err(|e| format!("Error esperando adaptador: {}", e))?;
+        Ok(adapter)
+    }
+
+    /// Busca un dispositivo BLE por su ID, primero en la caché de referencias
+    async fn lookup_device(&self, device_id: &str) -> BleResult<(Device, String)> {
+        debug!(device_id = %device_id, "🔍 Buscando dispositivo BLE");
+
+        let cached_device = self.devices.lock().unwrap_or_else(|e| e.into_inner()).get(device_id).cloned();
+        if let Some(cached_device) = cached_device {
+            // Extraer el nombre del device_id (formato: "ManoDerecha_54c2e6cf")
+            let device_name = device_id.split('_').next().unwrap_or("Unknown");
+            info!(device_id = %device_id, "♻️ Usando dispositivo en caché (reconexión rápida)");
+            return Ok((cached_device, format!("BH-{}", device_name)));
+        }
+
+        // Si no está en caché, hacer escaneo completo
+        debug!(device_id = %device_id, "📡 Dispositivo no en caché, iniciando escaneo...");
+        let adapter = self.available_adapter().await?;
+        let mut scan = adapter.scan(&[]).await
+            .map_err(|e| format!("Error iniciando escaneo: {}", e))?;
+
+        let scan_timeout = tokio::time::sleep(FIND_DEVICE_TIMEOUT);
+        tokio::pin!(scan_timeout);
+
+        loop {
+            tokio::select! {
+                _ = &mut scan_timeout => {
+                    return Err(format!("Dispositivo {} no encontrado en 10 segundos", device_id));
+                }
+                discovered = scan.next() => {
+                    let Some(discovered_device) = discovered else { break };
+                    let device = discovered_device.device;
+
+                    if let Some(local_name) = &discovered_device.adv_data.local_name {
+                        if local_name.contains("BH-") && device.id().to_string() == device_id {
+                            info!(device_id = %device_id, device_name = %local_name, "✅ Dispositivo encontrado");
+                            self.devices.lock().unwrap_or_else(|e| e.into_inner()).insert(device_id.to_string(), device.clone());
+                            return Ok((device, local_name.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(format!("Dispositivo {} no encontrado", device_id))
+    }
+
+    async fn scan_devices(&self, timeout: Duration, max_devices: usize) -> BleResult<Vec<DiscoveredDevice>> {
+        let adapter = self.available_adapter().await?;
+        let mut scan = adapter.scan(&[]).await
+            .map_err(|e| format!("Error iniciando escaneo: {}", e))?;
+
+        let mut devices = Vec::with_capacity(max_devices);
+        let mut seen_devices = HashSet::with_capacity(max_devices);
+
+        let scan_timeout = tokio::time::sleep(timeout);
+        tokio::pin!(scan_timeout);
+
+        loop {
+            tokio::select! {
+                _ = &mut scan_timeout => {
+                    info!(found_devices = devices.len(), "⏰ Escaneo BLE completado por timeout");
+                    break;
+                }
+                discovered = scan.next() => {
+                    let Some(discovered_device) = discovered else { break };
+
+                    // Verificar primero si es un dispositivo BH
+                    let local_name = match &discovered_device.adv_data.local_name {
+                        Some(name) if name.contains("BH-") => name.clone(),
+                        _ => continue,
+                    };
+
+                    let device = discovered_device.device;
+                    let device_id = device.id().to_string();
+
+                    // Evitar duplicados usando HashSet
+                    if !seen_devices.insert(device_id.clone()) {
+                        continue;
+                    }
+
+                    devices.push(DiscoveredDevice {
+                        id: device_id,
+                        name: local_name,
+                        rssi: discovered_device.rssi,
+                    });
+
+                    if devices.len() >= max_devices {
+                        info!(max_devices, "🔍 Límite de dispositivos alcanzado, terminando escaneo");
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(devices)
+    }
+
+    async fn forward_notifications(&self, device_id: &str, packets: mpsc::Sender<Vec<u8>>) -> BleResult<()> {
+        let (device, _) = self.lookup_device(device_id).await?;
+
+        // 1. Establecer conexión BLE
+        establish_ble_connection(&self.adapter().await?, &device).await?;
+        // 2. Descubrir servicios y características
+        let notification_char = discover_notification_characteristic(&device).await?;
+
+        // 3. Suscribirse a notificaciones
+        info!(device_id = %device_id, "📡 Suscribiéndose a notificaciones BLE");
+        let mut notification_stream = notification_char.notify().await
+            .map_err(|e| {
+                error!(device_id = %device_id, error = %e, "❌ Error en suscripción BLE");
+                format!("Error suscribiendo a notificaciones de {}: {}", device_id, e)
+            })?;
+        info!(device_id = %device_id, "🔔 Notificaciones BLE configuradas");
+
+        // 4. Reenviar notificaciones hasta que se corte el stream o nadie escuche
+        while let Some(notification_result) = notification_stream.next().await {
+            match notification_result {
+                Ok(data_bytes) => {
+                    if packets.send(data_bytes).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!(device_id = %device_id, error = %e, "❌ Error en stream de notificaciones");
+                    break;
+                }
+            }
+        }
+
+        info!(device_id = %device_id, "📡 Stream de notificaciones terminado");
+        Ok(())
+    }
+
+    async fn disconnect_device(&self, device_id: &str) -> BleResult<()> {
+        // Sin adaptador o sin referencia no hay conexión que cerrar
+        let adapter = self.adapter.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let device = self.devices.lock().unwrap_or_else(|e| e.into_inner()).get(device_id).cloned();
+        let (Some(adapter), Some(device)) = (adapter, device) else {
+            debug!(device_id = %device_id, "Dispositivo sin conexión BLE que cerrar");
+            return Ok(());
+        };
+
+        adapter.disconnect_device(&device).await
+            .map_err(|e| format!("Error desconectando {}: {}", device_id, e))?;
+
+        info!(device_id = %device_id, "🔌 Conexión BLE cerrada");
+        Ok(())
+    }
+}
+
+impl BleTransport for BluestTransport {
+    fn scan(&self, timeout: Duration, max_devices: usize) -> BoxFuture<'_, BleResult<Vec<DiscoveredDevice>>> {
+        Box::pin(self.scan_devices(timeout, max_devices))
+    }
+
+    fn find_device<'a>(&'a self, device_id: &'a str) -> BoxFuture<'a, BleResult<String>> {
+        Box::pin(async move {
+            let (_, device_name) = self.lookup_device(device_id).await?;
+            Ok(device_name)
+        })
+    }
+
+    fn stream_notifications<'a>(
+        &'a self,
+        device_id: &'a str,
+        packets: mpsc::Sender<Vec<u8>>,
+    ) -> BoxFuture<'a, BleResult<()>> {
+        Box::pin(self.forward_notifications(device_id, packets))
+    }
+
+    fn disconnect<'a>(&'a self, device_id: &'a str) -> BoxFuture<'a, BleResult<()>> {
+        Box::pin(self.disconnect_device(device_id))
+    }
+
+    fn reset(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.devices.lock().unwrap_or_else(|e| e.into_inner()).clear();
+            // Forzar reinicialización del adaptador
+            *self.adapter.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        })
+    }
+}
+
+/// Establece la conexión BLE con el dispositivo
+#[instrument(skip(adapter, device))]
+async fn establish_ble_connection(adapter: &Adapter, device: &Device) -> BleResult<()> {
+    debug!("Iniciando conexión BLE");
+
+    adapter.connect_device(device).await
+        .map_err(|e| format!("Error conectando: {}", e))?;
+
+    info!("Conexión BLE establecida exitosamente");
+
+    // Esperar un momento para que se establezca la conexión GATT
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    Ok(())
+}
+
+/// Descubre y retorna la característica de notificación
+#[instrument(skip(device))]
+async fn discover_notification_characteristic(device: &Device) -> BleResult<Characteristic> {
+    // Obtener servicios directamente del dispositivo
+    let services = device.services().await
+        .map_err(|e| format!("Error obteniendo servicios: {}", e))?;
+
+    debug!(services_count = services.len(), "Servicios BLE descubiertos");
+
+    // Buscar característica con notificaciones
+    for service in &services {
+        let characteristics = service.characteristics().await
+            .map_err(|e| format!("Error obteniendo características: {}", e))?;
+
+        for characteristic in characteristics {
+            if let Ok(props) = characteristic.properties().await {
+                if props.notify {
+                    info!(uuid = %characteristic.uuid(), "Característica de notificación encontrada");
+                    return Ok(characteristic);
+                }
+            }
+        }
+    }
+
+    let error_msg = "No se encontró característica con notificaciones".to_string();
+    error!("{}", error_msg);
+    Err(error_msg)
+}
@@ -1,6 +1,8 @@
 //! Tipos y estructuras para el sistema BLE
 
-use serde::{Serialize};
+use serde::{Deserialize, Serialize};
+
+use crate::bout::ring::default_ring_id;
 
 // Estructura para representar un dispositivo BLE encontrado
 #[derive(Clone, Serialize, Debug)]
@@ -14,11 +16,24 @@ pub struct BleDevice {
     pub is_connectable: bool,
 }
 
+// Dispositivo BH visto durante un escaneo del transporte
+#[derive(Clone, Debug)]
+pub struct DiscoveredDevice {
+    pub id: String,
+    pub name: String,
+    pub rssi: Option<i16>,
+}
+
 // Estructura simple para eventos de combate
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimpleCombatEvent {
+    pub id: String,                // Identificador único del evento (UUID)
+    pub sequence: u64,             // Número de secuencia monotónico
+    #[serde(default = "default_ring_id")]
+    pub ring_id: String,           // Ring donde se produjo
     pub event_type: String,        // "slap", "kick"
     pub limb_name: String,         // "Mano Izquierda", "Pie Derecho", etc.
+    pub limb_type: LimbType,       // Extremidad que generó el evento
     pub fighter_id: String,        // ID del peleador (ej: "fighter_1", "fighter_2")
     pub competitor_name: String,   // Nombre del competidor
     pub velocity: Option<f32>,     // Velocidad en m/s
@@ -29,8 +44,10 @@ pub struct SimpleCombatEvent {
 }
 
 // Estructura para estadísticas máximas por competidor
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompetitorMaxStats {
+    #[serde(default = "default_ring_id")]
+    pub ring_id: String,
     pub fighter_id: String,        // "fighter_1", "fighter_2", etc.
     pub competitor_name: String,   // Nombre del peleador
     pub max_force: f32,
@@ -38,6 +55,21 @@ pub struct CompetitorMaxStats {
     pub max_acceleration: f32,
 }
 
+// Salud de un dispositivo conectado (batería y actividad)
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceHealth {
+    pub device_id: String,
+    pub device_name: String,
+    pub ring_id: String,
+    pub connected_at: u64,
+    pub last_seen: Option<u64>,      // Último paquete IMU recibido
+    pub battery_level: Option<u8>,   // Último nivel de batería reportado (%)
+    pub packets_received: u64,
+    pub online: bool,                // Recibió datos recientemente
+    pub queue_depth: usize,          // Notificaciones esperando en el actor del dispositivo
+    pub max_queue_depth: usize,      // Máxima espera registrada desde la conexión
+}
+
 // Datos básicos del sensor IMU
 #[derive(Debug, Clone)]
 pub struct ImuData {
@@ -53,7 +85,7 @@ pub struct ImuData {
 }
 
 // Tipos de extremidades
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LimbType {
     LeftHand,
     RightHand,
@@ -147,13 +179,96 @@ impl Default for SimpleDetectionConfig {
     }
 }
 
+// Sensibilidad de la detección de bloqueos y encogimientos del defensor.
+// Los umbrales se dividen por `sensitivity` (mayor = más sensible). Por
+// defecto no bajan de los del detector de golpes para no confundir un golpe
+// con una reacción.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefenseDetectionConfig {
+    pub enabled: bool,
+    pub sensitivity: f32,            // 0.25 - 4.0
+    pub flinch_min_acc: f32,         // g de desviación sobre la gravedad para un encogimiento
+    pub flinch_min_gyro: f32,        // °/s para un encogimiento
+    pub block_min_acc: f32,          // g de desviación para levantar la mano a cubrirse
+    pub block_min_gyro: f32,         // °/s para levantar la mano a cubrirse
+    pub strike_min_acc: f32,         // A partir de aquí se trata como golpe fuera de turno
+    pub cooldown_ms: u64,
+    pub strike_window_ms: u64,       // Margen antes y después de un golpe del atacante en que cuenta la reacción
+}
+
+impl Default for DefenseDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sensitivity: 1.0,
+            flinch_min_acc: 0.15,
+            flinch_min_gyro: 3.0,
+            block_min_acc: 0.35,
+            block_min_gyro: 5.0,
+            strike_min_acc: 0.8,
+            cooldown_ms: 500,
+            strike_window_ms: 500,
+        }
+    }
+}
+
+impl DefenseDetectionConfig {
+    /// Verifica que los umbrales sean coherentes
+    pub fn validate(&self) -> BleResult<()> {
+        if !(0.25..=4.0).contains(&self.sensitivity) {
+            return Err(format!("Sensibilidad fuera de rango: {} (0.25 - 4.0)", self.sensitivity));
+        }
+        if self.flinch_min_acc <= 0.0 || self.flinch_min_gyro <= 0.0 {
+            return Err("Los umbrales de encogimiento deben ser mayores que 0".to_string());
+        }
+        if self.block_min_acc < self.flinch_min_acc || self.block_min_gyro < self.flinch_min_gyro {
+            return Err("Los umbrales de bloqueo no pueden ser menores que los de encogimiento".to_string());
+        }
+        if self.strike_min_acc <= self.block_min_acc {
+            return Err("El umbral de golpe debe superar al de bloqueo".to_string());
+        }
+        if self.strike_window_ms == 0 {
+            return Err("La ventana alrededor del golpe debe ser mayor que 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Movimiento defensivo detectado en las manos del defensor
+#[derive(Debug, Clone, Serialize)]
+pub struct DefensiveMotionEvent {
+    pub id: String,
+    pub ring_id: String,
+    pub motion_type: String,         // "flinch" o "block"
+    pub fighter_id: String,          // Defensor que se movió
+    pub competitor_name: String,
+    pub limb_type: LimbType,
+    pub limb_name: String,
+    pub striker: String,             // Atacante del turno
+    pub turn_number: u32,
+    pub acc_deviation: f32,          // g sobre la gravedad
+    pub gyro_magnitude: f32,         // °/s
+    pub magnitude: f32,              // Intensidad relativa al umbral de encogimiento (1.0 = umbral)
+    pub timestamp: u64,
+}
+
 // Información del competidor
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompetitorInfo {
     pub id: u8,
     pub name: String,
     pub weight: f32, // kg
 }
 
+// Dispositivo asignado a un competidor (se conserva aunque el dispositivo se caiga)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceAssignment {
+    pub device_id: String,
+    #[serde(default = "default_ring_id")]
+    pub ring_id: String,
+    pub competitor: CompetitorInfo,
+}
+
 // Tipo de resultado para operaciones BLE
 pub type BleResult<T> = Result<T, String>;
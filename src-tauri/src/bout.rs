@@ -0,0 +1,10 @@
+//! Módulo de combate - Rings, registro de eventos, turnos, recuperación y estado compartido de los combates en curso
+
+pub mod types;
+pub mod ring;
+pub mod state;
+pub mod event_log;
+pub mod turns;
+pub mod recovery;
+#[cfg(feature = "desktop")]
+pub mod commands;
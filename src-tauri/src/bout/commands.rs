@@ -0,0 +1,117 @@
+//! Comandos Tauri para el registro de eventos, los turnos y la recuperación del combate
+
+use tauri::State;
+use tracing::info;
+
+use crate::ble::state::BleManager;
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::bout::ring::ring_or_default;
+use crate::bout::types::{BoutRecoveryResult, BoutRecoverySummary, CombatLogEntry, TurnState};
+use crate::bout::recovery::{discard_recoverable_bout, recoverable_bout_summary, restore_recoverable_bout};
+use crate::bout::turns::{current_turn, pass_turn, start_turn_mode_with_defaults, stop_turn_mode};
+use crate::bout::event_log::{clear_combat_event_log, recent_combat_events, set_combat_event_voided};
+
+// Autor de las anulaciones hechas desde la app de operador
+const OPERATOR_ISSUER: &str = "operador";
+
+/// Obtiene el registro de eventos del combate de un ring
+#[tauri::command]
+pub fn get_event_log(
+    ring_id: Option<String>,
+    fighter_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<CombatLogEntry>, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    Ok(recent_combat_events(&ring_id, fighter_id.as_deref(), limit.unwrap_or(usize::MAX)))
+}
+
+/// Anula un evento detectado de un ring por su id
+#[tauri::command]
+pub fn void_combat_event(ble: State<'_, BleManager>, ring_id: Option<String>, event_id: String) -> Result<CombatLogEntry, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    info!(ring_id = %ring_id, event_id = %event_id, "↩️ Comando: Anular evento");
+    set_combat_event_voided(&ble, &ring_id, &event_id, true, OPERATOR_ISSUER)
+}
+
+/// Restaura un evento previamente anulado de un ring
+#[tauri::command]
+pub fn restore_combat_event(ble: State<'_, BleManager>, ring_id: Option<String>, event_id: String) -> Result<CombatLogEntry, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    info!(ring_id = %ring_id, event_id = %event_id, "↪️ Comando: Restaurar evento");
+    set_combat_event_voided(&ble, &ring_id, &event_id, false, OPERATOR_ISSUER)
+}
+
+/// Vacía el registro de eventos de un ring para empezar un nuevo combate
+#[tauri::command]
+pub fn clear_event_log(ring_id: Option<String>) -> Result<String, String> {
+    clear_combat_event_log(&ring_or_default(ring_id)?);
+    Ok("Registro de eventos vaciado exitosamente".to_string())
+}
+
+/// Activa el modo por turnos. `time_limit_ms` = 0 desactiva el límite de tiempo
+#[tauri::command]
+pub fn start_turn_based_mode(
+    hub: State<'_, BroadcastHub>,
+    ring_id: Option<String>,
+    first_striker: String,
+    fighters: Option<[String; 2]>,
+    strikes_per_turn: Option<u32>,
+    time_limit_ms: Option<u64>,
+) -> Result<TurnState, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    info!(ring_id = %ring_id, first_striker = %first_striker, "🔁 Comando: Activar modo por turnos");
+    start_turn_mode_with_defaults(&hub, &ring_id, &first_striker, fighters, strikes_per_turn, time_limit_ms)
+}
+
+/// Vuelve al combate libre (cualquier peleador puntúa)
+#[tauri::command]
+pub fn stop_turn_based_mode(hub: State<'_, BroadcastHub>, ring_id: Option<String>) -> Result<String, String> {
+    if stop_turn_mode(&hub, &ring_or_default(ring_id)?) {
+        Ok("Modo por turnos desactivado".to_string())
+    } else {
+        Ok("El modo por turnos no estaba activo".to_string())
+    }
+}
+
+/// Pasa el turno al otro peleador
+#[tauri::command]
+pub fn pass_combat_turn(hub: State<'_, BroadcastHub>, ring_id: Option<String>) -> Result<TurnState, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    info!(ring_id = %ring_id, "🔁 Comando: Pasar turno");
+    pass_turn(&hub, &ring_id)
+}
+
+/// Obtiene el turno actual de un ring (None si el combate no es por turnos)
+#[tauri::command]
+pub fn get_combat_turn(hub: State<'_, BroadcastHub>, ring_id: Option<String>) -> Result<Option<TurnState>, String> {
+    Ok(current_turn(&hub, &ring_or_default(ring_id)?))
+}
+
+/// Rings con feed abierto; el ring por defecto siempre aparece
+#[tauri::command]
+pub fn get_rings(hub: State<'_, BroadcastHub>) -> Result<Vec<String>, String> {
+    Ok(hub.ring_ids())
+}
+
+/// Obtiene el combate que quedó sin terminar tras un cierre inesperado (None si no hay)
+#[tauri::command]
+pub fn get_recoverable_bout() -> Result<Option<BoutRecoverySummary>, String> {
+    Ok(recoverable_bout_summary())
+}
+
+/// Restaura el combate sin terminar y vuelve a conectar sus dispositivos
+#[tauri::command]
+pub async fn restore_bout(ble: State<'_, BleManager>) -> Result<BoutRecoveryResult, String> {
+    info!("💾 Comando: Restaurar combate");
+    restore_recoverable_bout(&ble).await
+}
+
+/// Descarta el combate sin terminar
+#[tauri::command]
+pub fn discard_recoverable_bout_command() -> Result<String, String> {
+    if discard_recoverable_bout() {
+        Ok("Combate guardado descartado".to_string())
+    } else {
+        Ok("No había ningún combate pendiente de restaurar".to_string())
+    }
+}
@@ -0,0 +1,177 @@
+//! Registro de eventos del combate con anulación y restauración
+//!
+//! Cada ring lleva su propio registro, que se vacía al terminar el combate.
+
+use tracing::{info, warn, error};
+
+use crate::ble::types::SimpleCombatEvent;
+use crate::ble::detection::rebuild_max_stats;
+use crate::bout::types::{BoutResult, CombatLogEntry};
+use crate::ble::state::BleManager;
+use crate::bout::state::get_combat_event_log_state;
+use crate::bout::recovery::mark_bout_in_progress;
+use crate::judge::state::clear_judge_action_log;
+use crate::scoring::state::clear_score_ledger;
+use crate::scoring::engine::{emit_score_update, set_score_entry_voided};
+use crate::broadcast_ws::protocol::ServerMessage;
+
+/// Añade un evento detectado al registro del combate
+pub fn append_combat_event(event: &SimpleCombatEvent) {
+    let log = get_combat_event_log_state();
+    let mut log = match log.lock() {
+        Ok(log) => log,
+        Err(e) => {
+            error!(error = %e, "Error accediendo al registro de eventos");
+            return;
+        }
+    };
+
+    log.entry(event.ring_id.clone()).or_default().push(CombatLogEntry {
+        event: event.clone(),
+        voided: false,
+        voided_by: None,
+        before_stats_reset: false,
+    });
+    drop(log);
+    mark_bout_in_progress(&event.ring_id);
+}
+
+/// Busca un evento del registro de un ring por su id
+pub fn find_combat_event(ring_id: &str, event_id: &str) -> Option<CombatLogEntry> {
+    let log = get_combat_event_log_state();
+    let log = log.lock().ok()?;
+    log.get(ring_id)?.iter().find(|entry| entry.event.id == event_id).cloned()
+}
+
+/// Obtiene las últimas entradas del registro de un ring, opcionalmente filtradas por peleador
+pub fn recent_combat_events(ring_id: &str, fighter_id: Option<&str>, limit: usize) -> Vec<CombatLogEntry> {
+    let log = get_combat_event_log_state();
+    let Ok(log) = log.lock() else {
+        return Vec::new();
+    };
+
+    let Some(log) = log.get(ring_id) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<CombatLogEntry> = log.iter()
+        .rev()
+        .filter(|entry| fighter_id.is_none_or(|id| entry.event.fighter_id == id))
+        .take(limit)
+        .cloned()
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// Vacía el registro de eventos del combate de un ring
+pub fn clear_combat_event_log(ring_id: &str) {
+    let log = get_combat_event_log_state();
+    if let Ok(mut log) = log.lock() {
+        log.remove(ring_id);
+    }
+    info!(ring_id = %ring_id, "🧹 Registro de eventos del combate vaciado");
+}
+
+/// Vacía los registros del combate de un ring al terminarlo: eventos, puntuación y acciones de jueces.
+/// El marcador final se conserva para la pantalla de resultados.
+pub fn clear_bout_logs(ring_id: &str) {
+    clear_combat_event_log(ring_id);
+    clear_score_ledger(ring_id);
+    clear_judge_action_log(ring_id);
+}
+
+/// Marca los eventos de un ring como anteriores a un reset de estadísticas máximas
+///
+/// Así anular o restaurar un evento más tarde no recupera los máximos reseteados.
+pub fn mark_stats_reset(ring_id: &str) {
+    let log = get_combat_event_log_state();
+    let mut log = log.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(log) = log.get_mut(ring_id) {
+        log.iter_mut().for_each(|entry| entry.before_stats_reset = true);
+    }
+}
+
+/// Anula o restaura un evento de un ring, recalcula estadísticas y marcador y notifica el cambio
+pub fn set_combat_event_voided(
+    ble: &BleManager,
+    ring_id: &str,
+    event_id: &str,
+    voided: bool,
+    issued_by: &str,
+) -> BoutResult<CombatLogEntry> {
+    let (entry, fighter_events) = {
+        let log = get_combat_event_log_state();
+        let mut log = log.lock()
+            .map_err(|e| format!("Error accediendo al registro de eventos: {}", e))?;
+
+        let log = log.get_mut(ring_id)
+            .ok_or_else(|| format!("No existe el evento {} en el ring {}", event_id, ring_id))?;
+        let entry = log.iter_mut()
+            .find(|entry| entry.event.id == event_id)
+            .ok_or_else(|| format!("No existe el evento {} en el ring {}", event_id, ring_id))?;
+
+        if entry.voided == voided {
+            let state = if voided { "anulado" } else { "vigente" };
+            return Err(format!("El evento {} ya está {}", event_id, state));
+        }
+        entry.voided = voided;
+        entry.voided_by = voided.then(|| issued_by.to_string());
+        let entry = entry.clone();
+
+        // Eventos vigentes del peleador desde el último reset para recalcular máximos
+        let fighter_events: Vec<SimpleCombatEvent> = log.iter()
+            .filter(|other| !other.voided
+                && !other.before_stats_reset
+                && other.event.fighter_id == entry.event.fighter_id)
+            .map(|other| other.event.clone())
+            .collect();
+
+        (entry, fighter_events)
+    };
+
+    let fighter_id = &entry.event.fighter_id;
+    let stats = rebuild_max_stats(ble, ring_id, fighter_id, &entry.event.competitor_name, &fighter_events)?;
+
+    // El golpe puede no estar en el marcador si éste se reseteó después
+    let score = match set_score_entry_voided(ring_id, event_id, voided) {
+        Ok((score_entry, score)) => Some((score_entry, score)),
+        Err(e) => {
+            warn!(event_id = %event_id, error = %e, "Evento sin entrada en el marcador");
+            None
+        }
+    };
+
+    let message = if voided {
+        ServerMessage::EventVoided {
+            event_id: event_id.to_string(),
+            fighter_id: fighter_id.clone(),
+            data: entry.clone(),
+            issued_by: issued_by.to_string(),
+        }
+    } else {
+        ServerMessage::EventRestored {
+            event_id: event_id.to_string(),
+            fighter_id: fighter_id.clone(),
+            data: entry.clone(),
+            issued_by: issued_by.to_string(),
+        }
+    };
+    let hub = ble.hub();
+    hub.broadcast(ring_id, &message);
+
+    hub.broadcast(ring_id, &ServerMessage::MaxStatsUpdate {
+        fighter_id: fighter_id.clone(),
+        data: stats,
+        new_records: Vec::new(),
+    });
+
+    hub.emit_frontend("combat-event-voided", &message);
+    if let Some((score_entry, score)) = &score {
+        emit_score_update(score, Some(score_entry), hub);
+    }
+
+    info!(event_id = %event_id, ring_id = %ring_id, fighter_id = %fighter_id, voided = voided, issued_by = %issued_by,
+          "↩️ Estado de evento actualizado");
+    Ok(entry)
+}
@@ -0,0 +1,388 @@
+//! Recuperación del combate tras un cierre inesperado
+//!
+//! Mientras hay un combate en curso se guarda en disco el estado completo
+//! (asignaciones de dispositivos, estadísticas, registro de eventos, marcador y
+//! reglamento, round y turno de cada ring) tras cada cambio difundido y cada pocos
+//! segundos. El archivo se conserva mientras quede algún ring en combate. Al
+//! arrancar, si quedó un combate sin terminar, se ofrece restaurarlo: se
+//! recupera el estado, se vuelven a conectar los mismos dispositivos a los
+//! mismos competidores y se reenvía el estado a las pantallas.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::{Lazy, OnceCell};
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn, error};
+
+use crate::ble::connection::connect_to_device_with_competitor;
+use crate::ble::state::BleManager;
+use crate::bout::types::{
+    BoutRecoveryResult, BoutRecoverySnapshot, BoutRecoverySummary, BoutResult, CombatLogEntry, RingRecovery,
+    RingRecoverySummary,
+};
+use crate::bout::state::{current_event_sequence, get_combat_event_log_state, restore_event_sequence};
+use crate::bout::turns::{current_turn, resume_turn_mode};
+use crate::scoring::types::ScoreEntry;
+use crate::scoring::state::{get_active_rulesets_state, get_score_ledger_state, get_score_tallies_state, ring_ruleset, set_active_ruleset};
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::broadcast_ws::snapshot::build_state_snapshot;
+use crate::broadcast_ws::protocol::ServerMessage;
+use crate::webhooks::resume_bout_progress;
+
+/// Archivo del combate en curso dentro del directorio de datos
+const RECOVERY_FILE_NAME: &str = "bout_recovery.json";
+
+/// Espera tras un cambio para agrupar ráfagas de mensajes en una sola escritura
+const RECOVERY_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Intervalo de guardado aunque no haya cambios
+const RECOVERY_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Ruta del archivo de recuperación (se fija durante el setup)
+static RECOVERY_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+// Rings con un combate en curso que guardar (se marcan al asignar dispositivos o detectar golpes)
+static BOUT_IN_PROGRESS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+// Combate encontrado al arrancar, a la espera de que el operador decida
+static PENDING_RECOVERY: Lazy<Mutex<Option<BoutRecoverySnapshot>>> = Lazy::new(|| Mutex::new(None));
+
+/// Hay algún ring con un combate en curso
+fn any_bout_in_progress() -> bool {
+    BOUT_IN_PROGRESS.lock()
+        .map(|rings| !rings.is_empty())
+        .unwrap_or(false)
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Fija el directorio de datos donde guardar el combate en curso
+pub fn init_recovery_dir(data_dir: PathBuf) {
+    let _ = RECOVERY_PATH.set(data_dir.join(RECOVERY_FILE_NAME));
+}
+
+/// Carga el combate que quedó sin terminar y avisa al frontend de que puede restaurarse
+pub fn load_recoverable_bout(hub: &BroadcastHub) {
+    let Some(path) = RECOVERY_PATH.get() else {
+        return;
+    };
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "No se pudo leer el combate guardado");
+            return;
+        }
+    };
+    let snapshot: BoutRecoverySnapshot = match serde_json::from_str(&content) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Combate guardado inválido, se descarta");
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+    };
+
+    let summary = summarize(&snapshot);
+    info!(
+        saved_at = summary.saved_at,
+        devices = summary.devices,
+        events = summary.events,
+        "💾 Combate sin terminar encontrado, se puede restaurar"
+    );
+    if let Ok(mut pending) = PENDING_RECOVERY.lock() {
+        *pending = Some(snapshot);
+    }
+    hub.emit_frontend("bout-recovery-available", &summary);
+}
+
+/// Resumen del combate pendiente de restaurar, si lo hay
+pub fn recoverable_bout_summary() -> Option<BoutRecoverySummary> {
+    PENDING_RECOVERY.lock().ok()?.as_ref().map(summarize)
+}
+
+fn summarize(snapshot: &BoutRecoverySnapshot) -> BoutRecoverySummary {
+    let mut competitors: Vec<String> = snapshot.assignments.iter()
+        .map(|assignment| assignment.competitor.name.clone())
+        .collect();
+    competitors.sort();
+    competitors.dedup();
+
+    BoutRecoverySummary {
+        saved_at: snapshot.saved_at,
+        competitors,
+        devices: snapshot.assignments.len(),
+        events: snapshot.event_log.len(),
+        rings: snapshot.rings.iter()
+            .map(|ring| RingRecoverySummary {
+                ring_id: ring.ring_id.clone(),
+                current_round: ring.battle_config.as_ref().map(|config| config.current_round),
+                rounds: ring.battle_config.as_ref().map(|config| config.rounds),
+                view_type: ring.active_view.as_ref().map(|view| view.view_type.clone()),
+            })
+            .collect(),
+    }
+}
+
+/// Marca que hay un combate en curso en un ring
+///
+/// El combate pendiente de restaurar se conserva hasta que el operador lo
+/// restaure o lo descarte, aunque entretanto empiece otro.
+pub fn mark_bout_in_progress(ring_id: &str) {
+    let mut rings = BOUT_IN_PROGRESS.lock().unwrap_or_else(|e| e.into_inner());
+    rings.insert(ring_id.to_string());
+}
+
+/// El combate de un ring terminó con normalidad; sin más rings en combate no queda nada que recuperar
+pub fn finish_bout_recovery(ring_id: &str) {
+    let remaining = {
+        let Ok(mut rings) = BOUT_IN_PROGRESS.lock() else {
+            return;
+        };
+        if !rings.remove(ring_id) {
+            return;
+        }
+        rings.len()
+    };
+    if remaining > 0 {
+        info!(ring_id = %ring_id, remaining, "💾 Combate terminado, siguen otros rings en curso");
+        return;
+    }
+    remove_recovery_file();
+    info!(ring_id = %ring_id, "💾 Combate terminado, estado de recuperación eliminado");
+}
+
+/// Descarta el combate pendiente de restaurar; devuelve `false` si no había ninguno
+pub fn discard_recoverable_bout() -> bool {
+    let discarded = PENDING_RECOVERY.lock()
+        .map(|mut pending| pending.take().is_some())
+        .unwrap_or(false);
+    if discarded && !any_bout_in_progress() {
+        remove_recovery_file();
+    }
+    if discarded {
+        info!("💾 Combate pendiente de restaurar descartado");
+    }
+    discarded
+}
+
+fn remove_recovery_file() {
+    let Some(path) = RECOVERY_PATH.get() else {
+        return;
+    };
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!(path = %path.display(), error = %e, "No se pudo eliminar el combate guardado");
+        }
+    }
+}
+
+/// Reúne el estado actual del combate de todos los rings
+fn capture_bout_state(ble: &BleManager) -> BoutRecoverySnapshot {
+    // Un ring puede tener reglamento propio sin haber difundido nada todavía
+    let mut ring_ids: BTreeSet<String> = ble.hub().ring_ids().into_iter().collect();
+    ring_ids.extend(get_active_rulesets_state().lock()
+        .map(|rulesets| rulesets.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default());
+
+    let rings = ring_ids.into_iter()
+        .map(|ring_id| {
+            let feed = ble.hub().ring(&ring_id);
+            RingRecovery {
+                battle_config: feed.views().last_battle_config(),
+                active_view: feed.views().active_view(),
+                turn: current_turn(ble.hub(), &ring_id),
+                ruleset: ring_ruleset(&ring_id),
+                ring_id,
+            }
+        })
+        .filter(|ring| ring.battle_config.is_some() || ring.active_view.is_some() || ring.turn.is_some() || ring.ruleset.is_some())
+        .collect();
+
+    BoutRecoverySnapshot {
+        saved_at: now_ms(),
+        assignments: ble.device_assignments(),
+        max_stats: ble.max_stats().values().cloned().collect(),
+        scores: get_score_tallies_state().lock()
+            .map(|scores| scores.values().cloned().collect())
+            .unwrap_or_default(),
+        score_ledger: get_score_ledger_state().lock()
+            .map(|ledger| ledger.values().flatten().cloned().collect())
+            .unwrap_or_default(),
+        event_log: get_combat_event_log_state().lock()
+            .map(|log| log.values().flatten().cloned().collect())
+            .unwrap_or_default(),
+        event_sequence: current_event_sequence(),
+        rings,
+    }
+}
+
+/// Guarda el estado actual del combate (escritura atómica: archivo temporal y renombrado)
+pub fn save_recovery_snapshot(ble: &BleManager) -> Result<(), String> {
+    let path = RECOVERY_PATH.get()
+        .ok_or_else(|| "Directorio de datos no inicializado".to_string())?;
+    let content = serde_json::to_vec(&capture_bout_state(ble)).map_err(|e| e.to_string())?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creando {}: {}", parent.display(), e))?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Error guardando {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Error guardando {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+/// Escribe el último estado del combate en curso sin esperar al guardado periódico
+pub fn flush_recovery_snapshot(ble: &BleManager) {
+    if !any_bout_in_progress() {
+        return;
+    }
+    match save_recovery_snapshot(ble) {
+        Ok(()) => info!("💾 Estado del combate guardado antes de salir"),
+        Err(e) => error!(error = %e, "No se pudo guardar el estado del combate"),
+    }
+}
+
+/// Guarda el combate en curso tras cada cambio difundido en cualquier ring y cada pocos segundos
+pub async fn run_recovery_writer(ble: BleManager) {
+    let mut activity = ble.hub().activity();
+    let mut interval = tokio::time::interval(RECOVERY_SAVE_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            changed = activity.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                tokio::time::sleep(RECOVERY_SAVE_DEBOUNCE).await;
+                // Los mensajes llegados durante la espera quedan cubiertos por esta escritura
+                activity.borrow_and_update();
+            }
+            _ = interval.tick() => {}
+        }
+
+        if any_bout_in_progress() {
+            if let Err(e) = save_recovery_snapshot(&ble) {
+                error!(error = %e, "No se pudo guardar el estado del combate");
+            }
+        }
+    }
+}
+
+/// Restaura el combate pendiente y vuelve a conectar sus dispositivos
+pub async fn restore_recoverable_bout(ble: &BleManager) -> BoutResult<BoutRecoveryResult> {
+    let snapshot = PENDING_RECOVERY.lock()
+        .map_err(|e| format!("Error accediendo al combate guardado: {}", e))?
+        .take()
+        .ok_or_else(|| "No hay ningún combate pendiente de restaurar".to_string())?;
+
+    // Rings con algo que restaurar: los de las asignaciones y los que guardaron vista o turno
+    let ring_ids: BTreeSet<String> = snapshot.assignments.iter()
+        .map(|assignment| assignment.ring_id.clone())
+        .chain(snapshot.rings.iter().map(|ring| ring.ring_id.clone()))
+        .chain(snapshot.event_log.iter().map(|entry| entry.event.ring_id.clone()))
+        .collect();
+    BOUT_IN_PROGRESS.lock()
+        .map_err(|e| format!("Error accediendo al combate en curso: {}", e))?
+        .extend(ring_ids.iter().cloned());
+
+    restore_bout_state(ble, &snapshot)?;
+    for ring_id in &ring_ids {
+        ble.hub().broadcast(ring_id, &ServerMessage::Snapshot { data: Box::new(build_state_snapshot(ble, ring_id)) });
+    }
+    info!(events = snapshot.event_log.len(), rings = ring_ids.len(), "💾 Estado del combate restaurado");
+
+    // Las asignaciones se conservan aunque falle la reconexión, para reintentarla a mano
+    let mut reconnected_devices = Vec::new();
+    let mut failed_devices = BTreeMap::new();
+    for assignment in &snapshot.assignments {
+        ble.assign_device_to_competitor(&assignment.device_id, &assignment.ring_id, &assignment.competitor);
+        let competitor = &assignment.competitor;
+        match connect_to_device_with_competitor(
+            ble,
+            &assignment.ring_id,
+            assignment.device_id.clone(),
+            competitor.id,
+            competitor.name.clone(),
+            competitor.weight,
+        ).await {
+            Ok(()) => reconnected_devices.push(assignment.device_id.clone()),
+            Err(e) => {
+                warn!(device_id = %assignment.device_id, error = %e, "No se pudo reconectar el dispositivo");
+                failed_devices.insert(assignment.device_id.clone(), e);
+            }
+        }
+    }
+
+    if let Err(e) = save_recovery_snapshot(ble) {
+        error!(error = %e, "No se pudo guardar el estado del combate");
+    }
+
+    let result = BoutRecoveryResult {
+        restored_events: snapshot.event_log.len(),
+        reconnected_devices,
+        failed_devices,
+    };
+    ble.hub().emit_frontend("bout-recovered", &result);
+    Ok(result)
+}
+
+/// Vuelca el estado guardado en el estado global del combate
+fn restore_bout_state(ble: &BleManager, snapshot: &BoutRecoverySnapshot) -> BoutResult<()> {
+    let mut event_log: HashMap<String, Vec<CombatLogEntry>> = HashMap::new();
+    for entry in &snapshot.event_log {
+        event_log.entry(entry.event.ring_id.clone()).or_default().push(entry.clone());
+    }
+    *get_combat_event_log_state().lock()
+        .map_err(|e| format!("Error accediendo al registro de eventos: {}", e))? = event_log;
+    let last_sequence = snapshot.event_log.iter()
+        .map(|entry| entry.event.sequence)
+        .max()
+        .unwrap_or(0);
+    restore_event_sequence(snapshot.event_sequence.max(last_sequence));
+
+    *ble.max_stats() = snapshot.max_stats.iter()
+        .map(|stats| ((stats.ring_id.clone(), stats.fighter_id.clone()), stats.clone()))
+        .collect();
+    *get_score_tallies_state().lock()
+        .map_err(|e| format!("Error accediendo al marcador: {}", e))? = snapshot.scores.iter()
+        .map(|score| ((score.ring_id.clone(), score.fighter_id.clone()), score.clone()))
+        .collect();
+    let mut score_ledger: HashMap<String, Vec<ScoreEntry>> = HashMap::new();
+    for entry in &snapshot.score_ledger {
+        score_ledger.entry(entry.ring_id.clone()).or_default().push(entry.clone());
+    }
+    *get_score_ledger_state().lock()
+        .map_err(|e| format!("Error accediendo al registro de puntuación: {}", e))? = score_ledger;
+    for ring in &snapshot.rings {
+        let feed = ble.hub().ring(&ring.ring_id);
+        if let Some(config) = &ring.battle_config {
+            feed.views().remember_battle_config(config);
+            resume_bout_progress(&ring.ring_id, config.current_round.checked_sub(1).filter(|round| *round > 0));
+        } else {
+            resume_bout_progress(&ring.ring_id, None);
+        }
+        // Los golpes siguientes se puntúan con el mismo reglamento que los ya registrados
+        if let Some(ruleset) = &ring.ruleset {
+            set_active_ruleset(&ring.ring_id, ruleset.clone());
+        }
+        if let Some(view) = &ring.active_view {
+            feed.views().remember_active_view(&view.view_type, &view.data);
+        }
+        if let Some(turn) = &ring.turn {
+            resume_turn_mode(ble.hub(), turn.clone())?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+//! Identificadores de ring para llevar varios combates a la vez
+//!
+//! Cada ring tiene sus propios dispositivos, estadísticas, marcador, turnos y
+//! feed de transmisión. Lo que no indica ring va al ring por defecto, así que
+//! una instalación de un solo ring funciona igual que antes.
+
+use crate::bout::types::BoutResult;
+
+/// Ring usado cuando no se indica ninguno
+pub const DEFAULT_RING_ID: &str = "main";
+
+/// Longitud máxima de un identificador de ring
+const MAX_RING_ID_LEN: usize = 32;
+
+pub fn default_ring_id() -> String {
+    DEFAULT_RING_ID.to_string()
+}
+
+/// Ring indicado por un comando, o el ring por defecto
+pub fn ring_or_default(ring_id: Option<String>) -> BoutResult<String> {
+    match ring_id {
+        Some(ring_id) => {
+            validate_ring_id(&ring_id)?;
+            Ok(ring_id)
+        }
+        None => Ok(default_ring_id()),
+    }
+}
+
+/// Comprueba que el identificador sea apto para rutas y temas (letras, dígitos, `-` y `_`)
+pub fn validate_ring_id(ring_id: &str) -> BoutResult<()> {
+    let valid = !ring_id.is_empty()
+        && ring_id.len() <= MAX_RING_ID_LEN
+        && ring_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Identificador de ring inválido: '{}' (letras, dígitos, '-' o '_', máximo {})",
+            ring_id, MAX_RING_ID_LEN
+        ))
+    }
+}
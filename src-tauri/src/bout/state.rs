@@ -0,0 +1,38 @@
+//! Gestión de estado global del combate en curso
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+
+use crate::bout::types::CombatLogEntry;
+
+/// Registro thread-safe de eventos del combate por ring (ring_id -> eventos en orden)
+type CombatEventLog = Arc<Mutex<HashMap<String, Vec<CombatLogEntry>>>>;
+
+// Registro de eventos del combate en curso de cada ring, en orden de detección
+static COMBAT_EVENT_LOG: Lazy<CombatEventLog> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+// Secuencia monotónica de eventos detectados (no se reinicia entre combates)
+static EVENT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Función para obtener el registro de eventos del combate
+pub fn get_combat_event_log_state() -> CombatEventLog {
+    COMBAT_EVENT_LOG.clone()
+}
+
+/// Devuelve el siguiente número de secuencia de evento
+pub fn next_event_sequence() -> u64 {
+    EVENT_SEQUENCE.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+/// Último número de secuencia entregado
+pub fn current_event_sequence() -> u64 {
+    EVENT_SEQUENCE.load(Ordering::SeqCst)
+}
+
+/// Continúa la secuencia tras una recuperación sin repetir números ya usados
+pub fn restore_event_sequence(sequence: u64) {
+    EVENT_SEQUENCE.fetch_max(sequence, Ordering::SeqCst);
+}
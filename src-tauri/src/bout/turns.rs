@@ -0,0 +1,349 @@
+//! Modo por turnos: un atacante y un defensor que se alternan
+//!
+//! Solo cuentan los golpes del atacante del turno. Tras sus golpes válidos, o
+//! al agotarse el tiempo del turno, el turno pasa al otro peleador. Los golpes
+//! del defensor se marcan como falta y se difunden sin puntuar; sus bloqueos
+//! y encogimientos se difunden como evidencia para los oficiales. Cada ring
+//! lleva su propio turno. Los turnos y su temporizador pertenecen al
+//! `BroadcastHub`, de modo que cada hub lleva los suyos.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use once_cell::sync::OnceCell;
+use tokio::sync::{watch, Notify};
+use tracing::{info, warn};
+
+use crate::ble::types::{DefensiveMotionEvent, SimpleCombatEvent};
+use crate::bout::types::{BoutResult, TurnEndReason, TurnFoul, TurnState};
+use crate::broadcast_ws::hub::{BroadcastHub, WeakBroadcastHub};
+use crate::broadcast_ws::protocol::ServerMessage;
+
+/// Tiempo por turno por defecto
+pub const DEFAULT_TURN_TIME_LIMIT_MS: u64 = 30_000;
+
+// Turnos compartidos entre el hub y su temporizador
+#[derive(Default)]
+struct TurnShared {
+    turns: Mutex<HashMap<String, TurnState>>, // ring_id -> turno; sin entrada = combate libre
+    changed: Notify,                 // Despierta al temporizador cuando cambia el turno
+}
+
+// Turnos en curso de los rings de un hub
+pub struct TurnTracker {
+    shared: Arc<TurnShared>,
+    timer: OnceCell<()>,             // El temporizador se lanza con el primer turno
+    stop: watch::Sender<()>,         // Al descartarse termina el temporizador
+}
+
+impl Default for TurnTracker {
+    fn default() -> Self {
+        Self {
+            shared: Arc::default(),
+            timer: OnceCell::new(),
+            stop: watch::channel(()).0,
+        }
+    }
+}
+
+impl TurnTracker {
+    fn turns(&self) -> std::sync::MutexGuard<'_, HashMap<String, TurnState>> {
+        self.shared.turns.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn notify_changed(&self) {
+        self.shared.changed.notify_one();
+    }
+}
+
+// Resultado de comprobar un golpe contra el turno actual
+pub enum StrikeCheck {
+    Free,                            // Sin modo por turnos: cuenta como siempre
+    Valid(Option<TurnState>),        // Cuenta; Some si además pasó el turno
+    Foul(TurnFoul),                  // Fuera de turno: no cuenta
+}
+
+/// Copia del turno actual de un ring, si el modo por turnos está activo
+pub fn current_turn(hub: &BroadcastHub, ring_id: &str) -> Option<TurnState> {
+    hub.turns().turns().get(ring_id).cloned()
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Activa el modo por turnos empezando por `first_striker`
+pub fn start_turn_mode(
+    hub: &BroadcastHub,
+    ring_id: &str,
+    fighters: [String; 2],
+    first_striker: &str,
+    strikes_per_turn: u32,
+    time_limit_ms: Option<u64>,
+) -> BoutResult<TurnState> {
+    if fighters[0] == fighters[1] {
+        return Err("Los dos peleadores deben ser distintos".to_string());
+    }
+    if !fighters.iter().any(|fighter| fighter == first_striker) {
+        return Err(format!("'{}' no es uno de los peleadores del combate", first_striker));
+    }
+    if strikes_per_turn == 0 {
+        return Err("Debe haber al menos un golpe por turno".to_string());
+    }
+    if time_limit_ms == Some(0) {
+        return Err("El tiempo por turno debe ser mayor que 0".to_string());
+    }
+
+    let defender = fighters.iter()
+        .find(|fighter| *fighter != first_striker)
+        .cloned()
+        .unwrap_or_default();
+    let now = now_ms();
+    let state = TurnState {
+        ring_id: ring_id.to_string(),
+        fighters,
+        striker: first_striker.to_string(),
+        defender,
+        opening_striker: first_striker.to_string(),
+        turn_number: 1,
+        strikes_in_turn: 0,
+        strikes_per_turn,
+        time_limit_ms,
+        turn_started_at: now,
+        turn_deadline: time_limit_ms.map(|limit| now + limit),
+        last_strike_at: None,
+        last_struck: None,
+        fouls: Default::default(),
+        defensive_motions: Default::default(),
+    };
+
+    hub.turns().turns().insert(ring_id.to_string(), state.clone());
+    ensure_turn_timer(hub);
+    hub.turns().notify_changed();
+
+    info!(ring_id = %ring_id, striker = %state.striker, strikes_per_turn, ?time_limit_ms, "🔁 Modo por turnos activado");
+    announce_turn(hub, &state, TurnEndReason::Started);
+    Ok(state)
+}
+
+/// Activa el modo por turnos con los valores de la app para lo que no se indique
+///
+/// Sin peleadores se usan `fighter_1` y `fighter_2`; `time_limit_ms` = 0
+/// desactiva el límite de tiempo y sin él se usa el de por defecto.
+pub fn start_turn_mode_with_defaults(
+    hub: &BroadcastHub,
+    ring_id: &str,
+    first_striker: &str,
+    fighters: Option<[String; 2]>,
+    strikes_per_turn: Option<u32>,
+    time_limit_ms: Option<u64>,
+) -> BoutResult<TurnState> {
+    let fighters = fighters.unwrap_or_else(|| ["fighter_1".to_string(), "fighter_2".to_string()]);
+    let time_limit_ms = match time_limit_ms {
+        None => Some(DEFAULT_TURN_TIME_LIMIT_MS),
+        Some(0) => None,
+        Some(limit) => Some(limit),
+    };
+    start_turn_mode(hub, ring_id, fighters, first_striker, strikes_per_turn.unwrap_or(1), time_limit_ms)
+}
+
+/// Reanuda un modo por turnos recuperado; el turno en curso empieza de nuevo su tiempo
+pub fn resume_turn_mode(hub: &BroadcastHub, mut state: TurnState) -> BoutResult<()> {
+    let now = now_ms();
+    state.turn_started_at = now;
+    state.turn_deadline = state.time_limit_ms.map(|limit| now + limit);
+
+    info!(ring_id = %state.ring_id, striker = %state.striker, turn_number = state.turn_number,
+          "🔁 Modo por turnos reanudado");
+    hub.turns().turns().insert(state.ring_id.clone(), state);
+    ensure_turn_timer(hub);
+    hub.turns().notify_changed();
+    Ok(())
+}
+
+/// Desactiva el modo por turnos de un ring; devuelve `false` si no estaba activo
+pub fn stop_turn_mode(hub: &BroadcastHub, ring_id: &str) -> bool {
+    let stopped = hub.turns().turns().remove(ring_id).is_some();
+    if stopped {
+        hub.turns().notify_changed();
+        info!(ring_id = %ring_id, "🔁 Modo por turnos desactivado");
+    }
+    stopped
+}
+
+/// Vuelve a empezar los turnos al cambiar de round, con el atacante que abrió el combate
+///
+/// Las faltas y los movimientos defensivos se conservan: cuentan para todo el combate.
+pub fn restart_turns_for_round(hub: &BroadcastHub, ring_id: &str) -> Option<TurnState> {
+    let state = {
+        let mut turns = hub.turns().turns();
+        let state = turns.get_mut(ring_id)?;
+
+        // Los turnos recuperados de versiones anteriores no guardan quién abrió
+        if state.fighters.contains(&state.opening_striker) && state.striker != state.opening_striker {
+            std::mem::swap(&mut state.striker, &mut state.defender);
+        }
+        state.turn_number = 1;
+        state.strikes_in_turn = 0;
+        state.turn_started_at = now_ms();
+        state.turn_deadline = state.time_limit_ms.map(|limit| state.turn_started_at + limit);
+        state.clone()
+    };
+
+    announce_turn(hub, &state, TurnEndReason::RoundChange);
+    Some(state)
+}
+
+/// Pasa el turno al otro peleador a petición del operador
+pub fn pass_turn(hub: &BroadcastHub, ring_id: &str) -> BoutResult<TurnState> {
+    let state = {
+        let mut turns = hub.turns().turns();
+        let state = turns.get_mut(ring_id)
+            .ok_or_else(|| format!("El modo por turnos no está activo en el ring {}", ring_id))?;
+        advance_turn(state);
+        state.clone()
+    };
+
+    announce_turn(hub, &state, TurnEndReason::Manual);
+    Ok(state)
+}
+
+/// Comprueba un golpe detectado contra el turno actual de su ring
+pub fn check_strike(hub: &BroadcastHub, event: &SimpleCombatEvent) -> StrikeCheck {
+    let mut turns = hub.turns().turns();
+    let Some(state) = turns.get_mut(&event.ring_id) else {
+        return StrikeCheck::Free;
+    };
+
+    if event.fighter_id != state.striker {
+        let fouls = state.fouls.entry(event.fighter_id.clone()).or_insert(0);
+        *fouls += 1;
+        return StrikeCheck::Foul(TurnFoul {
+            fighter_id: event.fighter_id.clone(),
+            expected_striker: state.striker.clone(),
+            turn_number: state.turn_number,
+            total_fouls: *fouls,
+            event: event.clone(),
+        });
+    }
+
+    state.strikes_in_turn += 1;
+    state.last_strike_at = Some(event.timestamp);
+    state.last_struck = Some(state.defender.clone());
+    if state.strikes_in_turn < state.strikes_per_turn {
+        return StrikeCheck::Valid(None);
+    }
+    advance_turn(state);
+    StrikeCheck::Valid(Some(state.clone()))
+}
+
+/// Turno en curso del ring si el peleador es ahora el defensor o recibió el último golpe
+/// (el golpe que cierra un turno también provoca la reacción de quien lo recibe)
+pub fn defending_turn(hub: &BroadcastHub, ring_id: &str, fighter_id: &str) -> Option<TurnState> {
+    current_turn(hub, ring_id)
+        .filter(|state| state.defender == fighter_id || state.last_struck.as_deref() == Some(fighter_id))
+}
+
+/// Registra y difunde un bloqueo o encogimiento del defensor
+pub fn announce_defensive_motion(hub: &BroadcastHub, motion: &DefensiveMotionEvent) {
+    if let Some(state) = hub.turns().turns().get_mut(&motion.ring_id) {
+        *state.defensive_motions.entry(motion.fighter_id.clone()).or_insert(0) += 1;
+    }
+
+    hub.emit_frontend("defensive-motion", motion);
+    hub.broadcast(&motion.ring_id, &ServerMessage::DefensiveMotion { data: motion.clone() });
+}
+
+/// Difunde una falta por golpe fuera de turno
+pub fn announce_foul(hub: &BroadcastHub, foul: &TurnFoul) {
+    warn!(ring_id = %foul.event.ring_id, fighter_id = %foul.fighter_id, expected = %foul.expected_striker, turn = foul.turn_number,
+          "🚫 Golpe fuera de turno");
+
+    hub.emit_frontend("turn-foul", foul);
+    hub.broadcast(&foul.event.ring_id, &ServerMessage::TurnFoul { data: foul.clone() });
+}
+
+/// Difunde el turno actual y el motivo del cambio
+pub fn announce_turn(hub: &BroadcastHub, state: &TurnState, reason: TurnEndReason) {
+    info!(ring_id = %state.ring_id, turn = state.turn_number, striker = %state.striker, ?reason, "🔁 Turno de {}", state.striker);
+
+    hub.emit_frontend("turn-change", &serde_json::json!({ "reason": reason, "turn": state }));
+    hub.broadcast(&state.ring_id, &ServerMessage::TurnChange { reason, data: state.clone() });
+    hub.turns().notify_changed();
+}
+
+/// Intercambia atacante y defensor y reinicia el reloj del turno
+fn advance_turn(state: &mut TurnState) {
+    std::mem::swap(&mut state.striker, &mut state.defender);
+    state.turn_number += 1;
+    state.strikes_in_turn = 0;
+    state.turn_started_at = now_ms();
+    state.turn_deadline = state.time_limit_ms.map(|limit| state.turn_started_at + limit);
+}
+
+/// Lanza el temporizador del hub que pasa el turno al agotarse el tiempo
+fn ensure_turn_timer(hub: &BroadcastHub) {
+    let tracker = hub.turns();
+    tracker.timer.get_or_init(|| {
+        // El temporizador no retiene el hub: termina cuando este se descarta
+        let timer = run_turn_timer(hub.downgrade(), tracker.shared.clone(), tracker.stop.subscribe());
+        // Los comandos síncronos de Tauri no corren dentro del runtime: se usa el suyo
+        #[cfg(feature = "desktop")]
+        tauri::async_runtime::spawn(timer);
+        #[cfg(not(feature = "desktop"))]
+        tokio::spawn(timer);
+    });
+}
+
+/// Turno que vence antes entre todos los rings
+fn next_turn_deadline(shared: &TurnShared) -> Option<(String, u64)> {
+    let turns = shared.turns.lock().unwrap_or_else(|e| e.into_inner());
+    turns.values()
+        .filter_map(|state| state.turn_deadline.map(|deadline| (state.ring_id.clone(), deadline)))
+        .min_by_key(|(_, deadline)| *deadline)
+}
+
+async fn run_turn_timer(hub: WeakBroadcastHub, shared: Arc<TurnShared>, mut stop: watch::Receiver<()>) {
+    loop {
+        match next_turn_deadline(&shared) {
+            Some((ring_id, deadline)) => {
+                let wait = Duration::from_millis(deadline.saturating_sub(now_ms()));
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {
+                        let Some(hub) = hub.upgrade() else {
+                            return;
+                        };
+                        expire_turn(&hub, &ring_id, deadline);
+                    }
+                    _ = shared.changed.notified() => {}
+                    _ = stop.changed() => return,
+                }
+            }
+            None => tokio::select! {
+                _ = shared.changed.notified() => {}
+                // El hub se descartó
+                _ = stop.changed() => return,
+            },
+        }
+    }
+}
+
+/// Pasa el turno si sigue vigente el plazo que venció
+fn expire_turn(hub: &BroadcastHub, ring_id: &str, deadline: u64) {
+    let state = {
+        let mut turns = hub.turns().turns();
+        let Some(state) = turns.get_mut(ring_id) else {
+            return;
+        };
+        if state.turn_deadline != Some(deadline) {
+            return;
+        }
+        advance_turn(state);
+        state.clone()
+    };
+
+    announce_turn(hub, &state, TurnEndReason::Timeout);
+}
@@ -0,0 +1,120 @@
+//! Tipos y estructuras para el registro de eventos del combate
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+use crate::ble::types::{CompetitorMaxStats, DeviceAssignment, SimpleCombatEvent};
+use crate::scoring::types::{FighterScore, ScoreEntry, ScoringRuleset};
+use crate::broadcast_ws::protocol::BattleConfig;
+use crate::broadcast_ws::snapshot::ActiveView;
+use crate::bout::ring::default_ring_id;
+
+// Entrada del registro de eventos del combate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombatLogEntry {
+    pub event: SimpleCombatEvent,
+    pub voided: bool,                // Evento anulado (no cuenta en estadísticas ni marcador)
+    pub voided_by: Option<String>,   // Quién anuló el evento
+    #[serde(default)]
+    pub before_stats_reset: bool,    // Anterior a un reset de máximos (no cuenta al recalcularlos)
+}
+
+// Tipo de resultado para operaciones del combate
+pub type BoutResult<T> = Result<T, String>;
+
+// Motivo por el que terminó un turno
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnEndReason {
+    Started,                         // Primer turno del combate
+    Strike,                          // El atacante dio sus golpes válidos
+    Timeout,                         // Se agotó el tiempo del turno
+    Manual,                          // El operador pasó el turno
+    RoundChange,                     // Empezó un nuevo round y los turnos vuelven a empezar
+}
+
+// Estado del modo por turnos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnState {
+    #[serde(default = "default_ring_id")]
+    pub ring_id: String,
+    pub fighters: [String; 2],
+    pub striker: String,             // Peleador que puede golpear
+    pub defender: String,            // Peleador que recibe
+    #[serde(default)]
+    pub opening_striker: String,     // Atacante del primer turno de cada round
+    pub turn_number: u32,
+    pub strikes_in_turn: u32,        // Golpes válidos del turno actual
+    pub strikes_per_turn: u32,
+    pub time_limit_ms: Option<u64>,
+    pub turn_started_at: u64,
+    pub turn_deadline: Option<u64>,  // Timestamp en ms en que vence el turno
+    #[serde(default)]
+    pub last_strike_at: Option<u64>, // Último golpe válido del atacante (ms)
+    #[serde(default)]
+    pub last_struck: Option<String>, // Defensor que recibió ese golpe
+    pub fouls: BTreeMap<String, u32>, // Golpes fuera de turno por peleador
+    pub defensive_motions: BTreeMap<String, u32>, // Bloqueos y encogimientos por peleador
+}
+
+// Golpe fuera de turno
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnFoul {
+    pub fighter_id: String,
+    pub expected_striker: String,
+    pub turn_number: u32,
+    pub total_fouls: u32,
+    pub event: SimpleCombatEvent,
+}
+
+// Estado del combate guardado en disco para recuperarlo tras un cierre inesperado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoutRecoverySnapshot {
+    pub saved_at: u64,
+    pub assignments: Vec<DeviceAssignment>,
+    pub max_stats: Vec<CompetitorMaxStats>,
+    pub scores: Vec<FighterScore>,
+    pub score_ledger: Vec<ScoreEntry>,
+    pub event_log: Vec<CombatLogEntry>,
+    pub event_sequence: u64,         // Última secuencia usada, para no repetir números
+    #[serde(default)]
+    pub rings: Vec<RingRecovery>,
+}
+
+// Estado propio de cada ring en el combate guardado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RingRecovery {
+    pub ring_id: String,
+    pub battle_config: Option<BattleConfig>,
+    pub active_view: Option<ActiveView>,
+    pub turn: Option<TurnState>,
+    #[serde(default)]
+    pub ruleset: Option<ScoringRuleset>, // Reglamento propio con el que se puntuaba el ring
+}
+
+// Resumen del combate recuperable que se muestra al operador
+#[derive(Debug, Clone, Serialize)]
+pub struct BoutRecoverySummary {
+    pub saved_at: u64,
+    pub competitors: Vec<String>,
+    pub devices: usize,
+    pub events: usize,
+    pub rings: Vec<RingRecoverySummary>,
+}
+
+// Resumen de un ring del combate recuperable
+#[derive(Debug, Clone, Serialize)]
+pub struct RingRecoverySummary {
+    pub ring_id: String,
+    pub current_round: Option<u32>,
+    pub rounds: Option<u32>,
+    pub view_type: Option<String>,
+}
+
+// Resultado de restaurar un combate
+#[derive(Debug, Clone, Serialize)]
+pub struct BoutRecoveryResult {
+    pub restored_events: usize,
+    pub reconnected_devices: Vec<String>,
+    pub failed_devices: BTreeMap<String, String>, // device_id -> error
+}
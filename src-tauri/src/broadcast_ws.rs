@@ -1,87 +1,64 @@
+pub mod protocol;
+pub mod channel;
+pub mod snapshot;
+pub mod hub;
+
+pub mod client;
+pub mod settings;
+pub mod access;
+pub mod tls;
+pub mod server;
+pub mod api;
+pub mod control;
+pub mod sse;
+pub mod topics;
+pub mod delay;
+#[cfg(feature = "desktop")]
+pub mod commands;
+
+use std::collections::HashMap;
 use std::{net::SocketAddr};
+use std::sync::atomic::Ordering;
 
 use axum::{
-    extract::ws::{Message, WebSocket, WebSocketUpgrade},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::get,
-    Router,
+    extract::ws::{close_code, Message, WebSocket, WebSocketUpgrade},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
-use axum::routing::get_service;
-use once_cell::sync::OnceCell;
-use tokio::{net::TcpListener, sync::broadcast, task::JoinHandle};
-use tower_http::services::ServeDir;
-use tracing::{error, info, warn};
-
-static BROADCAST_TX: OnceCell<broadcast::Sender<String>> = OnceCell::new();
-static SERVER_HANDLE: OnceCell<JoinHandle<()>> = OnceCell::new();
-
- pub async fn start_ws_server(port: u16, bind_all: bool, static_dir: Option<String>) -> Result<String, String> {
-    if SERVER_HANDLE.get().is_some() {
-        return Ok("WS server already running".to_string());
-    }
-
-    let (tx, _rx) = broadcast::channel::<String>(1024);
-    let _ = BROADCAST_TX.set(tx);
-
-    let bind_ip = if bind_all { "0.0.0.0" } else { "127.0.0.1" };
-    let addr: SocketAddr = format!("{}:{}", bind_ip, port)
-        .parse()
-        .map_err(|e| format!("Invalid addr: {}", e))?;
-
-    let static_dir = static_dir.unwrap_or_else(|| "static".to_string());
-
-    let app = Router::new()
-        .route("/ws", get(ws_upgrade))
-        .fallback_service(
-            get_service(ServeDir::new(static_dir.clone())).handle_error(|e| async move {
-                error!(error = %e, "Static file service error");
-                (StatusCode::INTERNAL_SERVER_ERROR, "static service error")
-            }),
-        );
-
-    let listener = TcpListener::bind(addr)
-        .await
-        .map_err(|e| format!("Failed binding {}: {}", addr, e))?;
-
-    info!(%addr, static_dir = %static_dir, "🚀 WS/HTTP server starting");
-
-    let handle = tokio::spawn(async move {
-        if let Err(e) = axum::serve(listener, app).await {
-            error!(error = %e, "Server error");
-        }
-    });
-
-    let _ = SERVER_HANDLE.set(handle);
-
-    Ok(format!("WS server running at http://{}", addr))
-}
+use futures::{SinkExt, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
 
-pub fn ws_broadcast<T: serde::Serialize>(event: &T) {
-    if let Some(tx) = BROADCAST_TX.get() {
-        match serde_json::to_string(event) {
-            Ok(payload) => {
-                let _ = tx.send(payload);
-            }
-            Err(e) => warn!(error = %e, "Failed to serialize event for WS broadcast"),
-        }
-    }
-}
-
-// Estructura para configuración de batalla
-#[derive(serde::Serialize, Clone)]
-pub struct BattleConfig {
-    pub mode: String, // "time" o "rounds"
-    pub rounds: u32,
-    pub round_duration: Option<u32>, // Solo para modo "time"
-    pub current_round: u32,
-    pub timestamp: u64,
-}
+use crate::ble::state::BleManager;
+use crate::judge::session::{authenticate_judge, submit_judge_action, JudgeSession};
+use access::{authorize, extract_token, AccessGrant};
+use client::WsClient;
+use hub::BroadcastHub;
+use server::ServerContext;
+use topics::TopicFilter;
+use channel::BroadcastFrame;
+use delay::{sleep_until_due, validate_delay, DelayBuffer, DelaySource};
+use crate::webhooks::{notify_round_end, notify_view_change};
+use crate::bout::ring::{validate_ring_id, DEFAULT_RING_ID};
+#[cfg(feature = "desktop")]
+use crate::bout::ring::ring_or_default;
+use crate::bout::turns::{restart_turns_for_round, stop_turn_mode};
+use crate::bout::recovery::finish_bout_recovery;
+use crate::bout::event_log::clear_bout_logs;
+use snapshot::build_state_snapshot;
+use protocol::{
+    BattleConfig, ClientMessage, ErrorCode, ServerMessage, MIN_SUPPORTED_PROTOCOL_VERSION,
+    PROTOCOL_VERSION,
+};
 
 // Comando para enviar configuración de batalla
+#[cfg(feature = "desktop")]
 #[tauri::command]
 #[allow(dead_code)]
 pub fn broadcast_battle_config(
+    ble: tauri::State<'_, BleManager>,
+    ring_id: Option<String>,
     mode: String,
     rounds: u32,
     round_duration: Option<u32>,
@@ -92,81 +69,445 @@ pub fn broadcast_battle_config(
         rounds,
         round_duration,
         current_round,
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64,
     };
+    let ring_id = ring_or_default(ring_id)?;
+    apply_battle_config(&ble, &ring_id, &config);
 
-    ws_broadcast(&serde_json::json!({
-        "type": "battle_config",
-        "data": config
-    }));
-
-    info!(mode = %config.mode, rounds = config.rounds, current_round = config.current_round, "⚙️ Battle config with round info broadcasted");
+    info!(ring_id = %ring_id, mode = %config.mode, rounds = config.rounds, current_round = config.current_round, "⚙️ Battle config with round info broadcasted");
     Ok(format!("Battle config sent: {} mode, round {}/{}", config.mode, config.current_round, config.rounds))
 }
 
+/// Difunde la configuración y, si cambió el round, el aviso de cambio de round
+pub fn apply_battle_config(ble: &BleManager, ring_id: &str, config: &BattleConfig) {
+    let hub = ble.hub();
+    let previous = hub.ring(ring_id).views().remember_battle_config(config);
+    hub.broadcast(ring_id, &ServerMessage::BattleConfig { data: config.clone() });
+
+    // Aviso explícito de cambio de round para consumidores que no siguen la config
+    let previous_round = previous.map(|previous| previous.current_round);
+    if previous_round != Some(config.current_round) {
+        hub.broadcast(ring_id, &ServerMessage::RoundChange {
+            previous_round,
+            current_round: config.current_round,
+            rounds: config.rounds,
+        });
+
+        if let Some(previous_round) = previous_round.filter(|round| *round < config.current_round) {
+            notify_round_end(ble, ring_id, previous_round, Some(config.rounds));
+        }
+
+        // Cada round empieza sus turnos desde el primero
+        if previous_round.is_some() {
+            restart_turns_for_round(hub, ring_id);
+        }
+    }
+}
+
+/// Configuración de batalla implícita en las vistas de portada y de avance de round
+fn battle_config_from_view(view_type: &str, data: &serde_json::Value) -> Option<BattleConfig> {
+    let (config, mode_key, rounds_key, current_round) = match view_type {
+        // La portada se puede volver a mostrar a mitad de combate desde los controles
+        "cover" => {
+            let config = data.get("battleConfig")?;
+            let current_round = config.get("currentRound").and_then(|round| round.as_u64()).unwrap_or(1);
+            (config, "mode", "rounds", current_round as u32)
+        }
+        "round_advance" => (data, "battleMode", "totalRounds", data.get("currentRound")?.as_u64()? as u32),
+        _ => return None,
+    };
+
+    Some(BattleConfig {
+        mode: config.get(mode_key)?.as_str()?.to_string(),
+        rounds: config.get(rounds_key)?.as_u64()? as u32,
+        round_duration: config.get("roundDuration").and_then(|duration| duration.as_u64()).map(|duration| duration as u32),
+        current_round,
+    })
+}
+
 // Comando para cambiar vista de transmisión
+#[cfg(feature = "desktop")]
 #[tauri::command]
 #[allow(dead_code)]
 pub fn broadcast_view_change(
+    ble: tauri::State<'_, BleManager>,
+    ring_id: Option<String>,
     view_type: String,
     data: Option<serde_json::Value>,
 ) -> Result<String, String> {
-    let message = serde_json::json!({
-        "viewType": view_type,
-        "data": data.unwrap_or(serde_json::json!({})),
-        "timestamp": std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64,
+    let ring_id = ring_or_default(ring_id)?;
+    change_view(&ble, &ring_id, &view_type, data.unwrap_or(serde_json::json!({})));
+    Ok(format!("View changed to: {}", view_type))
+}
+
+/// Cambia la vista de un ring y aplica lo que implica (fin del combate, configuración del round)
+pub fn change_view(ble: &BleManager, ring_id: &str, view_type: &str, data: serde_json::Value) {
+    let hub = ble.hub();
+    hub.ring(ring_id).views().remember_active_view(view_type, &data);
+    notify_view_change(ble, ring_id, view_type, &data);
+    hub.broadcast(ring_id, &ServerMessage::ViewChange {
+        view_type: view_type.to_string(),
+        data: data.clone(),
     });
 
-    ws_broadcast(&message);
+    // El combate terminó: se vuelve al combate libre
+    if matches!(view_type, "combat_finished" | "combat_cancelled") {
+        stop_turn_mode(hub, ring_id);
+        finish_bout_recovery(ring_id);
+        clear_bout_logs(ring_id);
+    }
 
-    info!(view_type = %view_type, "📺 View change broadcasted");
-    Ok(format!("View changed to: {}", view_type))
+    // La app no envía la configuración aparte: se deduce de la portada y del avance de round
+    if let Some(config) = battle_config_from_view(view_type, &data) {
+        apply_battle_config(ble, ring_id, &config);
+    }
+
+    info!(ring_id = %ring_id, view_type = %view_type, "📺 View change broadcasted");
+}
+
+async fn ws_upgrade(
+    ws: WebSocketUpgrade,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    State(context): State<ServerContext>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    accept_upgrade(ws, remote_addr, context, DEFAULT_RING_ID.to_string(), query, headers, false)
+}
+
+/// Feed retrasado: sigue el retardo ajustable desde la app
+async fn ws_upgrade_delayed(
+    ws: WebSocketUpgrade,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    State(context): State<ServerContext>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    accept_upgrade(ws, remote_addr, context, DEFAULT_RING_ID.to_string(), query, headers, true)
+}
+
+/// Feed de un ring concreto (`/ws/ring/:ring_id`)
+async fn ws_upgrade_ring(
+    ws: WebSocketUpgrade,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    State(context): State<ServerContext>,
+    Path(ring_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    accept_upgrade(ws, remote_addr, context, ring_id, query, headers, false)
+}
+
+/// Feed retrasado de un ring concreto (`/ws/ring/:ring_id/delayed`)
+async fn ws_upgrade_ring_delayed(
+    ws: WebSocketUpgrade,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    State(context): State<ServerContext>,
+    Path(ring_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    accept_upgrade(ws, remote_addr, context, ring_id, query, headers, true)
+}
+
+fn accept_upgrade(
+    ws: WebSocketUpgrade,
+    remote_addr: SocketAddr,
+    context: ServerContext,
+    ring_id: String,
+    query: HashMap<String, String>,
+    headers: HeaderMap,
+    delayed_feed: bool,
+) -> Response {
+    info!(%remote_addr, ring_id = %ring_id, delayed_feed, "🔌 New WebSocket connection attempt");
+
+    if let Err(e) = validate_ring_id(&ring_id) {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+
+    let token = extract_token(&query, &headers);
+    let grant = match authorize(token.as_deref(), remote_addr, &context.settings) {
+        Ok(grant) => grant,
+        Err(e) => {
+            warn!(%remote_addr, reason = %e, "⛔ WebSocket connection rejected");
+            return (StatusCode::UNAUTHORIZED, e).into_response();
+        }
+    };
+
+    // Conectarse no crea rings: solo se sigue un ring que ya existe
+    if context.hub.existing_ring(&ring_id).is_none() {
+        warn!(%remote_addr, ring_id = %ring_id, "⛔ WebSocket connection to unknown ring rejected");
+        return (StatusCode::NOT_FOUND, format!("El ring {} no existe", ring_id)).into_response();
+    }
+
+    let topics = match TopicFilter::parse_list(query.get("topics").map(String::as_str).unwrap_or_default()) {
+        Ok(topics) => topics,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    let delay_source = match parse_delay_source(&context.hub, &query, delayed_feed) {
+        Ok(source) => source,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    // El hueco viaja con la conexión y se libera al cerrarse o si la actualización falla
+    let Some(slot) = context.hub.reserve_connection(context.settings.max_connections) else {
+        warn!(%remote_addr, max = context.settings.max_connections, "⛔ WebSocket connection limit reached");
+        return (StatusCode::SERVICE_UNAVAILABLE, "Connection limit reached").into_response();
+    };
+
+    ws.on_upgrade(move |socket| async move {
+        handle_socket(socket, remote_addr, ring_id, grant, topics, delay_source, context).await;
+        drop(slot);
+    })
 }
 
-async fn ws_upgrade(ws: WebSocketUpgrade) -> impl IntoResponse {
-    info!("🔌 New WebSocket connection attempt");
-    ws.on_upgrade(handle_socket)
+/// Retardo del cliente: `?delay=ms` propio o el del feed retrasado
+pub fn parse_delay_source(
+    hub: &BroadcastHub,
+    query: &HashMap<String, String>,
+    delayed_feed: bool,
+) -> Result<DelaySource, String> {
+    match query.get("delay") {
+        Some(delay) => {
+            let delay_ms = delay.parse::<u64>()
+                .map_err(|_| format!("Retardo inválido: '{}'", delay))?;
+            Ok(DelaySource::Fixed(validate_delay(delay_ms)?))
+        }
+        None if delayed_feed => Ok(hub.delayed_feed().source()),
+        None => Ok(DelaySource::live()),
+    }
 }
 
-async fn handle_socket(mut socket: WebSocket) {
-    info!("✅ WebSocket connection established");
-    
-    let Some(tx) = BROADCAST_TX.get() else {
-        error!("❌ Broadcast channel not ready");
-        let _ = socket.send(Message::Text("{\"error\":\"broadcast not ready\"}".into())).await;
-        return;
+async fn handle_socket(
+    socket: WebSocket,
+    remote_addr: SocketAddr,
+    ring_id: String,
+    grant: AccessGrant,
+    topics: TopicFilter,
+    delay_source: DelaySource,
+    context: ServerContext,
+) {
+    info!(%remote_addr, ring_id = %ring_id, role = ?grant.role, label = %grant.label, "✅ WebSocket connection established");
+    let (mut sink, mut stream) = socket.split();
+
+    let ServerContext { hub, ble, mut shutdown, .. } = context;
+    let mut rx = hub.ring(&ring_id).channel().subscribe();
+    // Los tokens de juez o admin autentican la conexión como juez con su etiqueta
+    let mut judge_session = JudgeSession {
+        judge: grant.role.can_judge().then(|| grant.label.clone()),
     };
+    let client = hub.clients().register(remote_addr, &ring_id, grant, topics);
+    info!(client_id = client.id, "📡 WebSocket client subscribed to broadcast channel");
+
+    // Tarea de escritura: vacía la cola acotada del cliente hacia el socket
+    let writer_client = client.clone();
+    let mut writer = tokio::spawn(async move {
+        while let Some(message) = writer_client.queue.pop().await {
+            if sink.send(message).await.is_err() {
+                break;
+            }
+            writer_client.sent_messages.fetch_add(1, Ordering::Relaxed);
+        }
+        let _ = sink.close().await;
+    });
 
-    let mut rx = tx.subscribe();
-    info!("📡 WebSocket client subscribed to broadcast channel");
+    // Solo los mensajes difundidos y la instantánea se retrasan; las respuestas directas salen al momento
+    let mut delay = DelayBuffer::new(delay_source);
+
+    // Estado actual para clientes que se conectan a mitad de combate.
+    // La suscripción ya existe, así que ningún mensaje posterior se pierde.
+    enqueue_message(&client, &ServerMessage::hello());
+    enqueue_snapshot(&client, &ble, &ring_id, &mut delay);
+    if let Some(judge) = judge_session.judge.clone() {
+        enqueue_message(&client, &ServerMessage::JudgeAuthOk { judge });
+    }
+    info!(client_id = client.id, "📸 State snapshot queued for WebSocket client");
 
     loop {
+        if client.queue.is_closed() {
+            // Token revocado: el frame de cierre ya está en la cola
+            info!(client_id = client.id, "Access revoked; closing WebSocket");
+            break;
+        }
+        client.delay_ms.store(delay.delay_ms(), Ordering::Relaxed);
+        client.held_messages.store(delay.held() as u64, Ordering::Relaxed);
+        let next_due = delay.next_due();
+
         tokio::select! {
             msg = rx.recv() => {
                 match msg {
-                    Ok(text) => {
-                        if socket.send(Message::Text(text)).await.is_err() {
-                            break;
+                    Ok(frame) => {
+                        if !client.accepts(frame.topic.as_deref()) {
+                            continue;
                         }
+                        if let Some(frame) = delay.push(frame) {
+                            if !enqueue_frame(&client, &frame) {
+                                resync_client(&client, &ble, &ring_id, &mut delay, 1);
+                            }
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        resync_client(&client, &ble, &ring_id, &mut delay, missed);
                     }
-                    Err(_e) => {
-                        // lagged or closed; continue
+                    Err(RecvError::Closed) => {
+                        info!(client_id = client.id, "Broadcast channel closed; ending WebSocket");
+                        break;
                     }
                 }
             }
-            incoming = socket.recv() => {
+            incoming = stream.next() => {
                 match incoming {
                     Some(Ok(Message::Close(_))) | None => break,
-                    Some(Ok(_)) => { /* ignore client messages */ }
+                    // Una acción que llega junto a la revocación ya no se atiende
+                    Some(Ok(Message::Text(_))) if client.queue.is_closed() => continue,
+                    Some(Ok(Message::Text(text))) => {
+                        let reply = handle_client_message(&ble, &text, &client, &mut judge_session);
+                        if !enqueue_message(&client, &reply) {
+                            resync_client(&client, &ble, &ring_id, &mut delay, 1);
+                        }
+                    }
+                    Some(Ok(_)) => { /* ignore binary/ping frames */ }
                     Some(Err(_)) => break,
                 }
             }
+            _ = sleep_until_due(next_due) => {
+                release_due_frames(&client, &ble, &ring_id, &mut delay);
+            }
+            _ = delay.changed() => {
+                // Un retardo menor libera al momento lo que ya venció
+                release_due_frames(&client, &ble, &ring_id, &mut delay);
+            }
+            _ = client.queue.closed() => continue,
+            _ = &mut writer => {
+                // El socket dejó de aceptar escrituras
+                break;
+            }
+            _ = shutdown.changed() => {
+                // El servidor se está deteniendo: el cierre sustituye a lo pendiente para
+                // que llegue aunque la cola esté llena o el cliente vaya lento
+                info!(client_id = client.id, "Server stopping; closing WebSocket");
+                client.disconnect(close_code::AWAY, "Server shutting down");
+                break;
+            }
+        }
+    }
+
+    client.queue.close();
+    let _ = writer.await;
+    hub.clients().unregister(client.id);
+    info!(client_id = client.id, "🔌 WebSocket client disconnected");
+}
+
+/// Serializa un mensaje tipado como frame de texto
+fn to_ws_message(message: &ServerMessage) -> Option<Message> {
+    match message.to_json() {
+        Ok(payload) => Some(Message::Text(payload)),
+        Err(e) => {
+            warn!(error = %e, "Failed to serialize WS message");
+            None
+        }
+    }
+}
+
+/// Encola un mensaje difundido ya serializado; devuelve false si la cola está llena
+fn enqueue_frame(client: &WsClient, frame: &BroadcastFrame) -> bool {
+    client.queue.push(Message::Text(frame.payload.clone()))
+}
+
+/// Encola un mensaje para un único cliente; devuelve false si la cola está llena
+fn enqueue_message(client: &WsClient, message: &ServerMessage) -> bool {
+    match to_ws_message(message) {
+        Some(message) => client.queue.push(message),
+        None => true,
+    }
+}
+
+/// Encola los mensajes retrasados que ya vencieron
+fn release_due_frames(client: &WsClient, ble: &BleManager, ring_id: &str, delay: &mut DelayBuffer) {
+    for frame in delay.pop_due() {
+        if !enqueue_frame(client, &frame) {
+            resync_client(client, ble, ring_id, delay, 1);
+            return;
+        }
+    }
+}
+
+/// Encola la instantánea del ring detrás del retardo del cliente, en orden con lo difundido,
+/// para que un overlay retrasado no muestre el estado antes que el vídeo
+fn enqueue_snapshot(client: &WsClient, ble: &BleManager, ring_id: &str, delay: &mut DelayBuffer) -> bool {
+    let snapshot = ServerMessage::Snapshot { data: Box::new(build_state_snapshot(ble, ring_id)) };
+    let Some(frame) = BroadcastFrame::direct(&snapshot, 0) else {
+        return true;
+    };
+    match delay.push(frame) {
+        Some(frame) => enqueue_frame(client, &frame),
+        None => true,
+    }
+}
+
+/// El cliente se quedó atrás: descartar lo pendiente y reenviar el estado actual
+///
+/// Sirve tanto si el canal de difusión lo adelantó como si su cola se llenó,
+/// para que nunca quede con huecos sin saberlo.
+fn resync_client(client: &WsClient, ble: &BleManager, ring_id: &str, delay: &mut DelayBuffer, missed: u64) {
+    client.lag_events.fetch_add(1, Ordering::Relaxed);
+    let replacement = to_ws_message(&ServerMessage::Lagged { missed }).into_iter().collect();
+    let discarded = client.queue.replace_all(replacement) + delay.clear();
+    enqueue_snapshot(client, ble, ring_id, delay);
+    client.record_dropped(missed + discarded as u64);
+    warn!(client_id = client.id, missed, discarded, "🐢 WebSocket client lagged; snapshot resent");
+}
+
+/// Procesa un mensaje de texto del cliente y devuelve la respuesta a enviarle
+fn handle_client_message(
+    ble: &BleManager,
+    text: &str,
+    client: &WsClient,
+    judge_session: &mut JudgeSession,
+) -> ServerMessage {
+    let message: ClientMessage = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(e) => {
+            warn!(error = %e, "Unrecognized WS client message");
+            return ServerMessage::error(ErrorCode::InvalidMessage, format!("Mensaje no reconocido: {}", e));
+        }
+    };
+
+    match message {
+        ClientMessage::Hello { protocol_version } => {
+            if (MIN_SUPPORTED_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&protocol_version) {
+                ServerMessage::HelloAck { client_version: protocol_version }
+            } else {
+                warn!(client_version = protocol_version, "Unsupported WS protocol version");
+                ServerMessage::error(
+                    ErrorCode::UnsupportedVersion,
+                    format!(
+                        "Versión de protocolo {} no soportada (soportadas: {}-{})",
+                        protocol_version, MIN_SUPPORTED_PROTOCOL_VERSION, PROTOCOL_VERSION
+                    ),
+                )
+            }
+        }
+        ClientMessage::Ping => ServerMessage::Pong,
+        ClientMessage::JudgeAuth { .. } | ClientMessage::JudgeAction { .. } if !client.grant.role.can_judge() => {
+            warn!(label = %client.grant.label, "Judge message from connection without judge role");
+            ServerMessage::error(ErrorCode::Unauthorized, "La conexión no tiene rol de juez")
+        }
+        ClientMessage::JudgeAuth { key } => authenticate_judge(&key, &client.grant, judge_session),
+        ClientMessage::JudgeAction { action } => submit_judge_action(ble, &client.ring_id, action, judge_session),
+        ClientMessage::Subscribe { topics } => {
+            let mut filter = client.topics.lock().unwrap_or_else(|e| e.into_inner());
+            match filter.subscribe(&topics) {
+                Ok(()) => ServerMessage::Subscribed { topics: filter.topics() },
+                Err(e) => ServerMessage::error(ErrorCode::InvalidMessage, e),
+            }
+        }
+        ClientMessage::Unsubscribe { topics } => {
+            let mut filter = client.topics.lock().unwrap_or_else(|e| e.into_inner());
+            match filter.unsubscribe(&topics) {
+                Ok(()) => ServerMessage::Subscribed { topics: filter.topics() },
+                Err(e) => ServerMessage::error(ErrorCode::InvalidMessage, e),
+            }
         }
     }
 }
@@ -0,0 +1,265 @@
+//! Control de acceso al servidor de transmisión mediante tokens con rol
+//!
+//! El token se envía como parámetro `?token=` (los navegadores no permiten
+//! cabeceras en WebSocket) o en la cabecera `Authorization: Bearer <token>`.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use axum::{
+    extract::{ConnectInfo, Query, Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::broadcast_ws::api::api_error;
+use crate::broadcast_ws::server::ServerContext;
+use crate::broadcast_ws::settings::{config_file_path, read_config_file, write_private_config_file, ServerSettings};
+
+/// Nombre del archivo de tokens dentro del directorio de configuración
+const TOKENS_FILE_NAME: &str = "broadcast_tokens.json";
+
+/// Longitud mínima de un token fijado por configuración
+const MIN_PROVISIONED_TOKEN_LEN: usize = 16;
+
+// Roles de acceso, de menor a mayor privilegio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessRole {
+    Viewer, // Solo lectura (pantallas de transmisión)
+    Judge,  // Puede enviar ajustes de puntuación
+    Admin,  // Lo del juez más la API de control remoto (`/api/control`)
+}
+
+impl AccessRole {
+    /// Indica si el rol puede enviar acciones de juez
+    pub fn can_judge(self) -> bool {
+        self >= AccessRole::Judge
+    }
+
+    /// Indica si el rol puede manejar sensores y combate desde `/api/control`
+    pub fn can_control(self) -> bool {
+        self == AccessRole::Admin
+    }
+}
+
+// Token de acceso emitido por el operador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessToken {
+    pub token: String,
+    pub role: AccessRole,
+    pub label: String,               // Nombre del juez o del equipo que lo usa
+    pub created_at: u64,
+}
+
+// Permisos concedidos a una conexión
+#[derive(Debug, Clone)]
+pub struct AccessGrant {
+    pub role: AccessRole,
+    pub label: String,
+    pub token: Option<String>,       // None para espectadores locales sin token
+}
+
+/// Mapa thread-safe de tokens emitidos (token -> datos del token)
+type AccessTokenRegistry = Arc<Mutex<HashMap<String, AccessToken>>>;
+
+// Tokens de acceso vigentes
+static ACCESS_TOKENS: Lazy<AccessTokenRegistry> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Función para obtener el registro de tokens
+pub fn get_access_tokens_state() -> AccessTokenRegistry {
+    ACCESS_TOKENS.clone()
+}
+
+/// Carga los tokens guardados en disco
+pub fn load_access_tokens() {
+    let tokens = match read_config_file::<Vec<AccessToken>>(TOKENS_FILE_NAME) {
+        Ok(Some(tokens)) => tokens,
+        Ok(None) => return,
+        Err(e) => {
+            warn!(error = %e, "Invalid access tokens file, ignoring");
+            return;
+        }
+    };
+
+    let registry = get_access_tokens_state();
+    let mut registry = registry.lock().unwrap_or_else(|e| e.into_inner());
+    registry.clear();
+    registry.extend(tokens.into_iter().map(|token| (token.token.clone(), token)));
+    info!(count = registry.len(), "🔑 Access tokens loaded");
+}
+
+fn save_access_tokens(registry: &HashMap<String, AccessToken>) -> Result<(), String> {
+    if config_file_path(TOKENS_FILE_NAME).is_none() {
+        // Sin directorio de configuración los tokens solo viven en memoria
+        return Ok(());
+    }
+
+    let mut tokens: Vec<&AccessToken> = registry.values().collect();
+    tokens.sort_by_key(|token| token.created_at);
+    // Los tokens dan acceso al servidor: el archivo solo lo lee el usuario de la app
+    write_private_config_file(TOKENS_FILE_NAME, &tokens).map(|_| ())
+}
+
+/// Emite un nuevo token para el rol indicado
+pub fn create_access_token(role: AccessRole, label: &str) -> Result<AccessToken, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("La etiqueta del token no puede estar vacía".to_string());
+    }
+
+    let token = AccessToken {
+        token: uuid::Uuid::new_v4().simple().to_string(),
+        role,
+        label: label.to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    };
+
+    let registry = get_access_tokens_state();
+    let mut registry = registry.lock()
+        .map_err(|e| format!("Error accediendo a los tokens: {}", e))?;
+    registry.insert(token.token.clone(), token.clone());
+    save_access_tokens(&registry)?;
+
+    info!(role = ?role, label = %token.label, "🔑 Access token created");
+    Ok(token)
+}
+
+/// Registra un token fijado por configuración (servidor sin interfaz); solo vive en memoria
+pub fn provision_access_token(token: &str, role: AccessRole, label: &str) -> Result<(), String> {
+    let token = token.trim();
+    if token.len() < MIN_PROVISIONED_TOKEN_LEN {
+        return Err(format!("El token debe tener al menos {} caracteres", MIN_PROVISIONED_TOKEN_LEN));
+    }
+
+    let registry = get_access_tokens_state();
+    let mut registry = registry.lock().unwrap_or_else(|e| e.into_inner());
+    registry.insert(token.to_string(), AccessToken {
+        token: token.to_string(),
+        role,
+        label: label.to_string(),
+        created_at: 0,
+    });
+
+    info!(role = ?role, label = %label, "🔑 Access token provisioned from config");
+    Ok(())
+}
+
+/// Revoca un token; las conexiones que lo usan deben cerrarse aparte
+pub fn revoke_access_token(token: &str) -> Result<AccessToken, String> {
+    let registry = get_access_tokens_state();
+    let mut registry = registry.lock()
+        .map_err(|e| format!("Error accediendo a los tokens: {}", e))?;
+
+    let revoked = registry.remove(token)
+        .ok_or_else(|| "No existe ese token".to_string())?;
+    save_access_tokens(&registry)?;
+
+    info!(role = ?revoked.role, label = %revoked.label, "🔑 Access token revoked");
+    Ok(revoked)
+}
+
+/// Lista los tokens vigentes ordenados por fecha de creación
+pub fn list_access_tokens() -> Vec<AccessToken> {
+    let registry = get_access_tokens_state();
+    let registry = registry.lock().unwrap_or_else(|e| e.into_inner());
+    let mut tokens: Vec<AccessToken> = registry.values().cloned().collect();
+    tokens.sort_by_key(|token| token.created_at);
+    tokens
+}
+
+/// Extrae el token de la petición (parámetro `token` o cabecera Authorization)
+pub fn extract_token(query: &HashMap<String, String>, headers: &HeaderMap) -> Option<String> {
+    if let Some(token) = query.get("token").filter(|token| !token.is_empty()) {
+        return Some(token.clone());
+    }
+
+    headers.get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Decide qué permisos tiene una conexión
+pub fn authorize(
+    token: Option<&str>,
+    remote_addr: SocketAddr,
+    settings: &ServerSettings,
+) -> Result<AccessGrant, String> {
+    if let Some(token) = token {
+        let registry = get_access_tokens_state();
+        let registry = registry.lock().unwrap_or_else(|e| e.into_inner());
+        return registry.get(token)
+            .map(|token| AccessGrant {
+                role: token.role,
+                label: token.label.clone(),
+                token: Some(token.token.clone()),
+            })
+            .ok_or_else(|| "Token de acceso inválido".to_string());
+    }
+
+    if !settings.require_token || (settings.allow_local_viewers && remote_addr.ip().is_loopback()) {
+        return Ok(AccessGrant {
+            role: AccessRole::Viewer,
+            label: "anonymous".to_string(),
+            token: None,
+        });
+    }
+
+    Err("Se requiere un token de acceso".to_string())
+}
+
+/// Middleware HTTP que rechaza peticiones sin permisos de espectador.
+/// Deja el `AccessGrant` en las extensiones de la petición para el handler.
+pub async fn require_viewer(
+    State(context): State<ServerContext>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let token = extract_token(&query, &headers);
+    match authorize(token.as_deref(), remote_addr, &context.settings) {
+        Ok(grant) => {
+            request.extensions_mut().insert(grant);
+            next.run(request).await
+        }
+        Err(e) => {
+            warn!(%remote_addr, path = %request.uri().path(), reason = %e, "⛔ HTTP request rejected");
+            api_error(StatusCode::UNAUTHORIZED, e)
+        }
+    }
+}
+
+/// Middleware HTTP que solo deja pasar tokens de administrador (control remoto)
+pub async fn require_admin(
+    State(context): State<ServerContext>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = extract_token(&query, &headers);
+    match authorize(token.as_deref(), remote_addr, &context.settings) {
+        Ok(grant) if grant.role.can_control() => next.run(request).await,
+        Ok(grant) => {
+            warn!(%remote_addr, path = %request.uri().path(), label = %grant.label, "⛔ Control request without admin role");
+            api_error(StatusCode::FORBIDDEN, "Se requiere un token de administrador")
+        }
+        Err(e) => {
+            warn!(%remote_addr, path = %request.uri().path(), reason = %e, "⛔ HTTP request rejected");
+            api_error(StatusCode::UNAUTHORIZED, e)
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! API REST de solo lectura para herramientas que no hablan WebSocket
+//! (Stream Deck, fuentes de datos de vMix, Google Sheets)
+//!
+//! Todas las rutas cuelgan de `/api` y aplican el mismo control de acceso que
+//! `/ws` con rol de espectador. Los datos del combate son del ring indicado en
+//! `?ring=` (por defecto el ring principal). El detalle de los clientes
+//! conectados (direcciones y etiquetas) solo se muestra a administradores.
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{Method, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use tower_http::cors::{Any, CorsLayer};
+
+use crate::ble::types::{CompetitorMaxStats, DeviceHealth};
+use crate::ble::state::BleManager;
+use crate::bout::ring::{validate_ring_id, DEFAULT_RING_ID};
+use crate::bout::types::{CombatLogEntry, TurnState};
+use crate::bout::turns::current_turn;
+use crate::bout::event_log::recent_combat_events;
+use crate::scoring::types::FighterScore;
+use crate::scoring::state::{active_ruleset, ring_score_tallies};
+use crate::broadcast_ws::access::{require_viewer, AccessGrant};
+use crate::broadcast_ws::protocol::{BattleConfig, PROTOCOL_VERSION};
+use crate::broadcast_ws::server::{ws_server_status, ServerContext, ServerStatus};
+use crate::broadcast_ws::snapshot::ActiveView;
+
+/// Eventos devueltos por defecto y máximo permitido en `/api/events`
+const DEFAULT_EVENTS_LIMIT: usize = 50;
+const MAX_EVENTS_LIMIT: usize = 500;
+
+// Combate en curso
+#[derive(Debug, Serialize)]
+pub struct BoutStatus {
+    pub ring_id: String,
+    pub battle_config: Option<BattleConfig>,
+    pub active_view: Option<ActiveView>,
+    pub ruleset: String,
+    pub scores: Vec<FighterScore>,
+    pub turn: Option<TurnState>,
+}
+
+// Estadísticas en vivo de un peleador
+#[derive(Debug, Serialize)]
+pub struct FighterStats {
+    pub fighter_id: String,
+    pub competitor_name: String,
+    pub max_stats: Option<CompetitorMaxStats>,
+    pub score: Option<FighterScore>,
+}
+
+// Estado del servidor y del sistema BLE
+#[derive(Debug, Serialize)]
+pub struct ApiStatus {
+    pub protocol_version: u32,
+    pub server: ServerStatus,
+    pub ble: serde_json::Value,
+}
+
+/// Rutas de la API REST
+pub fn api_router(context: ServerContext) -> Router<ServerContext> {
+    Router::new()
+        .route("/api/status", get(get_status))
+        .route("/api/rings", get(get_rings))
+        .route("/api/bout", get(get_bout))
+        .route("/api/stats", get(get_stats))
+        .route("/api/stats/:fighter_id", get(get_fighter_stats))
+        .route("/api/devices", get(get_devices))
+        .route("/api/events", get(get_events))
+        .route_layer(middleware::from_fn_with_state(context, require_viewer))
+        .layer(read_only_cors())
+}
+
+/// Lectura desde cualquier origen (páginas de overlays alojadas fuera)
+pub fn read_only_cors() -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods([Method::GET])
+        .allow_headers(Any)
+}
+
+pub fn api_error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(serde_json::json!({ "error": message.into() }))).into_response()
+}
+
+async fn get_status(State(context): State<ServerContext>, Extension(grant): Extension<AccessGrant>) -> Json<ApiStatus> {
+    let mut ble = context.ble.system_status();
+    if !grant.role.can_control() {
+        // Direcciones y etiquetas de los demás clientes; el total ya va en `server`
+        if let Some(status) = ble.as_object_mut() {
+            status.remove("broadcast_clients");
+        }
+    }
+
+    Json(ApiStatus {
+        protocol_version: PROTOCOL_VERSION,
+        server: ws_server_status(&context.hub, &context.settings).await,
+        ble,
+    })
+}
+
+/// Ring pedido con `?ring=`, o el ring por defecto
+fn query_ring(query: &HashMap<String, String>) -> Result<String, String> {
+    match query.get("ring") {
+        Some(ring_id) => validate_ring_id(ring_id).map(|()| ring_id.clone()),
+        None => Ok(DEFAULT_RING_ID.to_string()),
+    }
+}
+
+async fn get_rings(State(context): State<ServerContext>) -> Json<Vec<String>> {
+    Json(context.hub.ring_ids())
+}
+
+async fn get_bout(State(context): State<ServerContext>, Query(query): Query<HashMap<String, String>>) -> Response {
+    let ring_id = match query_ring(&query) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    let Some(feed) = context.hub.existing_ring(&ring_id) else {
+        return api_error(StatusCode::NOT_FOUND, format!("El ring {} no existe", ring_id));
+    };
+    let ruleset = active_ruleset(&ring_id).name;
+
+    Json(BoutStatus {
+        battle_config: feed.views().last_battle_config(),
+        active_view: feed.views().active_view(),
+        ruleset,
+        scores: sorted_scores(&ring_id),
+        turn: current_turn(&context.hub, &ring_id),
+        ring_id,
+    }).into_response()
+}
+
+async fn get_stats(State(context): State<ServerContext>, Query(query): Query<HashMap<String, String>>) -> Response {
+    match query_ring(&query) {
+        Ok(ring_id) => Json(fighter_stats(&context.ble, &ring_id)).into_response(),
+        Err(e) => api_error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+async fn get_fighter_stats(
+    State(context): State<ServerContext>,
+    Path(fighter_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let ring_id = match query_ring(&query) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    match fighter_stats(&context.ble, &ring_id).into_iter().find(|stats| stats.fighter_id == fighter_id) {
+        Some(stats) => Json(stats).into_response(),
+        None => api_error(StatusCode::NOT_FOUND, format!("Sin datos para {} en el ring {}", fighter_id, ring_id)),
+    }
+}
+
+async fn get_devices(State(context): State<ServerContext>, Query(query): Query<HashMap<String, String>>) -> Response {
+    // Sin `?ring=` se listan los dispositivos de todos los rings
+    let ring_id = match query.contains_key("ring").then(|| query_ring(&query)).transpose() {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    let devices: Vec<DeviceHealth> = context.ble.connected_devices_health().into_iter()
+        .filter(|device| ring_id.as_ref().is_none_or(|ring_id| device.ring_id == *ring_id))
+        .collect();
+    Json(devices).into_response()
+}
+
+async fn get_events(Query(query): Query<HashMap<String, String>>) -> Response {
+    let ring_id = match query_ring(&query) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    let limit = match query.get("limit").map(|limit| limit.parse::<usize>()) {
+        None => DEFAULT_EVENTS_LIMIT,
+        Some(Ok(limit)) => limit.min(MAX_EVENTS_LIMIT),
+        Some(Err(_)) => return api_error(StatusCode::BAD_REQUEST, "Parámetro 'limit' inválido"),
+    };
+    let fighter_id = query.get("fighter_id").map(String::as_str);
+
+    let events: Vec<CombatLogEntry> = recent_combat_events(&ring_id, fighter_id, limit);
+    Json(events).into_response()
+}
+
+fn sorted_scores(ring_id: &str) -> Vec<FighterScore> {
+    let mut scores = ring_score_tallies(ring_id);
+    scores.sort_by(|a, b| a.fighter_id.cmp(&b.fighter_id));
+    scores
+}
+
+/// Une estadísticas máximas y marcador por peleador de un ring
+pub fn fighter_stats(ble: &BleManager, ring_id: &str) -> Vec<FighterStats> {
+    let max_stats: HashMap<String, CompetitorMaxStats> = ble.ring_max_stats(ring_id).into_iter()
+        .map(|stats| (stats.fighter_id.clone(), stats))
+        .collect();
+    let mut scores: HashMap<String, FighterScore> = sorted_scores(ring_id).into_iter()
+        .map(|score| (score.fighter_id.clone(), score))
+        .collect();
+
+    let mut result: Vec<FighterStats> = max_stats.into_iter()
+        .map(|(fighter_id, stats)| FighterStats {
+            competitor_name: stats.competitor_name.clone(),
+            score: scores.remove(&fighter_id),
+            max_stats: Some(stats),
+            fighter_id,
+        })
+        .collect();
+
+    // Peleadores con puntuación pero sin golpes registrados
+    result.extend(scores.into_values().map(|score| FighterStats {
+        fighter_id: score.fighter_id.clone(),
+        competitor_name: score.competitor_name.clone(),
+        max_stats: None,
+        score: Some(score),
+    }));
+
+    result.sort_by(|a, b| a.fighter_id.cmp(&b.fighter_id));
+    result
+}
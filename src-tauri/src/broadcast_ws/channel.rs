@@ -0,0 +1,127 @@
+//! Canal de difusión compartido por WebSocket y SSE
+//!
+//! Cada mensaje difundido recibe un identificador secuencial y se guarda en
+//! un búfer circular para que los clientes SSE puedan reanudar con
+//! `Last-Event-ID` sin perder mensajes.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::broadcast_ws::protocol::ServerMessage;
+
+/// Capacidad del canal de difusión
+const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
+
+/// Mensajes guardados para reanudar clientes SSE
+pub const REPLAY_BUFFER_CAPACITY: usize = 512;
+
+// Mensaje difundido con su identificador secuencial
+#[derive(Debug)]
+pub struct BroadcastFrame {
+    pub id: u64,
+    pub message_type: String,        // `type` del mensaje, usado como nombre de evento SSE
+    pub topic: Option<String>,       // Tema para las suscripciones
+    pub payload: String,             // Sobre JSON completo
+}
+
+// Búfer de mensajes recientes y siguiente identificador
+struct ReplayBuffer {
+    next_id: u64,
+    frames: VecDeque<Arc<BroadcastFrame>>,
+}
+
+// Canal de difusión con su búfer de reanudación; vive más que el servidor para sobrevivir a los reinicios
+pub struct BroadcastChannel {
+    tx: broadcast::Sender<Arc<BroadcastFrame>>,
+    replay: Mutex<ReplayBuffer>,
+}
+
+impl Default for BroadcastChannel {
+    fn default() -> Self {
+        Self {
+            tx: broadcast::channel(BROADCAST_CHANNEL_CAPACITY).0,
+            replay: Mutex::new(ReplayBuffer {
+                next_id: 1,
+                frames: VecDeque::with_capacity(REPLAY_BUFFER_CAPACITY),
+            }),
+        }
+    }
+}
+
+impl BroadcastFrame {
+    /// Mensaje para un solo cliente con forma de frame, para que pase por su retardo.
+    /// Lleva el id del último mensaje difundido para que la reanudación siga desde ahí.
+    pub fn direct(message: &ServerMessage, last_id: u64) -> Option<Arc<Self>> {
+        match message.to_typed_json() {
+            Ok((message_type, payload)) => Some(Arc::new(Self { id: last_id, message_type, topic: None, payload })),
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize direct message");
+                None
+            }
+        }
+    }
+}
+
+impl BroadcastChannel {
+    /// Difunde un mensaje a todos los clientes conectados
+    pub fn publish(&self, message: &ServerMessage) {
+        let (message_type, payload) = match message.to_typed_json() {
+            Ok(parts) => parts,
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize event for broadcast");
+                return;
+            }
+        };
+
+        // El envío ocurre con el búfer bloqueado para que los identificadores lleguen en orden
+        let mut buffer = self.replay.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = Arc::new(BroadcastFrame {
+            id: buffer.next_id,
+            message_type,
+            topic: message.topic(),
+            payload,
+        });
+        buffer.next_id += 1;
+        if buffer.frames.len() >= REPLAY_BUFFER_CAPACITY {
+            buffer.frames.pop_front();
+        }
+        buffer.frames.push_back(frame.clone());
+        let _ = self.tx.send(frame);
+    }
+
+    /// Se suscribe al canal de difusión
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<BroadcastFrame>> {
+        self.tx.subscribe()
+    }
+
+    /// Identificador del último mensaje difundido (0 si no hay ninguno)
+    pub fn last_frame_id(&self) -> u64 {
+        self.replay.lock().unwrap_or_else(|e| e.into_inner()).next_id - 1
+    }
+
+    /// Mensajes posteriores a `last_id`; None si ya no están en el búfer
+    pub fn frames_since(&self, last_id: u64) -> Option<Vec<Arc<BroadcastFrame>>> {
+        let buffer = self.replay.lock().unwrap_or_else(|e| e.into_inner());
+        let newest = buffer.next_id - 1;
+
+        // Identificador de otra sesión de la app (los ids se reinician al arrancar)
+        if last_id > newest {
+            return None;
+        }
+        if last_id == newest {
+            return Some(Vec::new());
+        }
+
+        let oldest = buffer.frames.front().map(|frame| frame.id)?;
+        if last_id + 1 < oldest {
+            return None;
+        }
+
+        Some(buffer.frames.iter()
+            .filter(|frame| frame.id > last_id)
+            .cloned()
+            .collect())
+    }
+}
@@ -0,0 +1,255 @@
+//! Clientes WebSocket conectados: cola de envío acotada y contadores por cliente
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use axum::extract::ws::{close_code, CloseFrame, Message};
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use crate::broadcast_ws::access::{AccessGrant, AccessRole};
+use crate::broadcast_ws::topics::TopicFilter;
+
+/// Máximo de mensajes pendientes por cliente antes de descartar
+pub const CLIENT_QUEUE_CAPACITY: usize = 256;
+
+// Cola de envío acotada de un cliente, consumida por su tarea de escritura
+pub struct ClientQueue {
+    messages: Mutex<VecDeque<Message>>,
+    notify: Notify,
+    closed: AtomicBool,
+    closed_notify: Notify,           // Avisa a la conexión de que debe terminar
+}
+
+impl ClientQueue {
+    fn new() -> Self {
+        Self {
+            messages: Mutex::new(VecDeque::with_capacity(CLIENT_QUEUE_CAPACITY)),
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+            closed_notify: Notify::new(),
+        }
+    }
+
+    /// Encola un mensaje; devuelve false si la cola está llena o cerrada
+    pub fn push(&self, message: Message) -> bool {
+        let mut messages = self.messages.lock().unwrap_or_else(|e| e.into_inner());
+        if self.is_closed() || messages.len() >= CLIENT_QUEUE_CAPACITY {
+            return false;
+        }
+        messages.push_back(message);
+        drop(messages);
+        self.notify.notify_one();
+        true
+    }
+
+    /// Descarta lo pendiente y lo reemplaza (usado tras un retraso con la instantánea).
+    /// Una cola cerrada conserva lo que tenga (p. ej. el frame de cierre).
+    pub fn replace_all(&self, replacement: Vec<Message>) -> usize {
+        let mut messages = self.messages.lock().unwrap_or_else(|e| e.into_inner());
+        if self.is_closed() {
+            return 0;
+        }
+        let discarded = messages.len();
+        messages.clear();
+        messages.extend(replacement);
+        drop(messages);
+        self.notify.notify_one();
+        discarded
+    }
+
+    /// Espera el siguiente mensaje; devuelve None cuando la cola se cierra
+    pub async fn pop(&self) -> Option<Message> {
+        loop {
+            {
+                let mut messages = self.messages.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(message) = messages.pop_front() {
+                    return Some(message);
+                }
+            }
+            if self.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Cierra la cola y despierta a la tarea de escritura y a quien espere en `closed`
+    pub fn close(&self) {
+        // Bajo el cerrojo para que ningún `push` en curso cuele un mensaje tras el cierre
+        let messages = self.messages.lock().unwrap_or_else(|e| e.into_inner());
+        self.closed.store(true, Ordering::Release);
+        drop(messages);
+        self.notify.notify_one();
+        self.closed_notify.notify_waiters();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Espera a que la cola se cierre
+    pub async fn closed(&self) {
+        loop {
+            // Se registra antes de comprobar para no perder un cierre entre medias
+            let notified = self.closed_notify.notified();
+            if self.is_closed() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+// Cliente WebSocket conectado
+pub struct WsClient {
+    pub id: u64,
+    pub remote_addr: SocketAddr,
+    pub ring_id: String,             // Ring cuyo feed recibe
+    pub connected_at: u64,
+    pub grant: AccessGrant,
+    pub topics: Mutex<TopicFilter>,
+    pub queue: ClientQueue,
+    pub sent_messages: AtomicU64,
+    pub dropped_messages: AtomicU64,
+    pub lag_events: AtomicU64,
+    pub delay_ms: AtomicU64,         // Retardo aplicado a los mensajes difundidos
+    pub held_messages: AtomicU64,    // Mensajes retenidos por el retardo
+}
+
+impl WsClient {
+    /// Indica si el cliente está suscrito al tema de un mensaje
+    pub fn accepts(&self, topic: Option<&str>) -> bool {
+        self.topics.lock().unwrap_or_else(|e| e.into_inner()).matches(topic)
+    }
+
+    /// Cierra la conexión: el frame de cierre sustituye a lo pendiente y la cola no admite más
+    pub fn disconnect(&self, code: u16, reason: &str) {
+        self.queue.replace_all(vec![Message::Close(Some(CloseFrame {
+            code,
+            reason: reason.to_string().into(),
+        }))]);
+        self.queue.close();
+    }
+
+    /// Registra un mensaje descartado por cola llena o retraso
+    pub fn record_dropped(&self, count: u64) {
+        self.dropped_messages.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn status(&self) -> ClientStatus {
+        ClientStatus {
+            client_id: self.id,
+            remote_addr: self.remote_addr.to_string(),
+            ring_id: self.ring_id.clone(),
+            connected_at: self.connected_at,
+            role: self.grant.role,
+            label: self.grant.label.clone(),
+            topics: self.topics.lock().unwrap_or_else(|e| e.into_inner()).topics(),
+            queued_messages: self.queue.pending(),
+            sent_messages: self.sent_messages.load(Ordering::Relaxed),
+            dropped_messages: self.dropped_messages.load(Ordering::Relaxed),
+            lag_events: self.lag_events.load(Ordering::Relaxed),
+            delay_ms: self.delay_ms.load(Ordering::Relaxed),
+            held_messages: self.held_messages.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// Contadores de un cliente expuestos en el estado del sistema
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientStatus {
+    pub client_id: u64,
+    pub remote_addr: String,
+    pub ring_id: String,
+    pub connected_at: u64,
+    pub role: AccessRole,
+    pub label: String,
+    pub topics: Option<Vec<String>>,
+    pub queued_messages: usize,
+    pub sent_messages: u64,
+    pub dropped_messages: u64,
+    pub lag_events: u64,
+    pub delay_ms: u64,
+    pub held_messages: u64,
+}
+
+// Clientes WebSocket conectados
+pub struct ClientRegistry {
+    clients: Mutex<HashMap<u64, Arc<WsClient>>>,
+    next_client_id: AtomicU64,       // Identificador incremental de clientes
+}
+
+impl Default for ClientRegistry {
+    fn default() -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            next_client_id: AtomicU64::new(1),
+        }
+    }
+}
+
+impl ClientRegistry {
+    /// Registra un nuevo cliente conectado
+    pub fn register(&self, remote_addr: SocketAddr, ring_id: &str, grant: AccessGrant, topics: TopicFilter) -> Arc<WsClient> {
+        let client = Arc::new(WsClient {
+            id: self.next_client_id.fetch_add(1, Ordering::Relaxed),
+            remote_addr,
+            ring_id: ring_id.to_string(),
+            connected_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            grant,
+            topics: Mutex::new(topics),
+            queue: ClientQueue::new(),
+            sent_messages: AtomicU64::new(0),
+            dropped_messages: AtomicU64::new(0),
+            lag_events: AtomicU64::new(0),
+            delay_ms: AtomicU64::new(0),
+            held_messages: AtomicU64::new(0),
+        });
+
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        clients.insert(client.id, client.clone());
+        client
+    }
+
+    /// Elimina un cliente del registro
+    pub fn unregister(&self, client_id: u64) {
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        clients.remove(&client_id);
+    }
+
+    /// Número de clientes conectados
+    pub fn count(&self) -> usize {
+        self.clients.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Desconecta a los clientes que usan un token; devuelve cuántos había
+    pub fn disconnect_with_token(&self, token: &str) -> usize {
+        let clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        let mut disconnected = 0;
+        for client in clients.values() {
+            if client.grant.token.as_deref() == Some(token) {
+                // La tarea de escritura envía el cierre y la conexión deja de atender al cliente
+                client.disconnect(close_code::POLICY, "Access token revoked");
+                disconnected += 1;
+            }
+        }
+        disconnected
+    }
+
+    /// Contadores de todos los clientes conectados
+    pub fn status(&self) -> Vec<ClientStatus> {
+        let clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        let mut status: Vec<ClientStatus> = clients.values().map(|client| client.status()).collect();
+        status.sort_by_key(|client| client.client_id);
+        status
+    }
+}
@@ -0,0 +1,108 @@
+//! Comandos Tauri para controlar el servidor de transmisión
+
+use tauri::State;
+use tracing::{info, warn};
+
+use crate::ble::state::BleManager;
+use crate::broadcast_ws::access::{
+    create_access_token, list_access_tokens, revoke_access_token, AccessRole, AccessToken,
+};
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::broadcast_ws::tls::regenerate_self_signed_certificate;
+use crate::broadcast_ws::settings::{load_server_settings, save_server_settings, ServerSettings};
+use crate::broadcast_ws::server::{start_ws_server, stop_ws_server, ws_server_status, ServerStatus};
+
+/// Obtiene la configuración guardada del servidor
+#[tauri::command]
+pub fn get_broadcast_server_settings() -> Result<ServerSettings, String> {
+    Ok(load_server_settings())
+}
+
+/// Guarda la configuración del servidor (se aplica en el próximo arranque o reinicio)
+#[tauri::command]
+pub fn update_broadcast_server_settings(settings: ServerSettings) -> Result<ServerSettings, String> {
+    save_server_settings(&settings)?;
+    Ok(settings)
+}
+
+/// Arranca el servidor con la configuración guardada
+#[tauri::command]
+pub async fn start_broadcast_server(ble: State<'_, BleManager>) -> Result<ServerStatus, String> {
+    info!("▶️ Comando: Arrancar servidor de transmisión");
+    start_ws_server(ble.hub(), &ble, &load_server_settings()).await
+}
+
+/// Detiene el servidor cerrando las conexiones abiertas
+#[tauri::command]
+pub async fn stop_broadcast_server(hub: State<'_, BroadcastHub>) -> Result<String, String> {
+    info!("⏹️ Comando: Detener servidor de transmisión");
+    if stop_ws_server(&hub).await {
+        Ok("Servidor de transmisión detenido".to_string())
+    } else {
+        Ok("El servidor de transmisión no estaba en marcha".to_string())
+    }
+}
+
+/// Reinicia el servidor aplicando la configuración guardada
+#[tauri::command]
+pub async fn restart_broadcast_server(ble: State<'_, BleManager>) -> Result<ServerStatus, String> {
+    info!("🔄 Comando: Reiniciar servidor de transmisión");
+    stop_ws_server(ble.hub()).await;
+    start_ws_server(ble.hub(), &ble, &load_server_settings()).await
+}
+
+/// Obtiene el estado del servidor y la URL en uso
+#[tauri::command]
+pub async fn get_broadcast_server_status(hub: State<'_, BroadcastHub>) -> Result<ServerStatus, String> {
+    Ok(ws_server_status(&hub, &load_server_settings()).await)
+}
+
+/// Emite un token de acceso para espectadores, jueces o administradores
+#[tauri::command]
+pub fn create_broadcast_token(role: AccessRole, label: String) -> Result<AccessToken, String> {
+    create_access_token(role, &label)
+}
+
+/// Revoca un token y cierra las conexiones que lo estén usando
+#[tauri::command]
+pub fn revoke_broadcast_token(hub: State<'_, BroadcastHub>, token: String) -> Result<String, String> {
+    let revoked = revoke_access_token(&token)?;
+    let disconnected = hub.clients().disconnect_with_token(&token)
+        + hub.sse_clients().disconnect_with_token(&token);
+    Ok(format!("Token '{}' revocado ({} conexiones cerradas)", revoked.label, disconnected))
+}
+
+/// Lista los tokens de acceso vigentes
+#[tauri::command]
+pub fn list_broadcast_tokens() -> Result<Vec<AccessToken>, String> {
+    Ok(list_access_tokens())
+}
+
+/// Genera un nuevo certificado autofirmado (se aplica al reiniciar el servidor)
+#[tauri::command]
+pub fn regenerate_broadcast_certificate() -> Result<String, String> {
+    info!("🔒 Comando: Regenerar certificado TLS");
+    regenerate_self_signed_certificate()
+}
+
+/// Ajusta en vivo el retardo del feed retrasado sin perder mensajes y lo guarda
+#[tauri::command]
+pub fn set_broadcast_delay(hub: State<'_, BroadcastHub>, delay_ms: u64) -> Result<String, String> {
+    hub.delayed_feed().set_delay_ms(delay_ms)?;
+
+    let mut settings = load_server_settings();
+    settings.delayed_feed_ms = delay_ms;
+    if let Err(e) = save_server_settings(&settings) {
+        // El cambio en vivo ya se aplicó; solo no persistirá tras reiniciar
+        warn!(error = %e, "No se pudo guardar el retardo del feed");
+    }
+
+    info!(delay_ms, "⏱️ Retardo del feed de transmisión actualizado");
+    Ok(format!("Retardo del feed retrasado: {} ms", delay_ms))
+}
+
+/// Obtiene el retardo actual del feed retrasado
+#[tauri::command]
+pub fn get_broadcast_delay(hub: State<'_, BroadcastHub>) -> Result<u64, String> {
+    Ok(hub.delayed_feed().delay_ms())
+}
@@ -0,0 +1,267 @@
+//! API REST de control remoto del sistema BLE
+//!
+//! Permite manejar sensores y combate sin la interfaz de operador, por
+//! ejemplo en un servidor de ring sin pantalla: escanear, conectar y
+//! desconectar sensores, cambiar la vista, configurar y avanzar rounds,
+//! activar el modo por turnos, resetear estadísticas y terminar el combate.
+//! Todas las rutas cuelgan de `/api/control` y exigen un token de administrador.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::ble::connection::{connect_device, disconnect_all_devices, disconnect_device, scan_devices};
+use crate::ble::types::CompetitorInfo;
+use crate::bout::ring::ring_or_default;
+use crate::bout::turns::{start_turn_mode_with_defaults, stop_turn_mode};
+use crate::broadcast_ws::access::require_admin;
+use crate::broadcast_ws::api::api_error;
+use crate::broadcast_ws::protocol::BattleConfig;
+use crate::broadcast_ws::server::ServerContext;
+use crate::broadcast_ws::{apply_battle_config, change_view};
+
+// Petición de conexión de un sensor
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ConnectRequest {
+    pub ring_id: Option<String>,
+    pub competitor: Option<CompetitorInfo>, // Sin competidor se conecta con el detector básico
+}
+
+// Petición que solo indica el ring
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RingRequest {
+    pub ring_id: Option<String>,
+}
+
+// Cambio de vista de transmisión
+#[derive(Debug, Deserialize)]
+pub struct ViewRequest {
+    #[serde(default)]
+    pub ring_id: Option<String>,
+    pub view_type: String,
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+// Configuración de batalla de un ring
+#[derive(Debug, Deserialize)]
+pub struct BattleConfigRequest {
+    #[serde(default)]
+    pub ring_id: Option<String>,
+    #[serde(flatten)]
+    pub config: BattleConfig,
+}
+
+// Avance de round; sin `current_round` se pasa al siguiente
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RoundRequest {
+    pub ring_id: Option<String>,
+    pub current_round: Option<u32>,
+}
+
+// Activación del modo por turnos (mismos valores por defecto que la app)
+#[derive(Debug, Deserialize)]
+pub struct TurnModeRequest {
+    #[serde(default)]
+    pub ring_id: Option<String>,
+    pub first_striker: String,
+    #[serde(default)]
+    pub fighters: Option<[String; 2]>,
+    #[serde(default)]
+    pub strikes_per_turn: Option<u32>,
+    #[serde(default)]
+    pub time_limit_ms: Option<u64>,        // 0 desactiva el límite de tiempo
+}
+
+// Fin del combate; `cancelled` lo da por anulado en vez de terminado
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FinishBoutRequest {
+    pub ring_id: Option<String>,
+    pub cancelled: bool,
+}
+
+/// Rutas de control remoto
+pub fn control_router(context: ServerContext) -> Router<ServerContext> {
+    Router::new()
+        .route("/api/control/scan", post(scan))
+        .route("/api/control/devices/disconnect", post(disconnect_all))
+        .route("/api/control/devices/:device_id/connect", post(connect))
+        .route("/api/control/devices/:device_id/disconnect", post(disconnect))
+        .route("/api/control/view", post(view))
+        .route("/api/control/battle-config", post(battle_config))
+        .route("/api/control/round/advance", post(advance_round))
+        .route("/api/control/turns/start", post(start_turns))
+        .route("/api/control/turns/stop", post(stop_turns))
+        .route("/api/control/stats/reset", post(reset_stats))
+        .route("/api/control/bout/finish", post(finish_bout))
+        .route_layer(middleware::from_fn_with_state(context, require_admin))
+}
+
+/// Los fallos de hardware se devuelven como pasarela sin respuesta
+fn ble_error(message: String) -> Response {
+    api_error(StatusCode::BAD_GATEWAY, message)
+}
+
+fn done(message: String) -> Response {
+    Json(serde_json::json!({ "message": message })).into_response()
+}
+
+async fn scan(State(context): State<ServerContext>) -> Response {
+    match scan_devices(&context.ble).await {
+        Ok(devices) => Json(devices).into_response(),
+        Err(e) => ble_error(e),
+    }
+}
+
+async fn connect(
+    State(context): State<ServerContext>,
+    Path(device_id): Path<String>,
+    request: Option<Json<ConnectRequest>>,
+) -> Response {
+    let Json(request) = request.unwrap_or_default();
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    info!(device_id = %device_id, ring_id = %ring_id, "🔗 Control: Conectar dispositivo");
+
+    match connect_device(&context.ble, &ring_id, device_id.clone(), request.competitor).await {
+        Ok(()) => done(format!("Dispositivo {} conectado en el ring {}", device_id, ring_id)),
+        Err(e) => ble_error(e),
+    }
+}
+
+async fn disconnect(State(context): State<ServerContext>, Path(device_id): Path<String>) -> Response {
+    info!(device_id = %device_id, "🔌 Control: Desconectar dispositivo");
+    match disconnect_device(&context.ble, &device_id).await {
+        Ok(()) => done(format!("Dispositivo {} desconectado", device_id)),
+        Err(e) => ble_error(e),
+    }
+}
+
+async fn disconnect_all(State(context): State<ServerContext>) -> Response {
+    info!("🔌 Control: Desconectar todos los dispositivos");
+    match disconnect_all_devices(&context.ble).await {
+        Ok(()) => done("Todos los dispositivos desconectados".to_string()),
+        Err(e) => ble_error(e),
+    }
+}
+
+async fn view(State(context): State<ServerContext>, Json(request): Json<ViewRequest>) -> Response {
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    info!(ring_id = %ring_id, view_type = %request.view_type, "📺 Control: Cambiar vista");
+
+    change_view(&context.ble, &ring_id, &request.view_type, request.data.unwrap_or(serde_json::json!({})));
+    done(format!("Vista {} activa en el ring {}", request.view_type, ring_id))
+}
+
+async fn battle_config(State(context): State<ServerContext>, Json(request): Json<BattleConfigRequest>) -> Response {
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    let config = request.config;
+    if config.rounds == 0 || config.current_round == 0 || config.current_round > config.rounds {
+        return api_error(StatusCode::BAD_REQUEST, format!("Round fuera de rango: {} (1 - {})", config.current_round, config.rounds));
+    }
+    info!(ring_id = %ring_id, mode = %config.mode, rounds = config.rounds, current_round = config.current_round, "⚙️ Control: Configurar batalla");
+
+    apply_battle_config(&context.ble, &ring_id, &config);
+    Json(config).into_response()
+}
+
+async fn advance_round(State(context): State<ServerContext>, request: Option<Json<RoundRequest>>) -> Response {
+    let Json(request) = request.unwrap_or_default();
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    let Some(mut config) = context.hub.ring(&ring_id).views().last_battle_config() else {
+        return api_error(StatusCode::CONFLICT, format!("El ring {} no tiene configuración de batalla", ring_id));
+    };
+
+    let next_round = request.current_round.unwrap_or(config.current_round + 1);
+    if next_round == 0 || next_round > config.rounds {
+        return api_error(StatusCode::CONFLICT, format!("Round fuera de rango: {} (1 - {})", next_round, config.rounds));
+    }
+    info!(ring_id = %ring_id, current_round = next_round, rounds = config.rounds, "⏭️ Control: Avanzar round");
+
+    config.current_round = next_round;
+    apply_battle_config(&context.ble, &ring_id, &config);
+    Json(config).into_response()
+}
+
+async fn start_turns(State(context): State<ServerContext>, Json(request): Json<TurnModeRequest>) -> Response {
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    info!(ring_id = %ring_id, first_striker = %request.first_striker, "🔁 Control: Activar modo por turnos");
+
+    match start_turn_mode_with_defaults(
+        &context.hub,
+        &ring_id,
+        &request.first_striker,
+        request.fighters,
+        request.strikes_per_turn,
+        request.time_limit_ms,
+    ) {
+        Ok(turn) => Json(turn).into_response(),
+        Err(e) => api_error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+async fn stop_turns(State(context): State<ServerContext>, request: Option<Json<RingRequest>>) -> Response {
+    let Json(request) = request.unwrap_or_default();
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    info!(ring_id = %ring_id, "🔁 Control: Desactivar modo por turnos");
+
+    if stop_turn_mode(&context.hub, &ring_id) {
+        done("Modo por turnos desactivado".to_string())
+    } else {
+        done("El modo por turnos no estaba activo".to_string())
+    }
+}
+
+async fn reset_stats(State(context): State<ServerContext>, request: Option<Json<RingRequest>>) -> Response {
+    let Json(request) = request.unwrap_or_default();
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    info!(ring_id = %ring_id, "🔄 Control: Resetear estadísticas máximas");
+
+    context.ble.reset_ring_max_stats(&ring_id);
+    done(format!("Estadísticas máximas del ring {} reseteadas", ring_id))
+}
+
+/// Muestra la pantalla de fin de combate, con lo mismo que hace la app: fin de turnos, recuperación y webhooks
+async fn finish_bout(State(context): State<ServerContext>, request: Option<Json<FinishBoutRequest>>) -> Response {
+    let Json(request) = request.unwrap_or_default();
+    let ring_id = match ring_or_default(request.ring_id) {
+        Ok(ring_id) => ring_id,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    let view_type = if request.cancelled { "combat_cancelled" } else { "combat_finished" };
+    info!(ring_id = %ring_id, view_type = %view_type, "🏁 Control: Terminar combate");
+
+    change_view(&context.ble, &ring_id, view_type, serde_json::json!({}));
+    done(format!("Combate del ring {} terminado", ring_id))
+}
@@ -0,0 +1,166 @@
+//! Retardo de difusión para sincronizar overlays con el vídeo retrasado
+//!
+//! El stream va varios segundos por detrás del ring por la codificación.
+//! Los clientes pueden pedir un retardo fijo (`?delay=ms`) o conectarse al
+//! feed retrasado (`/ws/delayed`, `/sse/delayed`), cuyo retardo se ajusta en
+//! vivo desde la app. Los mensajes retenidos nunca se descartan: al cambiar
+//! el retardo se reprograman según su hora de llegada.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::broadcast_ws::channel::BroadcastFrame;
+
+/// Retardo máximo permitido
+pub const MAX_BROADCAST_DELAY_MS: u64 = 30_000;
+
+/// Mensajes retenidos por cliente antes de adelantar su envío
+const MAX_HELD_FRAMES: usize = 4096;
+
+/// Valida un retardo en milisegundos
+pub fn validate_delay(delay_ms: u64) -> Result<u64, String> {
+    if delay_ms > MAX_BROADCAST_DELAY_MS {
+        return Err(format!(
+            "El retardo máximo es {} ms (pedido: {} ms)",
+            MAX_BROADCAST_DELAY_MS, delay_ms
+        ));
+    }
+    Ok(delay_ms)
+}
+
+// Retardo del feed retrasado, ajustable en vivo
+pub struct DelayedFeed {
+    delay_ms: watch::Sender<u64>,
+}
+
+impl Default for DelayedFeed {
+    fn default() -> Self {
+        Self { delay_ms: watch::channel(0).0 }
+    }
+}
+
+impl DelayedFeed {
+    /// Cambia el retardo del feed retrasado; los clientes conectados lo aplican al momento
+    pub fn set_delay_ms(&self, delay_ms: u64) -> Result<(), String> {
+        let delay_ms = validate_delay(delay_ms)?;
+        self.delay_ms.send_replace(delay_ms);
+        Ok(())
+    }
+
+    /// Retardo actual del feed retrasado
+    pub fn delay_ms(&self) -> u64 {
+        *self.delay_ms.borrow()
+    }
+
+    /// Origen de retardo que sigue a este feed
+    pub fn source(&self) -> DelaySource {
+        DelaySource::Feed(self.delay_ms.subscribe())
+    }
+}
+
+// Origen del retardo de un cliente
+pub enum DelaySource {
+    Fixed(u64),                      // Retardo propio del cliente (0 = en vivo)
+    Feed(watch::Receiver<u64>),      // Sigue el retardo del feed retrasado
+}
+
+impl DelaySource {
+    pub fn live() -> Self {
+        DelaySource::Fixed(0)
+    }
+}
+
+// Cola temporizada de mensajes difundidos de un cliente
+pub struct DelayBuffer {
+    source: DelaySource,
+    held: VecDeque<(Instant, Arc<BroadcastFrame>)>,
+}
+
+impl DelayBuffer {
+    pub fn new(source: DelaySource) -> Self {
+        Self {
+            source,
+            held: VecDeque::new(),
+        }
+    }
+
+    /// Retardo aplicado en este momento
+    pub fn delay_ms(&self) -> u64 {
+        match &self.source {
+            DelaySource::Fixed(delay_ms) => *delay_ms,
+            DelaySource::Feed(rx) => *rx.borrow(),
+        }
+    }
+
+    pub fn held(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Retiene un mensaje; devuelve el que hay que enviar ya: el propio si no hay
+    /// retardo ni nada pendiente, o el más antiguo si la cola está llena
+    pub fn push(&mut self, frame: Arc<BroadcastFrame>) -> Option<Arc<BroadcastFrame>> {
+        if self.held.is_empty() && self.delay_ms() == 0 {
+            return Some(frame);
+        }
+        let overflow = if self.held.len() >= MAX_HELD_FRAMES {
+            self.held.pop_front().map(|(_, oldest)| oldest)
+        } else {
+            None
+        };
+        self.held.push_back((Instant::now(), frame));
+        overflow
+    }
+
+    /// Mensajes cuyo retardo ya se cumplió, en orden
+    pub fn pop_due(&mut self) -> Vec<Arc<BroadcastFrame>> {
+        let delay = Duration::from_millis(self.delay_ms());
+        let now = Instant::now();
+        let mut due = Vec::new();
+
+        while let Some((received_at, _)) = self.held.front() {
+            if *received_at + delay > now {
+                break;
+            }
+            if let Some((_, frame)) = self.held.pop_front() {
+                due.push(frame);
+            }
+        }
+        due
+    }
+
+    /// Momento en que vence el siguiente mensaje retenido
+    pub fn next_due(&self) -> Option<Instant> {
+        let delay = Duration::from_millis(self.delay_ms());
+        self.held.front().map(|(received_at, _)| *received_at + delay)
+    }
+
+    /// Descarta lo retenido (el cliente recibirá una instantánea); devuelve cuántos había
+    pub fn clear(&mut self) -> usize {
+        let discarded = self.held.len();
+        self.held.clear();
+        discarded
+    }
+
+    /// Espera a que cambie el retardo del feed; nunca termina para retardos fijos
+    pub async fn changed(&mut self) {
+        match &mut self.source {
+            DelaySource::Feed(rx) => {
+                if rx.changed().await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+            DelaySource::Fixed(_) => std::future::pending::<()>().await,
+        }
+    }
+}
+
+/// Espera hasta el instante indicado; nunca termina si no hay nada retenido
+pub async fn sleep_until_due(due: Option<Instant>) {
+    match due {
+        Some(due) => tokio::time::sleep_until(due).await,
+        None => std::future::pending::<()>().await,
+    }
+}
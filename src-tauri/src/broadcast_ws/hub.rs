@@ -0,0 +1,201 @@
+//! Centro de difusión: todo el estado del servidor de transmisión
+//!
+//! Agrupa los feeds de cada ring (canal de difusión y última vista enviada),
+//! los clientes conectados, el feed retrasado, los turnos y el servidor en
+//! marcha. Los feeds se crean la primera vez que se difunde en un ring (el
+//! ring por defecto existe siempre); los clientes solo pueden conectarse a
+//! rings que ya existen. La app lo guarda en `tauri::State` y lo pasa a quien necesite
+//! difundir; el aviso al frontend de la app se inyecta como `FrontendEmitter`
+//! para poder sustituirlo.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use serde::Serialize;
+#[cfg(feature = "desktop")]
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+use tracing::error;
+
+use crate::bout::ring::DEFAULT_RING_ID;
+use crate::bout::turns::TurnTracker;
+use crate::broadcast_ws::channel::BroadcastChannel;
+use crate::broadcast_ws::client::ClientRegistry;
+use crate::broadcast_ws::delay::DelayedFeed;
+use crate::broadcast_ws::protocol::ServerMessage;
+use crate::broadcast_ws::server::ServerSlot;
+use crate::broadcast_ws::snapshot::ViewMemory;
+use crate::broadcast_ws::sse::SseRegistry;
+
+/// Destino de los eventos para el frontend de la app de operador
+pub trait FrontendEmitter: Send + Sync {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) -> Result<(), String>;
+}
+
+#[cfg(feature = "desktop")]
+impl<R: tauri::Runtime> FrontendEmitter for AppHandle<R> {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        self.emit(event, payload).map_err(|e| e.to_string())
+    }
+}
+
+// Feed de transmisión de un ring
+#[derive(Default)]
+pub struct RingFeed {
+    channel: BroadcastChannel,
+    views: ViewMemory,
+}
+
+impl RingFeed {
+    pub fn channel(&self) -> &BroadcastChannel {
+        &self.channel
+    }
+
+    pub fn views(&self) -> &ViewMemory {
+        &self.views
+    }
+}
+
+// Centro de difusión compartido (clonarlo comparte el mismo estado)
+#[derive(Clone)]
+pub struct BroadcastHub {
+    inner: Arc<HubInner>,
+}
+
+struct HubInner {
+    emitter: Arc<dyn FrontendEmitter>,
+    rings: Mutex<HashMap<String, Arc<RingFeed>>>, // ring_id -> feed
+    activity: watch::Sender<u64>,    // Mensajes difundidos en cualquier ring
+    clients: ClientRegistry,
+    sse_clients: SseRegistry,
+    connection_slots: AtomicUsize,   // Conexiones WebSocket/SSE abiertas o en curso de apertura
+    delayed_feed: DelayedFeed,
+    turns: TurnTracker,              // Turnos de cada ring y su temporizador
+    server: ServerSlot,
+    static_dir: String,              // Directorio de las pantallas de transmisión
+}
+
+impl BroadcastHub {
+    pub fn new(emitter: Arc<dyn FrontendEmitter>, static_dir: impl Into<String>) -> Self {
+        Self {
+            inner: Arc::new(HubInner {
+                emitter,
+                rings: Mutex::new(HashMap::from([(DEFAULT_RING_ID.to_string(), Arc::default())])),
+                activity: watch::channel(0).0,
+                clients: ClientRegistry::default(),
+                sse_clients: SseRegistry::default(),
+                connection_slots: AtomicUsize::new(0),
+                delayed_feed: DelayedFeed::default(),
+                turns: TurnTracker::default(),
+                server: ServerSlot::default(),
+                static_dir: static_dir.into(),
+            }),
+        }
+    }
+
+    /// Difunde un mensaje a los clientes conectados a un ring
+    pub fn broadcast(&self, ring_id: &str, message: &ServerMessage) {
+        self.ring(ring_id).channel.publish(message);
+        self.inner.activity.send_modify(|count| *count = count.wrapping_add(1));
+    }
+
+    /// Feed de un ring, creado la primera vez que se pide
+    pub fn ring(&self, ring_id: &str) -> Arc<RingFeed> {
+        let mut rings = self.inner.rings.lock().unwrap_or_else(|e| e.into_inner());
+        rings.entry(ring_id.to_string()).or_default().clone()
+    }
+
+    /// Feed de un ring solo si ya existe (una conexión no debe crear rings)
+    pub fn existing_ring(&self, ring_id: &str) -> Option<Arc<RingFeed>> {
+        let rings = self.inner.rings.lock().unwrap_or_else(|e| e.into_inner());
+        rings.get(ring_id).cloned()
+    }
+
+    /// Rings con feed abierto, ordenados
+    pub fn ring_ids(&self) -> Vec<String> {
+        let rings = self.inner.rings.lock().unwrap_or_else(|e| e.into_inner());
+        let mut ring_ids: Vec<String> = rings.keys().cloned().collect();
+        ring_ids.sort();
+        ring_ids
+    }
+
+    /// Aviso de actividad: cambia cada vez que se difunde algo en cualquier ring
+    pub fn activity(&self) -> watch::Receiver<u64> {
+        self.inner.activity.subscribe()
+    }
+
+    /// Emite un evento al frontend de la app de operador
+    pub fn emit_frontend<S: Serialize + ?Sized>(&self, event: &str, payload: &S) {
+        let result = serde_json::to_value(payload)
+            .map_err(|e| e.to_string())
+            .and_then(|payload| self.inner.emitter.emit_json(event, payload));
+        if let Err(e) = result {
+            error!(event = %event, error = %e, "Error emitiendo evento al frontend");
+        }
+    }
+
+    pub fn clients(&self) -> &ClientRegistry {
+        &self.inner.clients
+    }
+
+    pub fn sse_clients(&self) -> &SseRegistry {
+        &self.inner.sse_clients
+    }
+
+    pub fn delayed_feed(&self) -> &DelayedFeed {
+        &self.inner.delayed_feed
+    }
+
+    pub fn turns(&self) -> &TurnTracker {
+        &self.inner.turns
+    }
+
+    /// Referencia que no mantiene vivo el hub (para tareas de fondo)
+    pub fn downgrade(&self) -> WeakBroadcastHub {
+        WeakBroadcastHub { inner: Arc::downgrade(&self.inner) }
+    }
+
+    pub fn static_dir(&self) -> &str {
+        &self.inner.static_dir
+    }
+
+    pub(crate) fn server(&self) -> &ServerSlot {
+        &self.inner.server
+    }
+
+    /// Reserva un hueco para una conexión nueva si no se alcanzó el límite
+    ///
+    /// La comprobación y la reserva son una sola operación atómica, así que
+    /// varias conexiones simultáneas no pueden superar el límite entre ambas.
+    pub fn reserve_connection(&self, max_connections: usize) -> Option<ConnectionSlot> {
+        self.inner.connection_slots
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < max_connections).then_some(count + 1)
+            })
+            .ok()?;
+        Some(ConnectionSlot { hub: self.clone() })
+    }
+}
+
+// Referencia débil al hub
+#[derive(Clone)]
+pub struct WeakBroadcastHub {
+    inner: Weak<HubInner>,
+}
+
+impl WeakBroadcastHub {
+    pub fn upgrade(&self) -> Option<BroadcastHub> {
+        self.inner.upgrade().map(|inner| BroadcastHub { inner })
+    }
+}
+
+// Hueco reservado para una conexión; se libera al descartarlo
+pub struct ConnectionSlot {
+    hub: BroadcastHub,
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.hub.inner.connection_slots.fetch_sub(1, Ordering::AcqRel);
+    }
+}
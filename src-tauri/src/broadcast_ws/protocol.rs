@@ -0,0 +1,234 @@
+//! Protocolo tipado y versionado de mensajes WebSocket
+//!
+//! Todo mensaje que envía el servidor va dentro de un sobre con
+//! `protocol_version`, `type` y `timestamp`. Cualquier cambio incompatible en
+//! la forma de un mensaje debe incrementar `PROTOCOL_VERSION`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ble::types::{CompetitorMaxStats, DefensiveMotionEvent, DeviceHealth, SimpleCombatEvent};
+use crate::bout::types::{CombatLogEntry, TurnEndReason, TurnFoul, TurnState};
+use crate::broadcast_ws::snapshot::StateSnapshot;
+use crate::judge::types::{JudgeAction, JudgeActionRecord};
+use crate::scoring::types::{FighterScore, ScoreEntry};
+
+/// Versión actual del protocolo
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Versión más antigua que el servidor acepta de un cliente
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+// Configuración de batalla enviada a las pantallas de transmisión
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleConfig {
+    pub mode: String, // "time" o "rounds"
+    pub rounds: u32,
+    pub round_duration: Option<u32>, // Solo para modo "time"
+    pub current_round: u32,
+}
+
+// Mensajes que envía el servidor
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    // Saludo inicial al conectar
+    Hello {
+        server: String,
+        server_version: String,
+        min_supported_version: u32,
+    },
+    // Respuesta al saludo de un cliente
+    HelloAck {
+        client_version: u32,
+    },
+    // Estado actual enviado al conectar, antes de los mensajes en vivo
+    Snapshot {
+        data: Box<StateSnapshot>,
+    },
+    BattleConfig {
+        data: BattleConfig,
+    },
+    // El combate pasó a otro round
+    RoundChange {
+        previous_round: Option<u32>,
+        current_round: u32,
+        rounds: u32,
+    },
+    ViewChange {
+        view_type: String,
+        data: serde_json::Value,
+    },
+    CombatEvent {
+        data: SimpleCombatEvent,
+    },
+    MaxStatsUpdate {
+        fighter_id: String,
+        data: CompetitorMaxStats,
+        new_records: Vec<String>,
+    },
+    MaxStatsReset,
+    ScoreUpdate {
+        fighter_id: String,
+        data: FighterScore,
+        entry: Option<ScoreEntry>,
+    },
+    ScoreReset,
+    EventVoided {
+        event_id: String,
+        fighter_id: String,
+        data: CombatLogEntry,
+        issued_by: String,
+    },
+    EventRestored {
+        event_id: String,
+        fighter_id: String,
+        data: CombatLogEntry,
+        issued_by: String,
+    },
+    JudgeAction {
+        data: JudgeActionRecord,
+    },
+    JudgeAuthOk {
+        judge: String,
+    },
+    JudgeAck {
+        data: JudgeActionRecord,
+    },
+    // Cambio de atacante en el modo por turnos
+    TurnChange {
+        reason: TurnEndReason,
+        data: TurnState,
+    },
+    // Golpe del defensor durante el turno del atacante (no puntúa)
+    TurnFoul {
+        data: TurnFoul,
+    },
+    // Bloqueo o encogimiento del defensor durante el turno rival
+    DefensiveMotion {
+        data: DefensiveMotionEvent,
+    },
+    // Conexión, desconexión o cambio de batería de un sensor
+    DeviceStatus {
+        connected: bool,
+        data: DeviceHealth,
+    },
+    // Temas a los que queda suscrito el cliente (None = todos)
+    Subscribed {
+        topics: Option<Vec<String>>,
+    },
+    Pong,
+    // El cliente se quedó atrás y perdió mensajes; le sigue una instantánea nueva
+    Lagged {
+        missed: u64,
+    },
+    Error {
+        code: ErrorCode,
+        message: String,
+    },
+}
+
+// Códigos de error del protocolo
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidMessage,
+    UnsupportedVersion,
+    Unauthorized,
+    Rejected,
+}
+
+impl ServerMessage {
+    /// Construye un mensaje de error
+    pub fn error(code: ErrorCode, message: impl Into<String>) -> Self {
+        ServerMessage::Error { code, message: message.into() }
+    }
+
+    /// Mensaje de saludo con la información del servidor
+    pub fn hello() -> Self {
+        ServerMessage::Hello {
+            server: env!("CARGO_PKG_NAME").to_string(),
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            min_supported_version: MIN_SUPPORTED_PROTOCOL_VERSION,
+        }
+    }
+
+    /// Tema del mensaje para las suscripciones; None se entrega a todos
+    pub fn topic(&self) -> Option<String> {
+        match self {
+            ServerMessage::CombatEvent { data } => Some(format!("combat:{}", data.fighter_id)),
+            ServerMessage::EventVoided { fighter_id, .. }
+            | ServerMessage::EventRestored { fighter_id, .. } => Some(format!("combat:{}", fighter_id)),
+            ServerMessage::MaxStatsUpdate { fighter_id, .. } => Some(format!("stats:{}", fighter_id)),
+            ServerMessage::MaxStatsReset => Some("stats".to_string()),
+            ServerMessage::ScoreUpdate { fighter_id, .. } => Some(format!("score:{}", fighter_id)),
+            ServerMessage::ScoreReset => Some("score".to_string()),
+            ServerMessage::BattleConfig { .. } => Some("config".to_string()),
+            ServerMessage::RoundChange { .. } => Some("config:round".to_string()),
+            ServerMessage::ViewChange { .. } => Some("view".to_string()),
+            ServerMessage::JudgeAction { .. } => Some("judge".to_string()),
+            ServerMessage::TurnChange { .. } => Some("turn".to_string()),
+            ServerMessage::TurnFoul { data } => Some(format!("turn:foul:{}", data.fighter_id)),
+            ServerMessage::DefensiveMotion { data } => Some(format!("turn:defense:{}", data.fighter_id)),
+            ServerMessage::DeviceStatus { data, .. } => Some(format!("device_health:{}", data.device_id)),
+            _ => None,
+        }
+    }
+
+    /// Serializa el mensaje dentro del sobre versionado
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&ServerEnvelope::new(self))
+    }
+
+    /// Serializa el mensaje y devuelve también su `type` (nombre de evento SSE)
+    pub fn to_typed_json(&self) -> Result<(String, String), serde_json::Error> {
+        let value = serde_json::to_value(ServerEnvelope::new(self))?;
+        let message_type = value["type"].as_str().unwrap_or("message").to_string();
+        Ok((message_type, value.to_string()))
+    }
+}
+
+// Sobre común de todos los mensajes del servidor
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerEnvelope<'a> {
+    pub protocol_version: u32,
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub message: &'a ServerMessage,
+}
+
+impl<'a> ServerEnvelope<'a> {
+    pub fn new(message: &'a ServerMessage) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            message,
+        }
+    }
+}
+
+// Mensajes que puede enviar un cliente
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Hello {
+        protocol_version: u32,
+    },
+    Ping,
+    // Confirma el rol de juez; `key` es el token de acceso de la conexión
+    JudgeAuth {
+        key: String,
+    },
+    JudgeAction {
+        #[serde(flatten)]
+        action: JudgeAction,
+    },
+    Subscribe {
+        topics: Vec<String>,
+    },
+    Unsubscribe {
+        topics: Vec<String>,
+    },
+}
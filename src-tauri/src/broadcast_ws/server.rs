@@ -0,0 +1,297 @@
+//! Ciclo de vida del servidor HTTP/WebSocket: arranque, parada y estado
+
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use axum_server::Handle;
+use axum::{http::StatusCode, middleware, routing::get, routing::get_service, Router};
+use serde::Serialize;
+use tokio::{net::TcpListener, sync::watch, sync::Mutex, task::JoinHandle};
+use tower_http::services::ServeDir;
+use tracing::{error, info, warn};
+
+use crate::broadcast_ws::{ws_upgrade, ws_upgrade_delayed, ws_upgrade_ring, ws_upgrade_ring_delayed};
+use crate::broadcast_ws::api::{api_router, read_only_cors};
+use crate::broadcast_ws::control::control_router;
+use crate::broadcast_ws::access::require_viewer;
+use crate::broadcast_ws::sse::{sse_handler, sse_handler_delayed, sse_handler_ring, sse_handler_ring_delayed};
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::broadcast_ws::settings::ServerSettings;
+use crate::broadcast_ws::tls::{load_tls_material, primary_lan_ip};
+use crate::ble::state::BleManager;
+
+/// Tiempo máximo de espera al cierre ordenado del servidor
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Servidor en ejecución, si lo hay
+#[derive(Default)]
+pub struct ServerSlot(Mutex<Option<RunningServer>>);
+
+struct RunningServer {
+    addr: SocketAddr,
+    settings: ServerSettings,
+    started_at: u64,
+    tls_fingerprint: Option<String>,
+    shutdown_tx: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+// Estado compartido por las rutas del servidor
+#[derive(Clone)]
+pub struct ServerContext {
+    pub hub: BroadcastHub,
+    pub ble: BleManager,
+    pub shutdown: watch::Receiver<bool>,
+    pub settings: ServerSettings,
+}
+
+// Estado del servidor expuesto a la app de operador
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerStatus {
+    pub running: bool,
+    pub url: Option<String>,         // URL para abrir las pantallas de transmisión
+    pub bind_address: String,
+    pub port: Option<u16>,           // Puerto realmente en uso
+    pub requested_port: u16,         // Puerto configurado
+    pub serve_static: bool,
+    pub started_at: Option<u64>,
+    pub connected_clients: usize,
+    pub tls: bool,
+    pub certificate_fingerprint: Option<String>, // Huella SHA-256 para confiar en el certificado
+}
+
+/// Arranca el servidor; si el puerto está ocupado prueba los siguientes
+pub async fn start_ws_server(hub: &BroadcastHub, ble: &BleManager, settings: &ServerSettings) -> Result<ServerStatus, String> {
+    settings.validate()?;
+    let mut running = hub.server().0.lock().await;
+
+    if let Some(server) = running.as_ref() {
+        if !server.task.is_finished() {
+            return Err(format!("WS server already running at {}", server.addr));
+        }
+    }
+
+    // El feed retrasado arranca con el retardo guardado
+    hub.delayed_feed().set_delay_ms(settings.delayed_feed_ms)?;
+
+    // Cargar el certificado antes de ocupar el puerto
+    let tls = if settings.tls_enabled {
+        Some(load_tls_material(settings).await?)
+    } else {
+        None
+    };
+
+    let ip: IpAddr = settings.bind_address.parse()
+        .map_err(|e| format!("Invalid bind address: {}", e))?;
+    let listener = bind_with_fallback(ip, settings.port, settings.port_fallback_attempts).await?;
+    let addr = listener.local_addr().map_err(|e| format!("Failed reading local addr: {}", e))?;
+    let listener = listener.into_std().map_err(|e| format!("Failed preparing listener: {}", e))?;
+
+    let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+    let app = build_router(settings.serve_static, ServerContext {
+        hub: hub.clone(),
+        ble: ble.clone(),
+        shutdown: shutdown_rx.clone(),
+        settings: settings.clone(),
+    });
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+
+    // Cierre ordenado: el mismo aviso que reciben los sockets detiene el servidor
+    let handle = Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        let _ = shutdown_rx.changed().await;
+        shutdown_handle.graceful_shutdown(Some(SHUTDOWN_TIMEOUT));
+    });
+
+    info!(%addr, serve_static = settings.serve_static, tls = tls.is_some(), "🚀 WS/HTTP server starting");
+
+    let tls_fingerprint = tls.as_ref().map(|tls| tls.fingerprint.clone());
+    let task = match tls {
+        Some(tls) => {
+            let server = axum_server::from_tcp_rustls(listener, tls.config).handle(handle);
+            tokio::spawn(async move {
+                log_server_exit(addr, server.serve(service).await);
+            })
+        }
+        None => {
+            let server = axum_server::from_tcp(listener).handle(handle);
+            tokio::spawn(async move {
+                log_server_exit(addr, server.serve(service).await);
+            })
+        }
+    };
+
+    let server = RunningServer {
+        addr,
+        settings: settings.clone(),
+        started_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        tls_fingerprint,
+        shutdown_tx,
+        task,
+    };
+    let status = running_status(hub, &server);
+    *running = Some(server);
+
+    Ok(status)
+}
+
+/// Detiene el servidor cerrando las conexiones abiertas; devuelve false si no estaba en marcha
+pub async fn stop_ws_server(hub: &BroadcastHub) -> bool {
+    let Some(server) = hub.server().0.lock().await.take() else {
+        return false;
+    };
+
+    info!(addr = %server.addr, "🛑 Stopping WS/HTTP server");
+    let _ = server.shutdown_tx.send(true);
+
+    let mut task = server.task;
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT * 2, &mut task).await.is_err() {
+        warn!("WS/HTTP server did not stop in time; aborting");
+        task.abort();
+    }
+    true
+}
+
+/// Estado actual del servidor
+pub async fn ws_server_status(hub: &BroadcastHub, settings: &ServerSettings) -> ServerStatus {
+    let running = hub.server().0.lock().await;
+    match running.as_ref() {
+        Some(server) if !server.task.is_finished() => running_status(hub, server),
+        _ => ServerStatus {
+            running: false,
+            url: None,
+            bind_address: settings.bind_address.clone(),
+            port: None,
+            requested_port: settings.port,
+            serve_static: settings.serve_static,
+            started_at: None,
+            connected_clients: 0,
+            tls: settings.tls_enabled,
+            certificate_fingerprint: None,
+        },
+    }
+}
+
+fn log_server_exit(addr: SocketAddr, result: std::io::Result<()>) {
+    if let Err(e) = result {
+        error!(error = %e, "Server error");
+    }
+    info!(%addr, "🛑 WS/HTTP server stopped");
+}
+
+fn running_status(hub: &BroadcastHub, server: &RunningServer) -> ServerStatus {
+    ServerStatus {
+        running: true,
+        url: Some(server_url(server.addr, server.tls_fingerprint.is_some())),
+        bind_address: server.settings.bind_address.clone(),
+        port: Some(server.addr.port()),
+        requested_port: server.settings.port,
+        serve_static: server.settings.serve_static,
+        started_at: Some(server.started_at),
+        connected_clients: hub.clients().count(),
+        tls: server.tls_fingerprint.is_some(),
+        certificate_fingerprint: server.tls_fingerprint.clone(),
+    }
+}
+
+/// URL para abrir las pantallas: la IP de escucha, o la de la red local si se
+/// escucha en todas las interfaces (0.0.0.0 no es navegable)
+fn server_url(addr: SocketAddr, tls: bool) -> String {
+    let host = if addr.ip().is_unspecified() {
+        primary_lan_ip()
+            .filter(|ip| !ip.is_unspecified() && !ip.is_loopback())
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    } else {
+        addr.ip()
+    };
+    let scheme = if tls { "https" } else { "http" };
+    format!("{}://{}", scheme, SocketAddr::new(host, addr.port()))
+}
+
+/// Intenta escuchar en el puerto pedido y en los siguientes si están ocupados
+async fn bind_with_fallback(ip: IpAddr, port: u16, attempts: u16) -> Result<TcpListener, String> {
+    let mut last_error = String::new();
+
+    for offset in 0..=attempts {
+        let Some(candidate) = port.checked_add(offset) else {
+            break;
+        };
+        let addr = SocketAddr::new(ip, candidate);
+
+        match TcpListener::bind(addr).await {
+            Ok(listener) => {
+                if offset > 0 {
+                    warn!(requested_port = port, port = candidate, "⚠️ Requested port busy; using fallback");
+                }
+                return Ok(listener);
+            }
+            // Windows devuelve PermissionDenied para puertos reservados
+            Err(e) if matches!(e.kind(), ErrorKind::AddrInUse | ErrorKind::PermissionDenied) => {
+                warn!(%addr, error = %e, "Port unavailable");
+                last_error = e.to_string();
+            }
+            Err(e) => return Err(format!("Failed binding {}: {}", addr, e)),
+        }
+    }
+
+    Err(format!(
+        "No free port between {} and {}: {}",
+        port,
+        port.saturating_add(attempts),
+        last_error
+    ))
+}
+
+fn build_router(serve_static: bool, context: ServerContext) -> Router {
+    let router = Router::new()
+        .route("/ws", get(ws_upgrade))
+        .route("/ws/delayed", get(ws_upgrade_delayed))
+        .route("/ws/ring/:ring_id", get(ws_upgrade_ring))
+        .route("/ws/ring/:ring_id/delayed", get(ws_upgrade_ring_delayed))
+        .route(
+            "/sse",
+            get(sse_handler)
+                .route_layer(middleware::from_fn_with_state(context.clone(), require_viewer))
+                .layer(read_only_cors()),
+        )
+        .route(
+            "/sse/delayed",
+            get(sse_handler_delayed)
+                .route_layer(middleware::from_fn_with_state(context.clone(), require_viewer))
+                .layer(read_only_cors()),
+        )
+        .route(
+            "/sse/ring/:ring_id",
+            get(sse_handler_ring)
+                .route_layer(middleware::from_fn_with_state(context.clone(), require_viewer))
+                .layer(read_only_cors()),
+        )
+        .route(
+            "/sse/ring/:ring_id/delayed",
+            get(sse_handler_ring_delayed)
+                .route_layer(middleware::from_fn_with_state(context.clone(), require_viewer))
+                .layer(read_only_cors()),
+        )
+        .merge(api_router(context.clone()))
+        .merge(control_router(context.clone()));
+
+    let router = if serve_static {
+        let static_dir = context.hub.static_dir().to_string();
+        info!(static_dir = %static_dir, "Serving broadcast screens");
+        router.fallback_service(
+            get_service(ServeDir::new(static_dir)).handle_error(|e| async move {
+                error!(error = %e, "Static file service error");
+                (StatusCode::INTERNAL_SERVER_ERROR, "static service error")
+            }),
+        )
+    } else {
+        router
+    };
+
+    router.with_state(context)
+}
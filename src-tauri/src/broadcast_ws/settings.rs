@@ -0,0 +1,169 @@
+//! Configuración persistente del servidor de transmisión
+
+use std::path::{Path, PathBuf};
+use once_cell::sync::OnceCell;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::broadcast_ws::delay::validate_delay;
+
+/// Nombre del archivo de configuración dentro del directorio de la app
+const SETTINGS_FILE_NAME: &str = "broadcast_server.json";
+
+// Directorio de configuración de la app (se fija durante el setup)
+static CONFIG_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+// Configuración del servidor HTTP/WebSocket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub port: u16,                   // Puerto preferido
+    pub bind_address: String,        // "0.0.0.0" para todas las interfaces, "127.0.0.1" solo local
+    pub serve_static: bool,          // Servir las pantallas de transmisión
+    pub port_fallback_attempts: u16, // Puertos siguientes a probar si el preferido está ocupado
+    pub require_token: bool,         // Exigir token de acceso para conectarse a /ws
+    pub allow_local_viewers: bool,   // Permitir espectadores sin token desde esta misma máquina
+    pub max_connections: usize,      // Máximo de conexiones WebSocket simultáneas
+    pub tls_enabled: bool,           // Servir HTTPS/WSS
+    pub tls_cert_path: Option<PathBuf>, // Certificado PEM propio (si falta se usa uno autofirmado)
+    pub tls_key_path: Option<PathBuf>,  // Clave privada PEM del certificado propio
+    pub delayed_feed_ms: u64,        // Retardo del feed retrasado (/ws/delayed, /sse/delayed)
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            port: 8080,
+            bind_address: "0.0.0.0".to_string(),
+            serve_static: true,
+            port_fallback_attempts: 10,
+            require_token: true,
+            allow_local_viewers: true,
+            max_connections: 50,
+            tls_enabled: false,
+            tls_cert_path: None,
+            tls_key_path: None,
+            delayed_feed_ms: 0,
+        }
+    }
+}
+
+impl ServerSettings {
+    /// Verifica que los valores sean utilizables
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("El puerto debe ser mayor que 0".to_string());
+        }
+        self.bind_address.parse::<std::net::IpAddr>()
+            .map_err(|e| format!("Dirección de escucha inválida '{}': {}", self.bind_address, e))?;
+        if self.max_connections == 0 {
+            return Err("El máximo de conexiones debe ser mayor que 0".to_string());
+        }
+        validate_delay(self.delayed_feed_ms)?;
+        if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
+            return Err("Se deben indicar el certificado y la clave TLS juntos".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Fija el directorio donde se guarda la configuración
+pub fn init_settings_dir(config_dir: PathBuf) {
+    let _ = CONFIG_DIR.set(config_dir);
+}
+
+/// Ruta de un archivo dentro del directorio de configuración
+pub fn config_file_path(file_name: &str) -> Option<PathBuf> {
+    CONFIG_DIR.get().map(|dir| dir.join(file_name))
+}
+
+/// Lee un archivo JSON del directorio de configuración (None si no existe)
+pub fn read_config_file<T: DeserializeOwned>(file_name: &str) -> Result<Option<T>, String> {
+    let Some(path) = config_file_path(file_name) else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Guarda un valor como JSON en el directorio de configuración
+pub fn write_config_file<T: Serialize>(file_name: &str, value: &T) -> Result<PathBuf, String> {
+    save_config_file(file_name, value, |path, content| std::fs::write(path, content))
+}
+
+/// Como `write_config_file`, pero legible solo por el usuario de la app (secretos)
+pub fn write_private_config_file<T: Serialize>(file_name: &str, value: &T) -> Result<PathBuf, String> {
+    save_config_file(file_name, value, write_private_file)
+}
+
+fn save_config_file<T: Serialize>(
+    file_name: &str,
+    value: &T,
+    write: impl FnOnce(&Path, &[u8]) -> std::io::Result<()>,
+) -> Result<PathBuf, String> {
+    let path = config_file_path(file_name)
+        .ok_or_else(|| "Directorio de configuración no inicializado".to_string())?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creando {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write(&path, content.as_bytes())
+        .map_err(|e| format!("Error guardando {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Escribe un archivo legible solo por el usuario de la app (claves y tokens)
+#[cfg(unix)]
+pub fn write_private_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    use std::fs::{OpenOptions, Permissions};
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` solo se aplica al crear; un archivo anterior puede tener otros permisos
+    file.set_permissions(Permissions::from_mode(0o600))?;
+    file.write_all(content)
+}
+
+/// En Windows el directorio de datos de la app ya es privado del usuario
+#[cfg(not(unix))]
+pub fn write_private_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, content)
+}
+
+/// Carga la configuración guardada o la de por defecto
+pub fn load_server_settings() -> ServerSettings {
+    let settings = read_config_file::<ServerSettings>(SETTINGS_FILE_NAME)
+        .and_then(|settings| match settings {
+            Some(settings) => settings.validate().map(|_| Some(settings)),
+            None => Ok(None),
+        });
+
+    match settings {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(e) => {
+            warn!(error = %e, "Invalid server settings, using defaults");
+            ServerSettings::default()
+        }
+    }
+}
+
+/// Guarda la configuración en disco
+pub fn save_server_settings(settings: &ServerSettings) -> Result<(), String> {
+    settings.validate()?;
+    let path = write_config_file(SETTINGS_FILE_NAME, settings)?;
+    info!(path = %path.display(), "💾 Server settings saved");
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! Estado actual del combate para clientes que se conectan a mitad de combate
+
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::ble::types::CompetitorMaxStats;
+use crate::ble::state::BleManager;
+use crate::bout::types::{CombatLogEntry, TurnState};
+use crate::bout::turns::current_turn;
+use crate::bout::event_log::recent_combat_events;
+use crate::scoring::types::FighterScore;
+use crate::scoring::state::ring_score_tallies;
+use crate::broadcast_ws::protocol::BattleConfig;
+
+/// Número de eventos recientes incluidos en la instantánea
+pub const SNAPSHOT_RECENT_EVENTS: usize = 20;
+
+// Última configuración de batalla y última vista enviadas
+#[derive(Default)]
+pub struct ViewMemory {
+    battle_config: Mutex<Option<BattleConfig>>,
+    active_view: Mutex<Option<ActiveView>>,
+}
+
+// Vista de transmisión activa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveView {
+    pub view_type: String,
+    pub data: serde_json::Value,
+}
+
+// Dispositivo conectado incluido en la instantánea
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectedDeviceInfo {
+    pub device_id: String,
+    pub device_name: String,
+}
+
+// Instantánea del estado actual del combate de un ring
+#[derive(Debug, Clone, Serialize)]
+pub struct StateSnapshot {
+    pub ring_id: String,
+    pub battle_config: Option<BattleConfig>,
+    pub active_view: Option<ActiveView>,
+    pub max_stats: Vec<CompetitorMaxStats>,
+    pub scores: Vec<FighterScore>,
+    pub connected_devices: Vec<ConnectedDeviceInfo>,
+    pub recent_events: Vec<CombatLogEntry>,
+    pub turn: Option<TurnState>,    // Solo en el modo por turnos
+}
+
+impl ViewMemory {
+    /// Recuerda la última configuración de batalla enviada y devuelve la anterior
+    pub fn remember_battle_config(&self, config: &BattleConfig) -> Option<BattleConfig> {
+        self.battle_config.lock()
+            .map(|mut last| last.replace(config.clone()))
+            .unwrap_or_default()
+    }
+
+    /// Recuerda la última vista enviada
+    pub fn remember_active_view(&self, view_type: &str, data: &serde_json::Value) {
+        if let Ok(mut view) = self.active_view.lock() {
+            *view = Some(ActiveView {
+                view_type: view_type.to_string(),
+                data: data.clone(),
+            });
+        }
+    }
+
+    /// Última configuración de batalla enviada
+    pub fn last_battle_config(&self) -> Option<BattleConfig> {
+        self.battle_config.lock()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    /// Última vista activa enviada
+    pub fn active_view(&self) -> Option<ActiveView> {
+        self.active_view.lock()
+            .map(|view| view.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Construye la instantánea del estado actual de un ring
+pub fn build_state_snapshot(ble: &BleManager, ring_id: &str) -> StateSnapshot {
+    let feed = ble.hub().ring(ring_id);
+    let battle_config = feed.views().last_battle_config();
+    let active_view = feed.views().active_view();
+
+    let connected_devices = ble.connected_devices_health().into_iter()
+        .filter(|device| device.ring_id == ring_id)
+        .map(|device| ConnectedDeviceInfo {
+            device_id: device.device_id,
+            device_name: device.device_name,
+        })
+        .collect();
+
+    StateSnapshot {
+        ring_id: ring_id.to_string(),
+        battle_config,
+        active_view,
+        max_stats: ble.ring_max_stats(ring_id),
+        scores: ring_score_tallies(ring_id),
+        connected_devices,
+        recent_events: recent_combat_events(ring_id, None, SNAPSHOT_RECENT_EVENTS),
+        turn: current_turn(ble.hub(), ring_id),
+    }
+}
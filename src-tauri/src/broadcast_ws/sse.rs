@@ -0,0 +1,348 @@
+//! Endpoint Server-Sent Events para overlays sencillos basados en `EventSource`
+//!
+//! Difunde los mismos mensajes que `/ws`, usando el `type` de cada mensaje
+//! como nombre de evento y su identificador secuencial como `id`. Al
+//! reconectar, el navegador envía `Last-Event-ID` y se reenvían los mensajes
+//! perdidos; si ya no están en el búfer se envía una instantánea. Cada ring
+//! tiene su feed en `/sse/ring/:ring_id`; `/sse` sigue al ring por defecto.
+//! Los streams abiertos se registran con su token para cerrarlos al revocarlo.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    response::{IntoResponse, Response},
+};
+use futures::Stream;
+use tokio::sync::{broadcast, broadcast::error::RecvError, watch, Notify};
+use tracing::{info, warn};
+
+use crate::broadcast_ws::access::AccessGrant;
+use crate::broadcast_ws::api::api_error;
+use crate::ble::state::BleManager;
+use crate::broadcast_ws::channel::BroadcastFrame;
+use crate::bout::ring::{validate_ring_id, DEFAULT_RING_ID};
+use crate::broadcast_ws::hub::{BroadcastHub, ConnectionSlot, RingFeed};
+use crate::broadcast_ws::protocol::ServerMessage;
+use crate::broadcast_ws::server::ServerContext;
+use crate::broadcast_ws::snapshot::build_state_snapshot;
+use crate::broadcast_ws::topics::TopicFilter;
+use crate::broadcast_ws::delay::{sleep_until_due, DelayBuffer};
+use crate::broadcast_ws::parse_delay_source;
+
+// Stream SSE abierto con el token que lo autorizó
+pub struct SseSession {
+    id: u64,
+    token: Option<String>,           // None para espectadores locales sin token
+    revoked: AtomicBool,
+    notify: Notify,
+}
+
+impl SseSession {
+    fn is_revoked(&self) -> bool {
+        self.revoked.load(Ordering::Acquire)
+    }
+
+    /// Espera a que se revoque el token del stream
+    async fn revoked(&self) {
+        loop {
+            // Se registra antes de comprobar para no perder una revocación entre medias
+            let notified = self.notify.notified();
+            if self.is_revoked() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+// Streams SSE abiertos
+pub struct SseRegistry {
+    sessions: Mutex<HashMap<u64, Arc<SseSession>>>,
+    next_session_id: AtomicU64,
+}
+
+impl Default for SseRegistry {
+    fn default() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            next_session_id: AtomicU64::new(1),
+        }
+    }
+}
+
+impl SseRegistry {
+    fn register(&self, grant: &AccessGrant) -> Arc<SseSession> {
+        let session = Arc::new(SseSession {
+            id: self.next_session_id.fetch_add(1, Ordering::Relaxed),
+            token: grant.token.clone(),
+            revoked: AtomicBool::new(false),
+            notify: Notify::new(),
+        });
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.insert(session.id, session.clone());
+        session
+    }
+
+    fn unregister(&self, session_id: u64) {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.remove(&session_id);
+    }
+
+    /// Termina los streams que usan un token; devuelve cuántos había
+    pub fn disconnect_with_token(&self, token: &str) -> usize {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut disconnected = 0;
+        for session in sessions.values() {
+            if session.token.as_deref() == Some(token) {
+                session.revoked.store(true, Ordering::Release);
+                session.notify.notify_waiters();
+                disconnected += 1;
+            }
+        }
+        disconnected
+    }
+}
+
+// Libera el hueco de conexión y el registro del cliente SSE cuando el stream se descarta
+struct SseClientGuard {
+    hub: BroadcastHub,
+    session: Arc<SseSession>,
+    _slot: ConnectionSlot,
+}
+
+impl Drop for SseClientGuard {
+    fn drop(&mut self) {
+        self.hub.sse_clients().unregister(self.session.id);
+        info!("🔌 SSE client disconnected");
+    }
+}
+
+// Estado del stream de un cliente SSE
+struct SseStream {
+    ble: BleManager,
+    ring_id: String,
+    feed: Arc<RingFeed>,
+    rx: broadcast::Receiver<Arc<BroadcastFrame>>,
+    shutdown: watch::Receiver<bool>,
+    pending: VecDeque<Event>,
+    topics: TopicFilter,
+    delay: DelayBuffer,
+    last_id: u64,                    // Último id entregado (evita duplicar lo reenviado)
+    session: Arc<SseSession>,
+    _guard: SseClientGuard,
+}
+
+/// Abre el stream SSE, reanudando desde `Last-Event-ID` si se indica
+pub async fn sse_handler(
+    State(context): State<ServerContext>,
+    Extension(grant): Extension<AccessGrant>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    open_sse_stream(context, grant, DEFAULT_RING_ID.to_string(), query, headers, false)
+}
+
+/// Feed SSE retrasado: sigue el retardo ajustable desde la app
+pub async fn sse_handler_delayed(
+    State(context): State<ServerContext>,
+    Extension(grant): Extension<AccessGrant>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    open_sse_stream(context, grant, DEFAULT_RING_ID.to_string(), query, headers, true)
+}
+
+/// Feed SSE de un ring concreto
+pub async fn sse_handler_ring(
+    State(context): State<ServerContext>,
+    Extension(grant): Extension<AccessGrant>,
+    Path(ring_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    open_sse_stream(context, grant, ring_id, query, headers, false)
+}
+
+/// Feed SSE retrasado de un ring concreto
+pub async fn sse_handler_ring_delayed(
+    State(context): State<ServerContext>,
+    Extension(grant): Extension<AccessGrant>,
+    Path(ring_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    open_sse_stream(context, grant, ring_id, query, headers, true)
+}
+
+fn open_sse_stream(
+    context: ServerContext,
+    grant: AccessGrant,
+    ring_id: String,
+    query: HashMap<String, String>,
+    headers: HeaderMap,
+    delayed_feed: bool,
+) -> Response {
+    let Some(slot) = context.hub.reserve_connection(context.settings.max_connections) else {
+        warn!(max = context.settings.max_connections, "⛔ SSE connection limit reached");
+        return api_error(StatusCode::SERVICE_UNAVAILABLE, "Connection limit reached");
+    };
+    if let Err(e) = validate_ring_id(&ring_id) {
+        return api_error(StatusCode::BAD_REQUEST, e);
+    }
+
+    let topics = match TopicFilter::parse_list(query.get("topics").map(String::as_str).unwrap_or_default()) {
+        Ok(topics) => topics,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+    let mut delay = match parse_delay_source(&context.hub, &query, delayed_feed) {
+        Ok(source) => DelayBuffer::new(source),
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, e),
+    };
+
+    // `?last_event_id=` permite reanudar a clientes que no envían la cabecera
+    let last_event_id = headers.get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| query.get("last_event_id").map(String::as_str))
+        .and_then(|value| value.trim().parse::<u64>().ok());
+
+    let Some(feed) = context.hub.existing_ring(&ring_id) else {
+        warn!(ring_id = %ring_id, "⛔ SSE connection to unknown ring rejected");
+        return api_error(StatusCode::NOT_FOUND, format!("El ring {} no existe", ring_id));
+    };
+
+    // Suscribirse antes de leer el búfer para no perder nada entre medias
+    let channel = feed.channel();
+    let rx = channel.subscribe();
+    let mut pending = VecDeque::new();
+    let mut last_id = channel.last_frame_id();
+
+    match last_event_id.and_then(|last_event_id| channel.frames_since(last_event_id)) {
+        Some(frames) => {
+            info!(ring_id = %ring_id, resumed_from = ?last_event_id, replayed = frames.len(), "📡 SSE client resumed");
+            if let Some(frame) = frames.last() {
+                last_id = frame.id;
+            }
+            // Lo reenviado también pasa por el retardo del cliente
+            pending.extend(frames.into_iter()
+                .filter(|frame| topics.matches(frame.topic.as_deref()))
+                .filter_map(|frame| delay.push(frame))
+                .map(|frame| frame_event(&frame)));
+        }
+        None => {
+            info!(ring_id = %ring_id, requested = ?last_event_id, "📡 SSE client connected");
+            pending.push_back(message_event(&ServerMessage::hello(), last_id));
+            pending.extend(delayed_snapshot(&context.ble, &ring_id, &mut delay, last_id));
+        }
+    }
+
+    let session = context.hub.sse_clients().register(&grant);
+    let state = SseStream {
+        ble: context.ble.clone(),
+        ring_id,
+        feed: feed.clone(),
+        rx,
+        shutdown: context.shutdown.clone(),
+        pending,
+        topics,
+        delay,
+        last_id,
+        session: session.clone(),
+        _guard: SseClientGuard {
+            hub: context.hub.clone(),
+            session,
+            _slot: slot,
+        },
+    };
+
+    Sse::new(sse_stream(state))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn sse_stream(state: SseStream) -> impl Stream<Item = Result<Event, Infallible>> {
+    futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if state.session.is_revoked() {
+                info!("⛔ SSE access revoked; ending stream");
+                return None;
+            }
+            if let Some(event) = state.pending.pop_front() {
+                return Some((Ok(event), state));
+            }
+
+            let next_due = state.delay.next_due();
+            tokio::select! {
+                msg = state.rx.recv() => match msg {
+                    Ok(frame) => {
+                        // Ya reenviado desde el búfer al reanudar
+                        if frame.id <= state.last_id {
+                            continue;
+                        }
+                        state.last_id = frame.id;
+                        if !state.topics.matches(frame.topic.as_deref()) {
+                            continue;
+                        }
+                        if let Some(frame) = state.delay.push(frame) {
+                            return Some((Ok(frame_event(&frame)), state));
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!(missed, "🐢 SSE client lagged; snapshot resent");
+                        state.last_id = state.feed.channel().last_frame_id();
+                        state.delay.clear();
+                        state.pending.push_back(message_event(&ServerMessage::Lagged { missed }, state.last_id));
+                        let snapshot = delayed_snapshot(&state.ble, &state.ring_id, &mut state.delay, state.last_id);
+                        state.pending.extend(snapshot);
+                    }
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = sleep_until_due(next_due) => {
+                    let due = state.delay.pop_due();
+                    state.pending.extend(due.iter().map(|frame| frame_event(frame)));
+                }
+                _ = state.delay.changed() => {
+                    let due = state.delay.pop_due();
+                    state.pending.extend(due.iter().map(|frame| frame_event(frame)));
+                }
+                _ = state.session.revoked() => continue,
+                _ = state.shutdown.changed() => return None,
+            }
+        }
+    })
+}
+
+/// Instantánea del ring detrás del retardo del cliente; devuelve el evento si sale ya
+fn delayed_snapshot(ble: &BleManager, ring_id: &str, delay: &mut DelayBuffer, last_id: u64) -> Option<Event> {
+    let snapshot = ServerMessage::Snapshot { data: Box::new(build_state_snapshot(ble, ring_id)) };
+    let frame = BroadcastFrame::direct(&snapshot, last_id)?;
+    delay.push(frame).map(|frame| frame_event(&frame))
+}
+
+/// Evento SSE de un mensaje difundido
+fn frame_event(frame: &BroadcastFrame) -> Event {
+    Event::default()
+        .id(frame.id.to_string())
+        .event(&frame.message_type)
+        .data(&frame.payload)
+}
+
+/// Evento SSE de un mensaje dirigido solo a este cliente.
+/// Lleva el id del último mensaje difundido para que la reanudación siga desde ahí.
+fn message_event(message: &ServerMessage, last_id: u64) -> Event {
+    match message.to_typed_json() {
+        Ok((message_type, payload)) => Event::default()
+            .id(last_id.to_string())
+            .event(message_type)
+            .data(payload),
+        Err(e) => {
+            warn!(error = %e, "Failed to serialize SSE message");
+            Event::default().comment("serialization error")
+        }
+    }
+}
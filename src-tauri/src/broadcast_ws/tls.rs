@@ -0,0 +1,121 @@
+//! Certificados TLS del servidor de transmisión
+//!
+//! Si el operador no indica certificado y clave propios, se genera uno
+//! autofirmado la primera vez y se guarda en el directorio de datos de la app.
+//! La huella SHA-256 se muestra para poder confiar en él en cada dispositivo.
+
+use std::net::{IpAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use axum_server::tls_rustls::RustlsConfig;
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::broadcast_ws::settings::{write_private_file, ServerSettings};
+
+/// Archivos del certificado autofirmado dentro del directorio TLS
+const CERT_FILE_NAME: &str = "broadcast-cert.pem";
+const KEY_FILE_NAME: &str = "broadcast-key.pem";
+
+// Directorio donde se guarda el certificado autofirmado (se fija durante el setup)
+static TLS_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+// Configuración TLS lista para el servidor
+pub struct TlsMaterial {
+    pub config: RustlsConfig,
+    pub fingerprint: String,         // SHA-256 del certificado en formato AA:BB:...
+}
+
+/// Fija el directorio de datos donde guardar el certificado autofirmado
+pub fn init_tls_dir(data_dir: PathBuf) {
+    let _ = TLS_DIR.set(data_dir.join("tls"));
+}
+
+/// Carga el certificado configurado o el autofirmado (generándolo si no existe)
+pub async fn load_tls_material(settings: &ServerSettings) -> Result<TlsMaterial, String> {
+    // rustls se compila sin proveedor por defecto; se usa ring
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let (cert_pem, key_pem, self_signed) = match (&settings.tls_cert_path, &settings.tls_key_path) {
+        (Some(cert_path), Some(key_path)) => (read_pem(cert_path)?, read_pem(key_path)?, false),
+        (None, None) => {
+            let (cert_pem, key_pem) = ensure_self_signed_certificate(false)?;
+            (cert_pem, key_pem, true)
+        }
+        _ => return Err("Se deben indicar el certificado y la clave TLS juntos".to_string()),
+    };
+
+    let fingerprint = certificate_fingerprint(&cert_pem)?;
+    let config = RustlsConfig::from_pem(cert_pem, key_pem)
+        .await
+        .map_err(|e| format!("Certificado o clave TLS inválidos: {}", e))?;
+
+    info!(fingerprint = %fingerprint, self_signed, "🔒 TLS certificate loaded");
+    Ok(TlsMaterial { config, fingerprint })
+}
+
+/// Genera de nuevo el certificado autofirmado y devuelve su huella
+pub fn regenerate_self_signed_certificate() -> Result<String, String> {
+    let (cert_pem, _) = ensure_self_signed_certificate(true)?;
+    certificate_fingerprint(&cert_pem)
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Error leyendo {}: {}", path.display(), e))
+}
+
+/// Devuelve el certificado autofirmado guardado o genera uno nuevo
+fn ensure_self_signed_certificate(force: bool) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let dir = TLS_DIR.get()
+        .ok_or_else(|| "Directorio de datos no inicializado".to_string())?;
+    let cert_path = dir.join(CERT_FILE_NAME);
+    let key_path = dir.join(KEY_FILE_NAME);
+
+    if !force && cert_path.exists() && key_path.exists() {
+        return Ok((read_pem(&cert_path)?, read_pem(&key_path)?));
+    }
+
+    let mut names = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+    if let Some(ip) = primary_lan_ip() {
+        names.push(ip.to_string());
+    }
+
+    let certified = rcgen::generate_simple_self_signed(names.clone())
+        .map_err(|e| format!("Error generando certificado autofirmado: {}", e))?;
+    let cert_pem = certified.cert.pem();
+    let key_pem = certified.key_pair.serialize_pem();
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Error creando {}: {}", dir.display(), e))?;
+    std::fs::write(&cert_path, &cert_pem)
+        .map_err(|e| format!("Error guardando {}: {}", cert_path.display(), e))?;
+    write_private_file(&key_path, key_pem.as_bytes())
+        .map_err(|e| format!("Error guardando {}: {}", key_path.display(), e))?;
+
+    info!(path = %cert_path.display(), names = ?names, "🔒 Self-signed certificate generated");
+    Ok((cert_pem.into_bytes(), key_pem.into_bytes()))
+}
+
+/// Huella SHA-256 del primer certificado del PEM
+fn certificate_fingerprint(cert_pem: &[u8]) -> Result<String, String> {
+    let cert = rustls_pemfile::certs(&mut &cert_pem[..])
+        .next()
+        .ok_or_else(|| "El archivo no contiene ningún certificado".to_string())?
+        .map_err(|e| format!("Certificado inválido: {}", e))?;
+
+    let digest = Sha256::digest(cert.as_ref());
+    Ok(digest.iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+/// IP de la interfaz con salida a la red local (no envía paquetes)
+pub(crate) fn primary_lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    if let Err(e) = socket.connect("192.168.0.1:80") {
+        warn!(error = %e, "Could not determine LAN IP");
+        return None;
+    }
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
@@ -0,0 +1,99 @@
+//! Suscripciones por tema de los clientes del servidor de transmisión
+//!
+//! Cada mensaje difundido tiene un tema como `combat:fighter_1`, `stats` o
+//! `device_health`. Suscribirse a un tema raíz (`combat`) recibe también
+//! todos sus subtemas, y un mensaje de un tema raíz (`stats`, p. ej. el reset
+//! de estadísticas) llega a quien sigue alguno de sus subtemas. Los mensajes
+//! sin tema (saludo, instantánea, errores) se entregan siempre.
+
+use std::collections::BTreeSet;
+
+/// Temas raíz válidos
+pub const TOPIC_ROOTS: &[&str] = &[
+    "combat",
+    "stats",
+    "score",
+    "config",
+    "view",
+    "judge",
+    "turn",
+    "device_health",
+];
+
+// Filtro de temas de un cliente; None recibe todo
+#[derive(Debug, Clone, Default)]
+pub struct TopicFilter {
+    topics: Option<BTreeSet<String>>,
+}
+
+impl TopicFilter {
+    /// Construye el filtro a partir de una lista separada por comas (`?topics=`)
+    pub fn parse_list(list: &str) -> Result<Self, String> {
+        let topics: Vec<String> = list.split(',')
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let mut filter = Self::default();
+        if !topics.is_empty() {
+            filter.subscribe(&topics)?;
+        }
+        Ok(filter)
+    }
+
+    /// Añade temas; el primer subscribe deja de recibir todo
+    pub fn subscribe(&mut self, topics: &[String]) -> Result<(), String> {
+        let validated = topics.iter()
+            .map(|topic| validate_topic(topic))
+            .collect::<Result<Vec<String>, String>>()?;
+        self.topics.get_or_insert_with(BTreeSet::new).extend(validated);
+        Ok(())
+    }
+
+    /// Quita temas de la suscripción explícita; sin ella (se recibe todo) no hay nada que quitar
+    pub fn unsubscribe(&mut self, topics: &[String]) -> Result<(), String> {
+        let Some(subscribed) = self.topics.as_mut() else {
+            return Err("No hay suscripción explícita: suscríbete solo a los temas que quieras recibir".to_string());
+        };
+        for topic in topics {
+            subscribed.remove(topic.trim());
+        }
+        Ok(())
+    }
+
+    /// Indica si un mensaje con el tema dado debe entregarse
+    pub fn matches(&self, topic: Option<&str>) -> bool {
+        let (Some(subscribed), Some(topic)) = (self.topics.as_ref(), topic) else {
+            return true;
+        };
+
+        // Vale en los dos sentidos: el tema suscrito contiene al del mensaje o al revés
+        subscribed.iter().any(|pattern| {
+            topic == pattern || is_subtopic(topic, pattern) || is_subtopic(pattern, topic)
+        })
+    }
+
+    /// Temas suscritos (None si recibe todo)
+    pub fn topics(&self) -> Option<Vec<String>> {
+        self.topics.as_ref().map(|topics| topics.iter().cloned().collect())
+    }
+}
+
+/// Indica si `topic` cuelga de `parent` (`stats:fighter_1` de `stats`)
+fn is_subtopic(topic: &str, parent: &str) -> bool {
+    topic.strip_prefix(parent).is_some_and(|rest| rest.starts_with(':'))
+}
+
+fn validate_topic(topic: &str) -> Result<String, String> {
+    let topic = topic.trim();
+    let root = topic.split(':').next().unwrap_or_default();
+    if !TOPIC_ROOTS.contains(&root) {
+        return Err(format!(
+            "Tema desconocido '{}' (válidos: {})",
+            topic,
+            TOPIC_ROOTS.join(", ")
+        ));
+    }
+    Ok(topic.to_string())
+}
@@ -0,0 +1,172 @@
+//! Servidor de ring sin interfaz
+//!
+//! Arranca el sistema BLE y el servidor de transmisión desde un archivo de
+//! configuración, sin ventana ni `tauri::Builder`, para llevar un ring desde
+//! un equipo Linux sin pantalla. Se maneja con la API HTTP/WebSocket
+//! (`/api/control` con token de administrador) y escribe los logs en JSON,
+//! un objeto por línea. La app de Tauri sigue siendo la interfaz del operador;
+//! sin ella el servidor se compila con
+//! `cargo build --bin ring-server --no-default-features`.
+
+pub mod config;
+
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::ble::actor::run_fanout;
+use crate::ble::connection::connect_device;
+use crate::ble::state::BleManager;
+use crate::ble::transport::BluestTransport;
+use crate::bout::recovery::{
+    discard_recoverable_bout, init_recovery_dir, load_recoverable_bout, recoverable_bout_summary, restore_recoverable_bout,
+    run_recovery_writer,
+};
+use crate::broadcast_ws::access::{load_access_tokens, provision_access_token, AccessRole};
+use crate::broadcast_ws::hub::{BroadcastHub, FrontendEmitter};
+use crate::broadcast_ws::server::start_ws_server;
+use crate::broadcast_ws::settings::{init_settings_dir, load_server_settings};
+use crate::broadcast_ws::tls::init_tls_dir;
+use crate::shutdown::shutdown_app;
+use config::{ConfiguredDevice, HeadlessConfig};
+
+// Sin app de operador los eventos para su frontend solo quedan en el log
+struct LogOnlyFrontend;
+
+impl FrontendEmitter for LogOnlyFrontend {
+    fn emit_json(&self, event: &str, _payload: serde_json::Value) -> Result<(), String> {
+        debug!(event = %event, "Evento de frontend sin interfaz");
+        Ok(())
+    }
+}
+
+/// Inicializa el logging en JSON (una línea por evento, apto para journald o Loki)
+pub fn init_json_tracing(default_filter: &str) {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| default_filter.into()),
+        )
+        .with(tracing_subscriber::fmt::layer().json())
+        .init();
+}
+
+/// Arranca el servidor de ring y lo mantiene hasta recibir Ctrl+C o SIGTERM
+///
+/// Espera el logging ya inicializado con `init_json_tracing`, para que sus
+/// errores también salgan en JSON.
+pub async fn run(config_path: &Path, config: HeadlessConfig) -> Result<(), String> {
+    // Las tareas que la app lanza con el runtime de Tauri comparten este runtime
+    #[cfg(feature = "desktop")]
+    tauri::async_runtime::set(tokio::runtime::Handle::current());
+    info!(config = %config_path.display(), "🥊 Iniciando servidor de ring sin interfaz");
+
+    // Mismos archivos de configuración y datos que usa la app
+    init_settings_dir(config.config_dir.clone());
+    load_access_tokens();
+    crate::webhooks::state::load_webhooks();
+    if let Some(token) = &config.admin_token {
+        provision_access_token(token, AccessRole::Admin, "ring-server")?;
+    }
+    init_tls_dir(config.data_dir.clone());
+    init_recovery_dir(config.data_dir.clone());
+
+    // Estado compartido: centro de difusión sin frontend y sistema BLE con el transporte real
+    let hub = BroadcastHub::new(Arc::new(LogOnlyFrontend), config.static_dir.to_string_lossy());
+    let ble = BleManager::new(Arc::new(BluestTransport::default()), hub.clone());
+
+    tokio::spawn(run_fanout(ble.clone()));
+    tokio::spawn(run_recovery_writer(ble.clone()));
+    tokio::spawn(crate::webhooks::delivery::run_delivery_worker());
+
+    let settings = config.server.clone().unwrap_or_else(load_server_settings);
+    let status = start_ws_server(&hub, &ble, &settings).await?;
+    info!(url = %status.url.unwrap_or_default(), "WebSocket server running");
+
+    start_integrations(&hub);
+
+    // Sin nadie a quien preguntar, el combate interrumpido se restaura o se descarta según la configuración
+    load_recoverable_bout(&hub);
+    if recoverable_bout_summary().is_some() {
+        if config.resume_bout {
+            match restore_recoverable_bout(&ble).await {
+                Ok(result) => info!(
+                    restored_events = result.restored_events,
+                    reconnected = result.reconnected_devices.len(),
+                    failed = result.failed_devices.len(),
+                    "💾 Combate interrumpido restaurado"
+                ),
+                Err(e) => error!(error = %e, "No se pudo restaurar el combate interrumpido"),
+            }
+        } else {
+            discard_recoverable_bout();
+            info!("🗑️ Combate interrumpido descartado");
+        }
+    }
+
+    connect_configured_devices(&ble, &config.devices).await;
+
+    wait_for_shutdown_signal().await;
+    shutdown_app(&ble).await;
+    Ok(())
+}
+
+/// Puentes opcionales con los ajustes guardados en el directorio de configuración
+fn start_integrations(hub: &BroadcastHub) {
+    let mqtt_hub = hub.clone();
+    tokio::spawn(async move {
+        let settings = crate::mqtt::settings::load_mqtt_settings();
+        if let Err(e) = crate::mqtt::publisher::start_mqtt_bridge(&mqtt_hub, &settings).await {
+            error!("No se pudo iniciar el puente MQTT: {}", e);
+        }
+    });
+
+    let osc_hub = hub.clone();
+    tokio::spawn(async move {
+        let settings = crate::osc::settings::load_osc_settings();
+        if let Err(e) = crate::osc::sender::start_osc_sender(&osc_hub, &settings).await {
+            error!("No se pudo iniciar el envío OSC: {}", e);
+        }
+    });
+
+    let obs_hub = hub.clone();
+    tokio::spawn(async move {
+        let settings = crate::obs::settings::load_obs_settings();
+        if let Err(e) = crate::obs::controller::start_obs_controller(&obs_hub, &settings).await {
+            error!("No se pudo iniciar la integración con OBS: {}", e);
+        }
+    });
+}
+
+/// Conecta los sensores de la configuración que no haya reconectado ya la recuperación
+async fn connect_configured_devices(ble: &BleManager, devices: &[ConfiguredDevice]) {
+    let connected = ble.connected_device_ids();
+    for device in devices.iter().filter(|device| !connected.contains(&device.device_id)) {
+        if let Err(e) = connect_device(ble, &device.ring_id, device.device_id.clone(), device.competitor.clone()).await {
+            warn!(device_id = %device.device_id, ring_id = %device.ring_id, error = %e, "No se pudo conectar el dispositivo configurado");
+        }
+    }
+}
+
+/// Espera Ctrl+C o, en Unix, SIGTERM (parada de systemd)
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => warn!(error = %e, "No se pudo escuchar SIGTERM; solo se atiende Ctrl+C"),
+        }
+    }
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!(error = %e, "No se pudo escuchar Ctrl+C");
+    }
+}
@@ -0,0 +1,88 @@
+//! Archivo de configuración del servidor de ring sin interfaz (TOML)
+//!
+//! Las rutas relativas se resuelven desde la carpeta del propio archivo, para
+//! que la configuración se pueda mover junto con sus datos.
+
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+
+use crate::ble::types::CompetitorInfo;
+use crate::bout::ring::{default_ring_id, validate_ring_id};
+use crate::broadcast_ws::settings::ServerSettings;
+
+// Configuración completa del servidor de ring
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeadlessConfig {
+    pub config_dir: PathBuf,             // Tokens, webhooks y ajustes de MQTT/OSC/OBS (mismos archivos que la app)
+    pub data_dir: PathBuf,               // Recuperación de combates y certificados TLS
+    #[serde(default = "default_static_dir")]
+    pub static_dir: PathBuf,             // Pantallas de transmisión
+    #[serde(default = "default_log_filter")]
+    pub log_filter: String,              // Filtro de logs si no hay RUST_LOG
+    #[serde(default)]
+    pub admin_token: Option<String>,     // Token de administrador para `/api/control`
+    #[serde(default = "default_resume_bout")]
+    pub resume_bout: bool,               // Restaurar sin preguntar el combate de una sesión interrumpida
+    #[serde(default)]
+    pub server: Option<ServerSettings>,  // Sin esta sección se usan los ajustes guardados en `config_dir`
+    #[serde(default)]
+    pub devices: Vec<ConfiguredDevice>,  // Sensores a conectar al arrancar
+}
+
+// Sensor que se conecta al arrancar
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfiguredDevice {
+    pub device_id: String,
+    #[serde(default = "default_ring_id")]
+    pub ring_id: String,
+    #[serde(default)]
+    pub competitor: Option<CompetitorInfo>, // Sin competidor se usa el detector básico
+}
+
+fn default_static_dir() -> PathBuf {
+    PathBuf::from("static/dist")
+}
+
+fn default_log_filter() -> String {
+    "beat_hard_combat=info,warn".to_string()
+}
+
+fn default_resume_bout() -> bool {
+    true
+}
+
+impl HeadlessConfig {
+    /// Lee y valida el archivo de configuración
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Error leyendo configuración {}: {}", path.display(), e))?;
+        let mut config: HeadlessConfig = toml::from_str(&content)
+            .map_err(|e| format!("Error parseando configuración TOML: {}", e))?;
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for dir in [&mut config.config_dir, &mut config.data_dir, &mut config.static_dir] {
+            if dir.is_relative() {
+                *dir = base_dir.join(&*dir);
+            }
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Verifica que los valores de la configuración sean coherentes
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(server) = &self.server {
+            server.validate()?;
+        }
+        for device in &self.devices {
+            if device.device_id.trim().is_empty() {
+                return Err("Cada dispositivo necesita un device_id".to_string());
+            }
+            validate_ring_id(&device.ring_id)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+//! Módulo de jueces - Ajustes manuales del combate enviados por WebSocket
+
+pub mod types;
+pub mod state;
+pub mod actions;
+pub mod session;
+#[cfg(feature = "desktop")]
+pub mod commands;
@@ -0,0 +1,145 @@
+//! Validación y aplicación de las acciones de los jueces
+
+use tracing::{info, warn};
+
+use crate::judge::types::{JudgeAction, JudgeActionRecord, JudgeResult};
+use crate::judge::state::get_judge_action_log_state;
+use crate::ble::state::BleManager;
+use crate::bout::event_log::{find_combat_event, set_combat_event_voided};
+use crate::scoring::engine::{emit_score_update, record_score_entry};
+use crate::scoring::types::{FighterScore, ScoreEntry, ScoreEntryKind};
+use crate::broadcast_ws::protocol::ServerMessage;
+
+// Máximo de puntos que un juez puede ajustar en una sola acción
+const MAX_POINTS_PER_ACTION: i32 = 10;
+
+/// Valida una acción, la aplica al marcador del ring, la registra y la retransmite
+pub fn apply_judge_action(
+    ble: &BleManager,
+    ring_id: &str,
+    judge: &str,
+    action: JudgeAction,
+) -> JudgeResult<JudgeActionRecord> {
+    validate_judge_action(ring_id, &action)?;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    // Las anulaciones notifican el marcador desde el registro de eventos
+    let manual_entry = match &action {
+        JudgeAction::AddPoint { fighter_id, points } => {
+            Some(record_manual_entry(ring_id, judge, fighter_id, ScoreEntryKind::ManualPoint, *points, None, timestamp)?)
+        }
+        JudgeAction::RemovePoint { fighter_id, points } => {
+            Some(record_manual_entry(ring_id, judge, fighter_id, ScoreEntryKind::ManualPoint, -*points, None, timestamp)?)
+        }
+        JudgeAction::Foul { fighter_id, reason } => {
+            Some(record_manual_entry(ring_id, judge, fighter_id, ScoreEntryKind::Foul, 0, reason.clone(), timestamp)?)
+        }
+        JudgeAction::Penalty { fighter_id, points, reason } => {
+            Some(record_manual_entry(ring_id, judge, fighter_id, ScoreEntryKind::Penalty, -*points, reason.clone(), timestamp)?)
+        }
+        JudgeAction::VoidHit { event_id } => {
+            set_combat_event_voided(ble, ring_id, event_id, true, judge)?;
+            None
+        }
+        JudgeAction::RestoreHit { event_id } => {
+            set_combat_event_voided(ble, ring_id, event_id, false, judge)?;
+            None
+        }
+    };
+
+    let record = JudgeActionRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        judge: judge.to_string(),
+        ring_id: ring_id.to_string(),
+        action,
+        timestamp,
+    };
+
+    // Auditoría
+    {
+        let log = get_judge_action_log_state();
+        let mut log = log.lock()
+            .map_err(|e| format!("Error accediendo a la auditoría de jueces: {}", e))?;
+        log.entry(ring_id.to_string()).or_default().push(record.clone());
+    }
+    info!(judge = %judge, ring_id = %ring_id, action = ?record.action, "⚖️ Acción de juez aplicada");
+
+    // Retransmitir a los espectadores del ring
+    ble.hub().broadcast(ring_id, &ServerMessage::JudgeAction { data: record.clone() });
+    if let Some((entry, score)) = manual_entry {
+        emit_score_update(&score, Some(&entry), ble.hub());
+    }
+
+    Ok(record)
+}
+
+/// Comprueba que la acción sea aplicable antes de modificar el marcador
+fn validate_judge_action(ring_id: &str, action: &JudgeAction) -> JudgeResult<()> {
+    match action {
+        JudgeAction::AddPoint { fighter_id, points }
+        | JudgeAction::RemovePoint { fighter_id, points }
+        | JudgeAction::Penalty { fighter_id, points, .. } => {
+            validate_fighter_id(fighter_id)?;
+            if !(1..=MAX_POINTS_PER_ACTION).contains(points) {
+                return Err(format!("Los puntos deben estar entre 1 y {}", MAX_POINTS_PER_ACTION));
+            }
+            Ok(())
+        }
+        JudgeAction::Foul { fighter_id, .. } => validate_fighter_id(fighter_id),
+        JudgeAction::VoidHit { event_id } | JudgeAction::RestoreHit { event_id } => {
+            let entry = find_combat_event(ring_id, event_id)
+                .ok_or_else(|| format!("No existe el golpe {} en el ring {}", event_id, ring_id))?;
+            let voiding = matches!(action, JudgeAction::VoidHit { .. });
+            if entry.voided == voiding {
+                let state = if voiding { "anulado" } else { "vigente" };
+                return Err(format!("El golpe {} ya está {}", event_id, state));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Verifica el formato "fighter_N" del identificador de peleador
+fn validate_fighter_id(fighter_id: &str) -> JudgeResult<()> {
+    let valid = fighter_id.strip_prefix("fighter_")
+        .is_some_and(|id| id.parse::<u8>().is_ok());
+
+    if valid {
+        Ok(())
+    } else {
+        warn!(fighter_id = %fighter_id, "Identificador de peleador inválido en acción de juez");
+        Err(format!("Identificador de peleador inválido: {}", fighter_id))
+    }
+}
+
+/// Registra una entrada manual en el marcador
+fn record_manual_entry(
+    ring_id: &str,
+    judge: &str,
+    fighter_id: &str,
+    kind: ScoreEntryKind,
+    points: i32,
+    reason: Option<String>,
+    timestamp: u64,
+) -> JudgeResult<(ScoreEntry, FighterScore)> {
+    let entry = ScoreEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        ring_id: ring_id.to_string(),
+        fighter_id: fighter_id.to_string(),
+        kind,
+        scored: true,
+        points,
+        bonus_points: 0,
+        issued_by: Some(judge.to_string()),
+        reason,
+        voided: false,
+        timestamp,
+    };
+
+    let score = record_score_entry(entry.clone(), "")?;
+    Ok((entry, score))
+}
@@ -0,0 +1,18 @@
+//! Comandos Tauri para la gestión de jueces
+//!
+//! Los jueces se autentican con un token de acceso con rol de juez
+//! (`create_broadcast_token`); revocarlo cierra también sus conexiones.
+
+use crate::bout::ring::ring_or_default;
+use crate::judge::types::JudgeActionRecord;
+use crate::judge::state::get_judge_action_log_state;
+
+/// Obtiene la auditoría de acciones aplicadas por los jueces en el combate en curso de un ring
+#[tauri::command]
+pub fn get_judge_action_log(ring_id: Option<String>) -> Result<Vec<JudgeActionRecord>, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    let log = get_judge_action_log_state();
+    let log = log.lock()
+        .map_err(|e| format!("Error accediendo a la auditoría de jueces: {}", e))?;
+    Ok(log.get(&ring_id).cloned().unwrap_or_default())
+}
@@ -0,0 +1,47 @@
+//! Manejo de mensajes de jueces recibidos por WebSocket
+
+use tracing::{info, warn};
+
+use crate::judge::types::JudgeAction;
+use crate::judge::actions::apply_judge_action;
+use crate::ble::state::BleManager;
+use crate::broadcast_ws::access::AccessGrant;
+use crate::broadcast_ws::protocol::{ErrorCode, ServerMessage};
+
+// Estado de autenticación de una conexión WebSocket
+#[derive(Debug, Default)]
+pub struct JudgeSession {
+    pub judge: Option<String>,       // Nombre del juez autenticado
+}
+
+/// Confirma la identidad de juez de la conexión
+///
+/// Los jueces se identifican con el token de acceso con el que conectan; la clave
+/// solo se acepta si es ese mismo token, para que revocarlo cierre la sesión.
+pub fn authenticate_judge(key: &str, grant: &AccessGrant, session: &mut JudgeSession) -> ServerMessage {
+    if grant.role.can_judge() && grant.token.as_deref() == Some(key) {
+        info!(judge = %grant.label, "🔑 Juez autenticado por WebSocket");
+        session.judge = Some(grant.label.clone());
+        return ServerMessage::JudgeAuthOk { judge: grant.label.clone() };
+    }
+
+    warn!(label = %grant.label, "Intento de autenticación de juez con clave distinta del token de la conexión");
+    ServerMessage::error(ErrorCode::Unauthorized, "Clave de juez inválida: conéctate con tu token de juez")
+}
+
+/// Aplica una acción enviada por un juez autenticado al ring de su conexión
+pub fn submit_judge_action(
+    ble: &BleManager,
+    ring_id: &str,
+    action: JudgeAction,
+    session: &JudgeSession,
+) -> ServerMessage {
+    let Some(judge) = session.judge.as_deref() else {
+        return ServerMessage::error(ErrorCode::Unauthorized, "No autenticado como juez");
+    };
+
+    match apply_judge_action(ble, ring_id, judge, action) {
+        Ok(record) => ServerMessage::JudgeAck { data: record },
+        Err(e) => ServerMessage::error(ErrorCode::Rejected, e),
+    }
+}
@@ -0,0 +1,26 @@
+//! Gestión de estado global del sistema de jueces
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+
+use crate::judge::types::JudgeActionRecord;
+
+/// Registro thread-safe de acciones aplicadas por los jueces (ring_id -> acciones en orden)
+type JudgeActionLog = Arc<Mutex<HashMap<String, Vec<JudgeActionRecord>>>>;
+
+// Auditoría de acciones de jueces del combate en curso de cada ring, en orden de llegada
+static JUDGE_ACTION_LOG: Lazy<JudgeActionLog> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Función para obtener la auditoría de acciones
+pub fn get_judge_action_log_state() -> JudgeActionLog {
+    JUDGE_ACTION_LOG.clone()
+}
+
+/// Vacía la auditoría de acciones de jueces de un ring
+pub fn clear_judge_action_log(ring_id: &str) {
+    let log = get_judge_action_log_state();
+    let mut log = log.lock().unwrap_or_else(|e| e.into_inner());
+    log.remove(ring_id);
+}
@@ -0,0 +1,53 @@
+//! Tipos y estructuras para el control de jueces y árbitros
+
+use serde::{Deserialize, Serialize};
+
+// Acción manual de un juez sobre el combate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum JudgeAction {
+    AddPoint {
+        fighter_id: String,
+        #[serde(default = "default_points")]
+        points: i32,
+    },
+    RemovePoint {
+        fighter_id: String,
+        #[serde(default = "default_points")]
+        points: i32,
+    },
+    Foul {
+        fighter_id: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    Penalty {
+        fighter_id: String,
+        points: i32,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    VoidHit {
+        event_id: String,
+    },
+    RestoreHit {
+        event_id: String,
+    },
+}
+
+fn default_points() -> i32 {
+    1
+}
+
+// Registro de auditoría de una acción aplicada
+#[derive(Debug, Clone, Serialize)]
+pub struct JudgeActionRecord {
+    pub id: String,                  // Identificador de la acción (UUID)
+    pub judge: String,               // Nombre del juez que la emitió
+    pub ring_id: String,
+    pub action: JudgeAction,
+    pub timestamp: u64,
+}
+
+// Tipo de resultado para operaciones de jueces
+pub type JudgeResult<T> = Result<T, String>;
@@ -1,19 +1,82 @@
+// Sin la app de escritorio, parte del backend solo la usan los comandos de Tauri
+#![cfg_attr(not(feature = "desktop"), allow(dead_code))]
+
+#[cfg(feature = "desktop")]
+use std::sync::Arc;
+#[cfg(feature = "desktop")]
 use tauri::Manager;
-use tracing::{error};
+#[cfg(feature = "desktop")]
+use tracing::{error, info};
+#[cfg(feature = "desktop")]
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Módulos del proyecto
 mod ble;
 mod broadcast_ws;
+mod scoring;
+mod judge;
+mod bout;
+mod mqtt;
+mod webhooks;
+mod osc;
+mod obs;
+mod shutdown;
+
+// Servidor de ring sin interfaz (binario `ring-server`)
+pub mod headless;
+
+// Estado compartido de la app
+#[cfg(feature = "desktop")]
+use ble::state::BleManager;
+#[cfg(feature = "desktop")]
+use ble::transport::BluestTransport;
+#[cfg(feature = "desktop")]
+use broadcast_ws::hub::BroadcastHub;
 
 // Re-exports de comandos BLE
+#[cfg(feature = "desktop")]
 use ble::commands::*;
 
+// Re-exports de comandos de puntuación
+#[cfg(feature = "desktop")]
+use scoring::commands::*;
+
+// Re-exports de comandos de jueces
+#[cfg(feature = "desktop")]
+use judge::commands::*;
+
+// Re-exports de comandos del registro de combate
+#[cfg(feature = "desktop")]
+use bout::commands::*;
+
 // Re-exports de comandos WebSocket
+#[cfg(feature = "desktop")]
 use broadcast_ws::broadcast_view_change;
+#[cfg(feature = "desktop")]
 use broadcast_ws::broadcast_battle_config;
 
+// Re-exports de comandos del servidor de transmisión
+#[cfg(feature = "desktop")]
+use broadcast_ws::commands::*;
+
+// Re-exports de comandos MQTT
+#[cfg(feature = "desktop")]
+use mqtt::commands::*;
+
+// Re-exports de comandos de webhooks
+#[cfg(feature = "desktop")]
+use webhooks::commands::*;
+
+// Re-exports de comandos OSC
+#[cfg(feature = "desktop")]
+use osc::commands::*;
+
+// Re-exports de comandos de OBS
+#[cfg(feature = "desktop")]
+use obs::commands::*;
+
 /// Inicializa el sistema de logging con tracing
+#[cfg(feature = "desktop")]
 fn init_tracing() {
     tracing_subscriber::registry()
         .with(
@@ -25,6 +88,7 @@ fn init_tracing() {
 }
 
 /// Resuelve la ruta de los archivos estáticos según el entorno
+#[cfg(feature = "desktop")]
 fn resolve_static_path(app: &tauri::App) -> String {
     if cfg!(debug_assertions) {
         // En desarrollo, usar static/dist dentro del directorio src-tauri
@@ -44,6 +108,7 @@ fn resolve_static_path(app: &tauri::App) -> String {
     }
 }
 
+#[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Inicializar logging
@@ -64,24 +129,154 @@ pub fn run() {
             get_system_info,
             cleanup_ble_system_command,
             get_combat_stats,
+            get_defense_detection,
+            update_defense_detection,
+
+            // Comandos de puntuación
+            load_scoring_ruleset,
+            reset_scoring_ruleset,
+            get_scoring_ruleset,
+            get_score_tally,
+            reset_score_tally,
+
+            // Comandos de jueces
+            get_judge_action_log,
+
+            // Comandos del registro de combate
+            get_event_log,
+            void_combat_event,
+            restore_combat_event,
+            clear_event_log,
+            start_turn_based_mode,
+            stop_turn_based_mode,
+            pass_combat_turn,
+            get_combat_turn,
+            get_rings,
+            get_recoverable_bout,
+            restore_bout,
+            discard_recoverable_bout_command,
             
             // Comandos WebSocket
             broadcast_battle_config,
             broadcast_view_change,
+
+            // Comandos del servidor de transmisión
+            get_broadcast_server_settings,
+            update_broadcast_server_settings,
+            start_broadcast_server,
+            stop_broadcast_server,
+            restart_broadcast_server,
+            get_broadcast_server_status,
+            create_broadcast_token,
+            revoke_broadcast_token,
+            list_broadcast_tokens,
+            regenerate_broadcast_certificate,
+            set_broadcast_delay,
+            get_broadcast_delay,
+
+            // Comandos MQTT
+            get_mqtt_settings,
+            update_mqtt_settings,
+            get_mqtt_status,
+
+            // Comandos de webhooks
+            list_webhooks,
+            create_webhook,
+            update_webhook,
+            delete_webhook,
+            list_pending_webhook_deliveries,
+            test_webhook,
+
+            // Comandos OSC
+            get_osc_settings,
+            update_osc_settings,
+            get_osc_status,
+            test_osc_output,
+
+            // Comandos de OBS
+            get_obs_settings,
+            update_obs_settings,
+            get_obs_status,
         ])
         .setup(|app| {
-            // Resolver ruta de archivos estáticos
-            let resource_path = resolve_static_path(app);
-        
+            // Estado compartido: centro de difusión y sistema BLE con el transporte real
+            let hub = BroadcastHub::new(Arc::new(app.handle().clone()), resolve_static_path(app));
+            let ble = BleManager::new(Arc::new(BluestTransport::default()), hub.clone());
+            app.manage(hub.clone());
+            app.manage(ble.clone());
+
+            // Configuración persistente del servidor de transmisión
+            match app.path().app_config_dir() {
+                Ok(config_dir) => {
+                    broadcast_ws::settings::init_settings_dir(config_dir);
+                    broadcast_ws::access::load_access_tokens();
+                    webhooks::state::load_webhooks();
+                }
+                Err(e) => error!("No se pudo resolver el directorio de configuración: {}", e),
+            }
+            match app.path().app_data_dir() {
+                Ok(data_dir) => {
+                    broadcast_ws::tls::init_tls_dir(data_dir.clone());
+                    bout::recovery::init_recovery_dir(data_dir);
+                    bout::recovery::load_recoverable_bout(&hub);
+                }
+                Err(e) => error!("No se pudo resolver el directorio de datos: {}", e),
+            }
+
             // Iniciar servidor WebSocket
+            let server_ble = ble.clone();
+            tauri::async_runtime::spawn(async move {
+                let settings = broadcast_ws::settings::load_server_settings();
+                match broadcast_ws::server::start_ws_server(server_ble.hub(), &server_ble, &settings).await {
+                    Ok(status) => info!("WebSocket server running at {}", status.url.unwrap_or_default()),
+                    Err(e) => error!("Failed to start WebSocket server: {}", e),
+                }
+            });
+
+            // Difusión de lo que detectan los actores de los dispositivos
+            tauri::async_runtime::spawn(ble::actor::run_fanout(ble.clone()));
+
+            // Guardado continuo del combate en curso para recuperarlo tras un cierre inesperado
+            tauri::async_runtime::spawn(bout::recovery::run_recovery_writer(ble.clone()));
+
+            // Repartidor de webhooks (reintenta también las entregas guardadas)
+            tauri::async_runtime::spawn(webhooks::delivery::run_delivery_worker());
+
+            // Iniciar puente MQTT si está habilitado
+            let mqtt_hub = hub.clone();
+            tauri::async_runtime::spawn(async move {
+                let settings = mqtt::settings::load_mqtt_settings();
+                if let Err(e) = mqtt::publisher::start_mqtt_bridge(&mqtt_hub, &settings).await {
+                    error!("No se pudo iniciar el puente MQTT: {}", e);
+                }
+            });
+
+            // Iniciar salida OSC si está habilitada
+            let osc_hub = hub.clone();
+            tauri::async_runtime::spawn(async move {
+                let settings = osc::settings::load_osc_settings();
+                if let Err(e) = osc::sender::start_osc_sender(&osc_hub, &settings).await {
+                    error!("No se pudo iniciar la salida OSC: {}", e);
+                }
+            });
+
+            // Conectar con OBS si está habilitado
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = broadcast_ws::start_ws_server(8080, true, Some(resource_path)).await {
-                    error!("Failed to start WebSocket server: {}", e);
+                let settings = obs::settings::load_obs_settings();
+                if let Err(e) = obs::controller::start_obs_controller(&hub, &settings).await {
+                    error!("No se pudo iniciar la integración con OBS: {}", e);
                 }
             });
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Al salir: desconectar sensores, cerrar el servidor y guardar lo pendiente
+            if let tauri::RunEvent::Exit = event {
+                let ble = app.state::<BleManager>().inner().clone();
+                tauri::async_runtime::block_on(shutdown::shutdown_app(&ble));
+            }
+        });
 }
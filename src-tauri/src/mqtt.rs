@@ -0,0 +1,9 @@
+//! Puente MQTT opcional para controladores de iluminación y marcadores
+//!
+//! Publica en un broker MQTT los mensajes del canal de difusión (golpes,
+//! récords, cambios de round y salud de dispositivos) en temas configurables.
+
+pub mod settings;
+pub mod publisher;
+#[cfg(feature = "desktop")]
+pub mod commands;
@@ -0,0 +1,32 @@
+//! Comandos Tauri para configurar el puente MQTT
+
+use tauri::State;
+use tracing::info;
+
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::mqtt::publisher::{mqtt_status, start_mqtt_bridge, MqttStatus};
+use crate::mqtt::settings::{load_mqtt_settings, save_mqtt_settings, MqttSettings};
+
+/// Obtiene la configuración guardada del puente MQTT (sin la contraseña)
+#[tauri::command]
+pub fn get_mqtt_settings() -> Result<MqttSettings, String> {
+    Ok(load_mqtt_settings().redacted())
+}
+
+/// Guarda la configuración y reinicia el puente con ella
+#[tauri::command]
+pub async fn update_mqtt_settings(
+    hub: State<'_, BroadcastHub>,
+    settings: MqttSettings,
+) -> Result<MqttStatus, String> {
+    info!("📡 Comando: Actualizar configuración MQTT");
+    let settings = settings.keep_stored_password(&load_mqtt_settings());
+    save_mqtt_settings(&settings)?;
+    start_mqtt_bridge(&hub, &settings).await
+}
+
+/// Obtiene el estado de la conexión con el broker
+#[tauri::command]
+pub fn get_mqtt_status() -> Result<MqttStatus, String> {
+    Ok(mqtt_status())
+}
@@ -0,0 +1,246 @@
+//! Publicador MQTT alimentado por el canal de difusión
+//!
+//! Cada mensaje difundido se traduce a su tema MQTT según la configuración.
+//! Mientras el broker no está disponible los mensajes esperan en la cola del
+//! cliente (hasta `buffer_capacity`) y se envían al reconectar; la sesión es
+//! persistente para no perder los QoS 1/2 que quedaron a medias.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::broadcast_ws::channel::BroadcastFrame;
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::mqtt::settings::{MqttSettings, MqttTopics};
+
+/// Espera máxima entre reintentos de conexión
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+// Estado del puente MQTT
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MqttStatus {
+    pub enabled: bool,
+    pub connected: bool,
+    pub broker: Option<String>,      // host:puerto en uso
+    pub published: u64,              // Mensajes entregados a la cola del cliente
+    pub dropped: u64,                // Mensajes descartados por cola llena o retraso
+    pub last_error: Option<String>,
+}
+
+// Puente en marcha
+struct RunningBridge {
+    client: AsyncClient,
+    publisher_task: JoinHandle<()>,
+    eventloop_task: JoinHandle<()>,
+}
+
+/// Estado thread-safe del puente MQTT
+type MqttStatusState = Arc<Mutex<MqttStatus>>;
+
+// Estado del puente (consultable desde la app)
+static MQTT_STATUS: Lazy<MqttStatusState> =
+    Lazy::new(|| Arc::new(Mutex::new(MqttStatus::default())));
+
+// Puente en marcha, si lo hay
+static RUNNING_BRIDGE: Lazy<tokio::sync::Mutex<Option<RunningBridge>>> =
+    Lazy::new(|| tokio::sync::Mutex::new(None));
+
+/// Función para obtener el estado del puente MQTT
+pub fn get_mqtt_status_state() -> MqttStatusState {
+    MQTT_STATUS.clone()
+}
+
+/// Copia del estado actual del puente
+pub fn mqtt_status() -> MqttStatus {
+    get_mqtt_status_state().lock()
+        .map(|status| status.clone())
+        .unwrap_or_default()
+}
+
+fn update_status(update: impl FnOnce(&mut MqttStatus)) {
+    let status = get_mqtt_status_state();
+    let mut status = status.lock().unwrap_or_else(|e| e.into_inner());
+    update(&mut status);
+}
+
+/// Arranca el puente con la configuración indicada, deteniendo el anterior
+pub async fn start_mqtt_bridge(hub: &BroadcastHub, settings: &MqttSettings) -> Result<MqttStatus, String> {
+    settings.validate()?;
+
+    // El hueco queda bloqueado entre la parada y el arranque para que dos
+    // reinicios simultáneos no dejen dos puentes publicando
+    let mut running = RUNNING_BRIDGE.lock().await;
+    shutdown_bridge(running.take()).await;
+
+    if !settings.enabled {
+        return Ok(mqtt_status());
+    }
+
+    let mut options = MqttOptions::new(settings.client_id.clone(), settings.host.clone(), settings.port);
+    options.set_keep_alive(Duration::from_secs(settings.keep_alive_secs));
+    options.set_clean_session(false);
+    if let Some(username) = settings.username.as_ref().filter(|username| !username.is_empty()) {
+        options.set_credentials(username.clone(), settings.password.clone().unwrap_or_default());
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, settings.buffer_capacity);
+    let broker = format!("{}:{}", settings.host, settings.port);
+
+    update_status(|status| {
+        *status = MqttStatus {
+            enabled: true,
+            broker: Some(broker.clone()),
+            ..MqttStatus::default()
+        };
+    });
+
+    // El bucle de eventos conecta, reintenta y vacía la cola de publicaciones
+    let eventloop_task = tokio::spawn(async move {
+        let mut reconnect_delay = Duration::from_secs(1);
+        loop {
+            match eventloop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    info!(broker = %broker, "📶 Conectado al broker MQTT");
+                    reconnect_delay = Duration::from_secs(1);
+                    update_status(|status| {
+                        status.connected = true;
+                        status.last_error = None;
+                    });
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    let was_connected = mqtt_status().connected;
+                    if was_connected {
+                        warn!(broker = %broker, error = %e, "📵 Conexión MQTT perdida, reintentando");
+                    } else {
+                        debug!(broker = %broker, error = %e, "Broker MQTT no disponible");
+                    }
+                    update_status(|status| {
+                        status.connected = false;
+                        status.last_error = Some(e.to_string());
+                    });
+                    tokio::time::sleep(reconnect_delay).await;
+                    reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+                }
+            }
+        }
+    });
+
+    let publisher_task = tokio::spawn(publish_frames(
+        hub.clone(),
+        settings.ring_id.clone(),
+        client.clone(),
+        settings.topics.clone(),
+        qos_level(settings.qos),
+        settings.retain,
+    ));
+
+    *running = Some(RunningBridge {
+        client,
+        publisher_task,
+        eventloop_task,
+    });
+
+    info!(broker = %format!("{}:{}", settings.host, settings.port), qos = settings.qos, "📡 Puente MQTT iniciado");
+    Ok(mqtt_status())
+}
+
+/// Desconecta del broker y detiene las tareas de un puente; devuelve `false` si no había
+async fn shutdown_bridge(bridge: Option<RunningBridge>) -> bool {
+    let Some(bridge) = bridge else {
+        return false;
+    };
+
+    bridge.publisher_task.abort();
+    let _ = bridge.client.try_disconnect();
+    // Dar un momento al bucle de eventos para enviar el DISCONNECT
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    bridge.eventloop_task.abort();
+
+    update_status(|status| {
+        status.enabled = false;
+        status.connected = false;
+    });
+    info!("📴 Puente MQTT detenido");
+    true
+}
+
+/// Reenvía al broker los mensajes difundidos que tienen tema MQTT configurado
+async fn publish_frames(
+    hub: BroadcastHub,
+    ring_id: String,
+    client: AsyncClient,
+    topics: MqttTopics,
+    qos: QoS,
+    retain: bool,
+) {
+    let mut rx = hub.ring(&ring_id).channel().subscribe();
+
+    loop {
+        match rx.recv().await {
+            Ok(frame) => {
+                let Some(topic) = mqtt_topic(&topics, &ring_id, &frame) else {
+                    continue;
+                };
+                // try_publish no bloquea: si la cola está llena el mensaje se descarta
+                match client.try_publish(topic, qos, retain, frame.payload.as_bytes().to_vec()) {
+                    Ok(()) => update_status(|status| status.published += 1),
+                    Err(e) => {
+                        debug!(error = %e, "Cola MQTT llena, mensaje descartado");
+                        update_status(|status| status.dropped += 1);
+                    }
+                }
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!(missed, "🐢 Publicador MQTT retrasado, mensajes descartados");
+                update_status(|status| status.dropped += missed);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+/// El aviso de máximos trae algún récord nuevo
+fn has_new_records(frame: &BroadcastFrame) -> bool {
+    serde_json::from_str::<serde_json::Value>(&frame.payload)
+        .is_ok_and(|payload| payload["new_records"].as_array().is_some_and(|records| !records.is_empty()))
+}
+
+/// Tema MQTT de un mensaje difundido, o `None` si ese tipo no se publica
+fn mqtt_topic(topics: &MqttTopics, ring_id: &str, frame: &BroadcastFrame) -> Option<String> {
+    let template = match frame.message_type.as_str() {
+        "combat_event" => &topics.combat,
+        // Anular o restaurar un golpe recalcula los máximos sin batir ningún récord
+        "max_stats_update" if has_new_records(frame) => &topics.records,
+        "round_change" => &topics.round,
+        "device_status" => &topics.device_health,
+        _ => return None,
+    };
+    if template.is_empty() {
+        return None;
+    }
+
+    // El sufijo del tema de difusión (`combat:<fighter_id>`) identifica al peleador o dispositivo
+    let id = frame.topic.as_deref()
+        .and_then(|topic| topic.split_once(':'))
+        .map(|(_, id)| id)
+        .unwrap_or_default();
+
+    Some(template
+        .replace("{ring_id}", ring_id)
+        .replace("{fighter_id}", id)
+        .replace("{device_id}", id))
+}
+
+fn qos_level(qos: u8) -> QoS {
+    match qos {
+        0 => QoS::AtMostOnce,
+        2 => QoS::ExactlyOnce,
+        _ => QoS::AtLeastOnce,
+    }
+}
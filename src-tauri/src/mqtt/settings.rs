@@ -0,0 +1,140 @@
+//! Configuración persistente del puente MQTT
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::broadcast_ws::settings::{read_config_file, write_config_file};
+use crate::bout::ring::{default_ring_id, validate_ring_id};
+
+/// Nombre del archivo de configuración dentro del directorio de la app
+const SETTINGS_FILE_NAME: &str = "mqtt_settings.json";
+
+/// Valor que sustituye a la contraseña guardada al leer la configuración
+pub const REDACTED_PASSWORD: &str = "********";
+
+// Temas MQTT por tipo de mensaje; `{ring_id}`, `{fighter_id}` y `{device_id}` se sustituyen.
+// Un tema vacío desactiva ese tipo de mensaje.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttTopics {
+    pub combat: String,
+    pub records: String,
+    pub round: String,
+    pub device_health: String,
+}
+
+impl Default for MqttTopics {
+    fn default() -> Self {
+        Self {
+            combat: "beathard/combat/{fighter_id}".to_string(),
+            records: "beathard/records/{fighter_id}".to_string(),
+            round: "beathard/round".to_string(),
+            device_health: "beathard/devices/{device_id}".to_string(),
+        }
+    }
+}
+
+// Configuración del publicador MQTT
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub ring_id: String,             // Ring cuyo feed se publica
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub qos: u8,                     // 0, 1 o 2
+    pub retain: bool,
+    pub keep_alive_secs: u64,
+    pub buffer_capacity: usize,      // Mensajes retenidos mientras el broker no está disponible
+    pub topics: MqttTopics,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ring_id: default_ring_id(),
+            host: "127.0.0.1".to_string(),
+            port: 1883,
+            client_id: "beat-hard-combat".to_string(),
+            username: None,
+            password: None,
+            qos: 1,
+            retain: false,
+            keep_alive_secs: 30,
+            buffer_capacity: 1000,
+            topics: MqttTopics::default(),
+        }
+    }
+}
+
+impl MqttSettings {
+    /// Copia para mostrar, sin la contraseña del broker
+    pub fn redacted(&self) -> Self {
+        let mut settings = self.clone();
+        if settings.password.as_deref().is_some_and(|password| !password.is_empty()) {
+            settings.password = Some(REDACTED_PASSWORD.to_string());
+        }
+        settings
+    }
+
+    /// Conserva la contraseña guardada si llega la versión ocultada
+    pub fn keep_stored_password(mut self, stored: &MqttSettings) -> Self {
+        if self.password.as_deref() == Some(REDACTED_PASSWORD) {
+            self.password = stored.password.clone();
+        }
+        self
+    }
+
+    /// Verifica que los valores sean utilizables
+    pub fn validate(&self) -> Result<(), String> {
+        validate_ring_id(&self.ring_id)?;
+        if self.host.trim().is_empty() {
+            return Err("El host del broker MQTT no puede estar vacío".to_string());
+        }
+        if self.port == 0 {
+            return Err("El puerto del broker MQTT debe ser mayor que 0".to_string());
+        }
+        if self.client_id.trim().is_empty() {
+            return Err("El client_id MQTT no puede estar vacío".to_string());
+        }
+        if self.qos > 2 {
+            return Err(format!("QoS MQTT inválido: {} (válidos: 0, 1, 2)", self.qos));
+        }
+        if self.keep_alive_secs < 5 {
+            return Err("El keep alive MQTT debe ser de al menos 5 segundos".to_string());
+        }
+        if self.buffer_capacity == 0 {
+            return Err("El búfer MQTT debe ser mayor que 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Carga la configuración guardada o la de por defecto
+pub fn load_mqtt_settings() -> MqttSettings {
+    let settings = read_config_file::<MqttSettings>(SETTINGS_FILE_NAME)
+        .and_then(|settings| match settings {
+            Some(settings) => settings.validate().map(|_| Some(settings)),
+            None => Ok(None),
+        });
+
+    match settings {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(e) => {
+            warn!(error = %e, "Configuración MQTT inválida, usando valores por defecto");
+            MqttSettings::default()
+        }
+    }
+}
+
+/// Guarda la configuración en disco
+pub fn save_mqtt_settings(settings: &MqttSettings) -> Result<(), String> {
+    settings.validate()?;
+    let path = write_config_file(SETTINGS_FILE_NAME, settings)?;
+    info!(path = %path.display(), "💾 Configuración MQTT guardada");
+    Ok(())
+}
@@ -0,0 +1,10 @@
+//! Integración con OBS Studio mediante obs-websocket v5
+//!
+//! Cambia de escena o muestra/oculta fuentes cuando la app cambia la vista de
+//! transmisión, y guarda el búfer de repetición tras los golpes más fuertes.
+
+pub mod settings;
+pub mod protocol;
+pub mod controller;
+#[cfg(feature = "desktop")]
+pub mod commands;
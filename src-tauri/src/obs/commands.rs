@@ -0,0 +1,31 @@
+//! Comandos Tauri para configurar la integración con OBS
+
+use tauri::State;
+use tracing::info;
+
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::obs::controller::{obs_status, start_obs_controller, ObsStatus};
+use crate::obs::settings::{load_obs_settings, save_obs_settings, ObsSettings};
+
+/// Obtiene la configuración guardada de OBS
+#[tauri::command]
+pub fn get_obs_settings() -> Result<ObsSettings, String> {
+    Ok(load_obs_settings())
+}
+
+/// Guarda la configuración y reconecta con OBS usándola
+#[tauri::command]
+pub async fn update_obs_settings(
+    hub: State<'_, BroadcastHub>,
+    settings: ObsSettings,
+) -> Result<ObsStatus, String> {
+    info!("🎬 Comando: Actualizar configuración de OBS");
+    save_obs_settings(&settings)?;
+    start_obs_controller(&hub, &settings).await
+}
+
+/// Obtiene el estado de la conexión con OBS
+#[tauri::command]
+pub fn get_obs_status() -> Result<ObsStatus, String> {
+    Ok(obs_status())
+}
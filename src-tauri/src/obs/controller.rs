@@ -0,0 +1,262 @@
+//! Controlador de OBS alimentado por el canal de difusión
+//!
+//! Mantiene la conexión con OBS (reconectando si se cae), aplica la acción
+//! configurada para cada cambio de vista y programa el guardado del búfer de
+//! repetición tras los golpes que superan la fuerza mínima.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::broadcast_ws::delay::sleep_until_due;
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::obs::protocol::ObsConnection;
+use crate::obs::settings::{ObsSettings, ObsSourceRef, ObsViewAction};
+
+/// Espera máxima entre reintentos de conexión
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+// Estado de la integración con OBS
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ObsStatus {
+    pub enabled: bool,
+    pub connected: bool,
+    pub obs_version: Option<String>,
+    pub current_view: Option<String>, // Última vista aplicada en OBS
+    pub replays_saved: u64,
+    pub last_error: Option<String>,
+}
+
+/// Estado thread-safe de la integración con OBS
+type ObsStatusState = Arc<Mutex<ObsStatus>>;
+
+// Estado de la integración (consultable desde la app)
+static OBS_STATUS: Lazy<ObsStatusState> =
+    Lazy::new(|| Arc::new(Mutex::new(ObsStatus::default())));
+
+// Tarea del controlador en marcha, si la hay
+static RUNNING_CONTROLLER: Lazy<tokio::sync::Mutex<Option<JoinHandle<()>>>> =
+    Lazy::new(|| tokio::sync::Mutex::new(None));
+
+/// Función para obtener el estado de la integración con OBS
+pub fn get_obs_status_state() -> ObsStatusState {
+    OBS_STATUS.clone()
+}
+
+/// Copia del estado actual de la integración
+pub fn obs_status() -> ObsStatus {
+    get_obs_status_state().lock()
+        .map(|status| status.clone())
+        .unwrap_or_default()
+}
+
+fn update_status(update: impl FnOnce(&mut ObsStatus)) {
+    let status = get_obs_status_state();
+    let mut status = status.lock().unwrap_or_else(|e| e.into_inner());
+    update(&mut status);
+}
+
+/// Arranca el controlador con la configuración indicada, deteniendo el anterior
+pub async fn start_obs_controller(hub: &BroadcastHub, settings: &ObsSettings) -> Result<ObsStatus, String> {
+    settings.validate()?;
+    stop_obs_controller().await;
+
+    if !settings.enabled {
+        return Ok(obs_status());
+    }
+
+    update_status(|status| {
+        *status = ObsStatus {
+            enabled: true,
+            ..ObsStatus::default()
+        };
+    });
+
+    let task = tokio::spawn(run_controller(hub.clone(), settings.clone()));
+    *RUNNING_CONTROLLER.lock().await = Some(task);
+
+    info!(host = %settings.host, port = settings.port, "🎬 Integración con OBS iniciada");
+    Ok(obs_status())
+}
+
+/// Detiene el controlador; devuelve `false` si no estaba en marcha
+pub async fn stop_obs_controller() -> bool {
+    let Some(task) = RUNNING_CONTROLLER.lock().await.take() else {
+        return false;
+    };
+    task.abort();
+    update_status(|status| {
+        status.enabled = false;
+        status.connected = false;
+    });
+    info!("🎬 Integración con OBS detenida");
+    true
+}
+
+/// Bucle de conexión con reintentos
+async fn run_controller(hub: BroadcastHub, settings: ObsSettings) {
+    let mut reconnect_delay = Duration::from_secs(1);
+
+    loop {
+        match ObsConnection::connect(&settings.host, settings.port, settings.password.as_deref()).await {
+            Ok(connection) => {
+                info!(obs_version = %connection.obs_version, "🎬 Conectado a OBS");
+                reconnect_delay = Duration::from_secs(1);
+                update_status(|status| {
+                    status.connected = true;
+                    status.obs_version = Some(connection.obs_version.clone());
+                    status.last_error = None;
+                });
+
+                let reason = drive_connection(&hub, connection, &settings).await;
+                warn!(reason = %reason, "🎬 Conexión con OBS perdida, reintentando");
+                update_status(|status| {
+                    status.connected = false;
+                    status.last_error = Some(reason);
+                });
+            }
+            Err(e) => {
+                debug!(error = %e, "OBS no disponible");
+                update_status(|status| status.last_error = Some(e));
+            }
+        }
+
+        tokio::time::sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+/// Atiende una conexión hasta que se pierde; devuelve el motivo
+async fn drive_connection(hub: &BroadcastHub, mut connection: ObsConnection, settings: &ObsSettings) -> String {
+    let feed = hub.ring(&settings.ring_id);
+    let mut rx = feed.channel().subscribe();
+    let mut scene_items: HashMap<ObsSourceRef, i64> = HashMap::new();
+    let mut pending_replay: Option<Instant> = None;
+    let mut last_replay: Option<Instant> = None;
+
+    if settings.replay.enabled {
+        // Falla si ya está activo; no es un error
+        if let Err(e) = connection.request("StartReplayBuffer", json!({})).await {
+            debug!(error = %e, "No se inició el búfer de repetición");
+        }
+    }
+
+    // Ponerse al día con la vista que se muestra ahora mismo
+    if let Some(view) = feed.views().active_view() {
+        if let Err(e) = apply_view(&mut connection, settings, &view.view_type, &mut scene_items).await {
+            warn!(view_type = %view.view_type, error = %e, "Error aplicando vista en OBS");
+        }
+    }
+
+    loop {
+        tokio::select! {
+            msg = rx.recv() => {
+                let frame = match msg {
+                    Ok(frame) => frame,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!(missed, "🐢 Controlador de OBS retrasado, mensajes descartados");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return "canal de difusión cerrado".to_string(),
+                };
+                if !matches!(frame.message_type.as_str(), "view_change" | "combat_event") {
+                    continue;
+                }
+                let Ok(payload) = serde_json::from_str::<Value>(&frame.payload) else {
+                    continue;
+                };
+
+                if frame.message_type == "view_change" {
+                    let view_type = payload["view_type"].as_str().unwrap_or_default();
+                    if let Err(e) = apply_view(&mut connection, settings, view_type, &mut scene_items).await {
+                        warn!(view_type, error = %e, "Error aplicando vista en OBS");
+                        update_status(|status| status.last_error = Some(e));
+                    }
+                } else if settings.replay.enabled && pending_replay.is_none() {
+                    let force = payload["data"]["force"].as_f64().unwrap_or_default() as f32;
+                    let cooled_down = last_replay
+                        .is_none_or(|last| last.elapsed() >= Duration::from_millis(settings.replay.cooldown_ms));
+                    if force >= settings.replay.min_force && cooled_down {
+                        info!(force, "💥 Golpe fuerte: guardando repetición en breve");
+                        pending_replay = Some(Instant::now() + Duration::from_millis(settings.replay.save_delay_ms));
+                    }
+                }
+            }
+            _ = sleep_until_due(pending_replay) => {
+                pending_replay = None;
+                last_replay = Some(Instant::now());
+                match connection.request("SaveReplayBuffer", json!({})).await {
+                    Ok(_) => {
+                        info!("🎞️ Repetición guardada en OBS");
+                        update_status(|status| status.replays_saved += 1);
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "Error guardando repetición en OBS");
+                        update_status(|status| status.last_error = Some(e));
+                    }
+                }
+            }
+            reason = connection.closed() => return reason,
+        }
+    }
+}
+
+/// Aplica en OBS la acción configurada para la vista
+async fn apply_view(
+    connection: &mut ObsConnection,
+    settings: &ObsSettings,
+    view_type: &str,
+    scene_items: &mut HashMap<ObsSourceRef, i64>,
+) -> Result<(), String> {
+    let Some(action) = settings.view_action(view_type) else {
+        return Ok(());
+    };
+
+    if let Some(scene) = action.scene.as_ref().filter(|scene| !scene.is_empty()) {
+        connection.request("SetCurrentProgramScene", json!({ "sceneName": scene })).await?;
+    }
+    set_sources_enabled(connection, action, scene_items).await?;
+
+    info!(view_type, scene = ?action.scene, "🎬 Vista aplicada en OBS");
+    update_status(|status| status.current_view = Some(view_type.to_string()));
+    Ok(())
+}
+
+async fn set_sources_enabled(
+    connection: &mut ObsConnection,
+    action: &ObsViewAction,
+    scene_items: &mut HashMap<ObsSourceRef, i64>,
+) -> Result<(), String> {
+    let changes = action.show_sources.iter().map(|source| (source, true))
+        .chain(action.hide_sources.iter().map(|source| (source, false)));
+
+    for (source, enabled) in changes {
+        let scene_item_id = match scene_items.get(source) {
+            Some(id) => *id,
+            None => {
+                let response = connection.request("GetSceneItemId", json!({
+                    "sceneName": source.scene,
+                    "sourceName": source.source,
+                })).await?;
+                let id = response["sceneItemId"].as_i64()
+                    .ok_or_else(|| format!("OBS no devolvió el id de '{}'", source.source))?;
+                scene_items.insert(source.clone(), id);
+                id
+            }
+        };
+
+        connection.request("SetSceneItemEnabled", json!({
+            "sceneName": source.scene,
+            "sceneItemId": scene_item_id,
+            "sceneItemEnabled": enabled,
+        })).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Cliente mínimo del protocolo obs-websocket v5
+//!
+//! Solo se usan peticiones (op 6) y sus respuestas (op 7); no se suscribe a
+//! eventos de OBS. La autenticación sigue el esquema del protocolo:
+//! `base64(sha256(base64(sha256(password + salt)) + challenge))`.
+
+use std::time::Duration;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+/// Versión RPC de obs-websocket que habla este cliente
+const RPC_VERSION: u64 = 1;
+
+/// Tiempo máximo de espera de una respuesta de OBS
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Códigos de operación del protocolo
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+type ObsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// Conexión identificada con OBS
+pub struct ObsConnection {
+    stream: ObsStream,
+    next_request_id: u64,
+    pub obs_version: String,
+}
+
+impl ObsConnection {
+    /// Conecta y completa el saludo (Hello → Identify → Identified)
+    pub async fn connect(host: &str, port: u16, password: Option<&str>) -> Result<Self, String> {
+        let url = format!("ws://{}:{}", host, port);
+        let (mut stream, _) = tokio::time::timeout(RESPONSE_TIMEOUT, connect_async(url.as_str()))
+            .await
+            .map_err(|_| format!("Tiempo de conexión con OBS agotado ({})", url))?
+            .map_err(|e| format!("No se pudo conectar con OBS en {}: {}", url, e))?;
+
+        let hello = read_op(&mut stream, OP_HELLO).await?;
+        let obs_version = hello["obsWebSocketVersion"].as_str().unwrap_or("desconocida").to_string();
+
+        let mut identify = json!({ "rpcVersion": RPC_VERSION, "eventSubscriptions": 0 });
+        if let Some(auth) = hello.get("authentication") {
+            let password = password
+                .filter(|password| !password.is_empty())
+                .ok_or_else(|| "OBS requiere contraseña".to_string())?;
+            let challenge = auth["challenge"].as_str().unwrap_or_default();
+            let salt = auth["salt"].as_str().unwrap_or_default();
+            identify["authentication"] = Value::String(auth_response(password, salt, challenge));
+        }
+        send_op(&mut stream, OP_IDENTIFY, identify).await?;
+        read_op(&mut stream, OP_IDENTIFIED).await
+            .map_err(|e| format!("OBS rechazó la identificación (¿contraseña incorrecta?): {}", e))?;
+
+        Ok(Self {
+            stream,
+            next_request_id: 1,
+            obs_version,
+        })
+    }
+
+    /// Envía una petición y espera su respuesta; devuelve `responseData`
+    pub async fn request(&mut self, request_type: &str, request_data: Value) -> Result<Value, String> {
+        let request_id = self.next_request_id.to_string();
+        self.next_request_id += 1;
+
+        send_op(&mut self.stream, OP_REQUEST, json!({
+            "requestType": request_type,
+            "requestId": request_id,
+            "requestData": request_data,
+        })).await?;
+
+        loop {
+            let response = read_op(&mut self.stream, OP_REQUEST_RESPONSE).await?;
+            if response["requestId"].as_str() != Some(request_id.as_str()) {
+                continue;
+            }
+
+            let status = &response["requestStatus"];
+            if status["result"].as_bool() == Some(true) {
+                return Ok(response.get("responseData").cloned().unwrap_or(Value::Null));
+            }
+            return Err(format!(
+                "{} falló (código {}): {}",
+                request_type,
+                status["code"].as_i64().unwrap_or_default(),
+                status["comment"].as_str().unwrap_or("sin detalle")
+            ));
+        }
+    }
+
+    /// Espera a que OBS cierre la conexión (responde a los pings mientras tanto)
+    pub async fn closed(&mut self) -> String {
+        loop {
+            match self.stream.next().await {
+                Some(Ok(Message::Close(frame))) => {
+                    return frame.map(|frame| frame.reason.to_string()).unwrap_or_else(|| "cerrada por OBS".to_string());
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return e.to_string(),
+                None => return "cerrada por OBS".to_string(),
+            }
+        }
+    }
+}
+
+async fn send_op(stream: &mut ObsStream, op: u64, data: Value) -> Result<(), String> {
+    let message = json!({ "op": op, "d": data }).to_string();
+    stream.send(Message::Text(message))
+        .await
+        .map_err(|e| format!("Error enviando a OBS: {}", e))
+}
+
+/// Lee mensajes hasta recibir uno con el código indicado; devuelve su `d`
+async fn read_op(stream: &mut ObsStream, op: u64) -> Result<Value, String> {
+    let read = async {
+        loop {
+            let message = match stream.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(frame))) => {
+                    let reason = frame.map(|frame| frame.reason.to_string()).unwrap_or_default();
+                    return Err(format!("OBS cerró la conexión: {}", reason));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(format!("Error leyendo de OBS: {}", e)),
+                None => return Err("OBS cerró la conexión".to_string()),
+            };
+
+            let Ok(mut message) = serde_json::from_str::<Value>(&message) else {
+                continue;
+            };
+            if message["op"].as_u64() == Some(op) {
+                return Ok(message["d"].take());
+            }
+        }
+    };
+
+    tokio::time::timeout(RESPONSE_TIMEOUT, read)
+        .await
+        .map_err(|_| "OBS no respondió a tiempo".to_string())?
+}
+
+/// Respuesta al desafío de autenticación de obs-websocket
+fn auth_response(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, salt)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
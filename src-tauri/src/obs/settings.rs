@@ -0,0 +1,143 @@
+//! Configuración persistente de la integración con OBS
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::broadcast_ws::settings::{read_config_file, write_config_file};
+use crate::bout::ring::{default_ring_id, validate_ring_id};
+
+/// Nombre del archivo de configuración dentro del directorio de la app
+const SETTINGS_FILE_NAME: &str = "obs_settings.json";
+
+// Fuente dentro de una escena de OBS
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ObsSourceRef {
+    pub scene: String,
+    pub source: String,
+}
+
+// Qué hacer en OBS al mostrar una vista de transmisión
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObsViewAction {
+    pub view_type: String,           // "cover", "live-combat", "round-stats", "summary-stats"...
+    #[serde(default)]
+    pub scene: Option<String>,       // Escena de programa a activar
+    #[serde(default)]
+    pub show_sources: Vec<ObsSourceRef>,
+    #[serde(default)]
+    pub hide_sources: Vec<ObsSourceRef>,
+}
+
+impl ObsViewAction {
+    fn scene(view_type: &str, scene: &str) -> Self {
+        Self {
+            view_type: view_type.to_string(),
+            scene: Some(scene.to_string()),
+            show_sources: Vec::new(),
+            hide_sources: Vec::new(),
+        }
+    }
+}
+
+// Guardado del búfer de repetición tras un golpe fuerte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObsReplaySettings {
+    pub enabled: bool,
+    pub min_force: f32,              // Fuerza mínima (N) para considerar el golpe "grande"
+    pub save_delay_ms: u64,          // Espera tras el golpe para incluir la reacción
+    pub cooldown_ms: u64,            // Tiempo mínimo entre guardados
+}
+
+impl Default for ObsReplaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_force: 800.0,
+            save_delay_ms: 2_000,
+            cooldown_ms: 10_000,
+        }
+    }
+}
+
+// Configuración de la conexión con OBS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObsSettings {
+    pub enabled: bool,
+    pub ring_id: String,             // Ring cuyas vistas y golpes controlan OBS
+    pub host: String,
+    pub port: u16,
+    pub password: Option<String>,
+    pub views: Vec<ObsViewAction>,
+    pub replay: ObsReplaySettings,
+}
+
+impl Default for ObsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ring_id: default_ring_id(),
+            host: "127.0.0.1".to_string(),
+            port: 4455,
+            password: None,
+            views: vec![
+                ObsViewAction::scene("cover", "Portada"),
+                ObsViewAction::scene("live-combat", "Combate"),
+                ObsViewAction::scene("round-stats", "Estadísticas del round"),
+                ObsViewAction::scene("summary-stats", "Resumen"),
+            ],
+            replay: ObsReplaySettings::default(),
+        }
+    }
+}
+
+impl ObsSettings {
+    /// Verifica que los valores sean utilizables
+    pub fn validate(&self) -> Result<(), String> {
+        validate_ring_id(&self.ring_id)?;
+        if self.host.trim().is_empty() {
+            return Err("El host de OBS no puede estar vacío".to_string());
+        }
+        if self.port == 0 {
+            return Err("El puerto de OBS debe ser mayor que 0".to_string());
+        }
+        if self.replay.min_force <= 0.0 {
+            return Err("La fuerza mínima para repeticiones debe ser mayor que 0".to_string());
+        }
+        if let Some(action) = self.views.iter().find(|action| action.view_type.trim().is_empty()) {
+            return Err(format!("Acción de OBS sin tipo de vista: {:?}", action.scene));
+        }
+        Ok(())
+    }
+
+    /// Acción configurada para una vista
+    pub fn view_action(&self, view_type: &str) -> Option<&ObsViewAction> {
+        self.views.iter().find(|action| action.view_type == view_type)
+    }
+}
+
+/// Carga la configuración guardada o la de por defecto
+pub fn load_obs_settings() -> ObsSettings {
+    let settings = read_config_file::<ObsSettings>(SETTINGS_FILE_NAME)
+        .and_then(|settings| match settings {
+            Some(settings) => settings.validate().map(|_| Some(settings)),
+            None => Ok(None),
+        });
+
+    match settings {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(e) => {
+            warn!(error = %e, "Configuración de OBS inválida, usando valores por defecto");
+            ObsSettings::default()
+        }
+    }
+}
+
+/// Guarda la configuración en disco
+pub fn save_obs_settings(settings: &ObsSettings) -> Result<(), String> {
+    settings.validate()?;
+    let path = write_config_file(SETTINGS_FILE_NAME, settings)?;
+    info!(path = %path.display(), "💾 Configuración de OBS guardada");
+    Ok(())
+}
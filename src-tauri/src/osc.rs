@@ -0,0 +1,10 @@
+//! Salida OSC para control de espectáculo (QLab, TouchDesigner)
+//!
+//! Envía por UDP un mensaje OSC por golpe, récord e inicio/fin de round, con
+//! direcciones configurables y varios destinos a la vez.
+
+pub mod settings;
+pub mod encoder;
+pub mod sender;
+#[cfg(feature = "desktop")]
+pub mod commands;
@@ -0,0 +1,38 @@
+//! Comandos Tauri para configurar la salida OSC
+
+use tauri::State;
+use tracing::info;
+
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::osc::sender::{osc_status, send_osc_test, start_osc_sender, OscStatus};
+use crate::osc::settings::{load_osc_settings, save_osc_settings, OscSettings};
+
+/// Obtiene la configuración guardada de la salida OSC
+#[tauri::command]
+pub fn get_osc_settings() -> Result<OscSettings, String> {
+    Ok(load_osc_settings())
+}
+
+/// Guarda la configuración y reinicia la salida con ella
+#[tauri::command]
+pub async fn update_osc_settings(
+    hub: State<'_, BroadcastHub>,
+    settings: OscSettings,
+) -> Result<OscStatus, String> {
+    info!("🎛️ Comando: Actualizar configuración OSC");
+    save_osc_settings(&settings)?;
+    start_osc_sender(&hub, &settings).await
+}
+
+/// Obtiene el estado de la salida OSC
+#[tauri::command]
+pub fn get_osc_status() -> Result<OscStatus, String> {
+    Ok(osc_status())
+}
+
+/// Envía `/beathard/test` a todos los destinos habilitados
+#[tauri::command]
+pub async fn test_osc_output() -> Result<String, String> {
+    let sent = send_osc_test(&load_osc_settings()).await?;
+    Ok(format!("Mensaje OSC de prueba enviado a {} destinos", sent))
+}
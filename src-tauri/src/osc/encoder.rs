@@ -0,0 +1,94 @@
+//! Codificación de mensajes OSC 1.0
+//!
+//! Cadenas terminadas en nulo y rellenadas a múltiplos de 4 bytes; enteros y
+//! flotantes de 32 bits en big-endian.
+
+// Argumento de un mensaje OSC
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+}
+
+// Mensaje OSC listo para codificar
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    pub fn new(address: impl Into<String>, args: Vec<OscArg>) -> Self {
+        Self {
+            address: address.into(),
+            args,
+        }
+    }
+
+    /// Paquete UDP del mensaje
+    pub fn encode(&self) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(64);
+        write_padded_str(&mut packet, &self.address);
+
+        let type_tags: String = std::iter::once(',')
+            .chain(self.args.iter().map(|arg| match arg {
+                OscArg::Int(_) => 'i',
+                OscArg::Float(_) => 'f',
+                OscArg::Str(_) => 's',
+            }))
+            .collect();
+        write_padded_str(&mut packet, &type_tags);
+
+        for arg in &self.args {
+            match arg {
+                OscArg::Int(value) => packet.extend_from_slice(&value.to_be_bytes()),
+                OscArg::Float(value) => packet.extend_from_slice(&value.to_be_bytes()),
+                OscArg::Str(value) => write_padded_str(&mut packet, value),
+            }
+        }
+        packet
+    }
+}
+
+/// Escribe la cadena con su nulo final y relleno hasta múltiplo de 4
+fn write_padded_str(packet: &mut Vec<u8>, value: &str) {
+    packet.extend_from_slice(value.as_bytes());
+    let padding = 4 - value.len() % 4;
+    packet.resize(packet.len() + padding, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_strings_to_four_bytes_with_at_least_one_null() {
+        // "/abc" ocupa justo 4 bytes y aun así necesita su nulo final
+        let packet = OscMessage::new("/abc", Vec::new()).encode();
+        assert_eq!(packet, b"/abc\0\0\0\0,\0\0\0");
+
+        let packet = OscMessage::new("/ab", Vec::new()).encode();
+        assert_eq!(packet, b"/ab\0,\0\0\0");
+    }
+
+    #[test]
+    fn encodes_type_tags_and_big_endian_arguments() {
+        let message = OscMessage::new("/hit", vec![
+            OscArg::Int(258),
+            OscArg::Float(1.5),
+            OscArg::Str("jab".to_string()),
+        ]);
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(b"/hit\0\0\0\0");
+        expected.extend_from_slice(b",ifs\0\0\0\0");
+        expected.extend_from_slice(&[0x00, 0x00, 0x01, 0x02]);
+        expected.extend_from_slice(&[0x3f, 0xc0, 0x00, 0x00]);
+        expected.extend_from_slice(b"jab\0");
+
+        let packet = message.encode();
+        assert_eq!(packet.len() % 4, 0);
+        assert_eq!(packet, expected);
+    }
+}
@@ -0,0 +1,268 @@
+//! Emisor OSC alimentado por el canal de difusión
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::net::UdpSocket;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::ble::types::LimbType;
+use crate::broadcast_ws::channel::BroadcastFrame;
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::osc::encoder::{OscArg, OscMessage};
+use crate::osc::settings::{OscAddresses, OscSettings};
+
+// Estado de la salida OSC
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OscStatus {
+    pub running: bool,
+    pub destinations: Vec<String>,   // Direcciones resueltas en uso
+    pub sent: u64,
+    pub errors: u64,
+    pub last_error: Option<String>,
+}
+
+/// Estado thread-safe de la salida OSC
+type OscStatusState = Arc<Mutex<OscStatus>>;
+
+// Estado de la salida OSC (consultable desde la app)
+static OSC_STATUS: Lazy<OscStatusState> =
+    Lazy::new(|| Arc::new(Mutex::new(OscStatus::default())));
+
+// Tarea del emisor en marcha, si la hay
+static RUNNING_SENDER: Lazy<tokio::sync::Mutex<Option<JoinHandle<()>>>> =
+    Lazy::new(|| tokio::sync::Mutex::new(None));
+
+/// Función para obtener el estado de la salida OSC
+pub fn get_osc_status_state() -> OscStatusState {
+    OSC_STATUS.clone()
+}
+
+/// Copia del estado actual de la salida OSC
+pub fn osc_status() -> OscStatus {
+    get_osc_status_state().lock()
+        .map(|status| status.clone())
+        .unwrap_or_default()
+}
+
+fn update_status(update: impl FnOnce(&mut OscStatus)) {
+    let status = get_osc_status_state();
+    let mut status = status.lock().unwrap_or_else(|e| e.into_inner());
+    update(&mut status);
+}
+
+/// Arranca el emisor con la configuración indicada, deteniendo el anterior
+pub async fn start_osc_sender(hub: &BroadcastHub, settings: &OscSettings) -> Result<OscStatus, String> {
+    settings.validate()?;
+    stop_osc_sender().await;
+
+    if !settings.enabled {
+        return Ok(osc_status());
+    }
+
+    let targets = resolve_destinations(settings).await?;
+    if targets.is_empty() {
+        return Err("No hay destinos OSC habilitados".to_string());
+    }
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| format!("Error abriendo socket UDP para OSC: {}", e))?;
+
+    update_status(|status| {
+        *status = OscStatus {
+            running: true,
+            destinations: targets.iter().map(|target| target.to_string()).collect(),
+            ..OscStatus::default()
+        };
+    });
+
+    let task = tokio::spawn(send_frames(
+        hub.clone(),
+        settings.ring_id.clone(),
+        socket,
+        targets,
+        settings.addresses.clone(),
+    ));
+    *RUNNING_SENDER.lock().await = Some(task);
+
+    info!(destinations = ?osc_status().destinations, "🎛️ Salida OSC iniciada");
+    Ok(osc_status())
+}
+
+/// Detiene el emisor; devuelve `false` si no estaba en marcha
+pub async fn stop_osc_sender() -> bool {
+    let Some(task) = RUNNING_SENDER.lock().await.take() else {
+        return false;
+    };
+    task.abort();
+    update_status(|status| status.running = false);
+    info!("🎛️ Salida OSC detenida");
+    true
+}
+
+/// Envía un mensaje de prueba a todos los destinos configurados
+pub async fn send_osc_test(settings: &OscSettings) -> Result<usize, String> {
+    let targets = resolve_destinations(settings).await?;
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| format!("Error abriendo socket UDP para OSC: {}", e))?;
+
+    let packet = OscMessage::new("/beathard/test", vec![OscArg::Int(1)]).encode();
+    for target in &targets {
+        socket.send_to(&packet, target)
+            .await
+            .map_err(|e| format!("Error enviando a {}: {}", target, e))?;
+    }
+    Ok(targets.len())
+}
+
+/// Resuelve los destinos habilitados a direcciones UDP
+async fn resolve_destinations(settings: &OscSettings) -> Result<Vec<SocketAddr>, String> {
+    let mut targets = Vec::new();
+    for destination in settings.destinations.iter().filter(|destination| destination.enabled) {
+        let target = tokio::net::lookup_host((destination.host.as_str(), destination.port))
+            .await
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| format!("No se pudo resolver el destino OSC '{}' ({})", destination.label, destination.host))?;
+        targets.push(target);
+    }
+    Ok(targets)
+}
+
+/// Reenvía a los destinos OSC los mensajes difundidos con dirección configurada
+async fn send_frames(
+    hub: BroadcastHub,
+    ring_id: String,
+    socket: UdpSocket,
+    targets: Vec<SocketAddr>,
+    addresses: OscAddresses,
+) {
+    let mut rx = hub.ring(&ring_id).channel().subscribe();
+
+    loop {
+        let frame = match rx.recv().await {
+            Ok(frame) => frame,
+            Err(RecvError::Lagged(missed)) => {
+                warn!(missed, "🐢 Salida OSC retrasada, mensajes descartados");
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        for message in osc_messages(&addresses, &frame) {
+            let packet = message.encode();
+            for target in &targets {
+                match socket.send_to(&packet, target).await {
+                    Ok(_) => update_status(|status| status.sent += 1),
+                    Err(e) => {
+                        debug!(%target, error = %e, "Error enviando mensaje OSC");
+                        update_status(|status| {
+                            status.errors += 1;
+                            status.last_error = Some(format!("{}: {}", target, e));
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Mensajes OSC correspondientes a un mensaje difundido
+fn osc_messages(addresses: &OscAddresses, frame: &BroadcastFrame) -> Vec<OscMessage> {
+    let message_type = frame.message_type.as_str();
+    if !matches!(message_type, "combat_event" | "max_stats_update" | "round_change") {
+        return Vec::new();
+    }
+    let payload: Value = match serde_json::from_str(&frame.payload) {
+        Ok(payload) => payload,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut messages = Vec::new();
+    match message_type {
+        "combat_event" if !addresses.hit.is_empty() => {
+            let event = &payload["data"];
+            let limb = serde_json::from_value::<LimbType>(event["limb_type"].clone())
+                .map(limb_slug)
+                .unwrap_or("unknown");
+            let address = addresses.hit
+                .replace("{fighter_id}", event["fighter_id"].as_str().unwrap_or_default())
+                .replace("{limb}", limb);
+            messages.push(OscMessage::new(address, vec![
+                float_arg(&event["force"]),
+                float_arg(&event["velocity"]),
+                float_arg(&event["confidence"]),
+            ]));
+        }
+        // Anular o restaurar un golpe recalcula los máximos sin batir ningún récord
+        "max_stats_update" if !addresses.record.is_empty() && has_new_records(&payload) => {
+            let stats = &payload["data"];
+            let address = addresses.record
+                .replace("{fighter_id}", payload["fighter_id"].as_str().unwrap_or_default());
+            messages.push(OscMessage::new(address, vec![
+                float_arg(&stats["max_force"]),
+                float_arg(&stats["max_velocity"]),
+                float_arg(&stats["max_acceleration"]),
+                OscArg::Str(record_names(&payload["new_records"])),
+            ]));
+        }
+        "round_change" => {
+            let rounds = int_arg(&payload["rounds"]);
+            let current_round = payload["current_round"].as_i64();
+            let previous_round = payload["previous_round"].as_i64();
+
+            // El fin del round anterior va antes del inicio del nuevo
+            if let (Some(previous), Some(current)) = (previous_round, current_round) {
+                if previous < current && !addresses.round_end.is_empty() {
+                    messages.push(OscMessage::new(addresses.round_end.clone(), vec![
+                        OscArg::Int(previous as i32),
+                        rounds.clone(),
+                    ]));
+                }
+            }
+            if let Some(current) = current_round.filter(|_| !addresses.round_start.is_empty()) {
+                messages.push(OscMessage::new(addresses.round_start.clone(), vec![
+                    OscArg::Int(current as i32),
+                    rounds,
+                ]));
+            }
+        }
+        _ => {}
+    }
+    messages
+}
+
+fn float_arg(value: &Value) -> OscArg {
+    OscArg::Float(value.as_f64().unwrap_or(0.0) as f32)
+}
+
+/// El aviso de máximos trae algún récord nuevo
+fn has_new_records(payload: &Value) -> bool {
+    payload["new_records"].as_array().is_some_and(|records| !records.is_empty())
+}
+
+/// Récords batidos separados por comas ("force,velocity")
+fn record_names(value: &Value) -> String {
+    value.as_array()
+        .map(|records| records.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(","))
+        .unwrap_or_default()
+}
+
+fn int_arg(value: &Value) -> OscArg {
+    OscArg::Int(value.as_i64().unwrap_or(0) as i32)
+}
+
+/// Nombre de la extremidad en las direcciones OSC
+fn limb_slug(limb_type: LimbType) -> &'static str {
+    match limb_type {
+        LimbType::LeftHand => "left_hand",
+        LimbType::RightHand => "right_hand",
+        LimbType::LeftFoot => "left_foot",
+        LimbType::RightFoot => "right_foot",
+    }
+}
@@ -0,0 +1,125 @@
+//! Configuración persistente de la salida OSC
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::broadcast_ws::settings::{read_config_file, write_config_file};
+use crate::bout::ring::{default_ring_id, validate_ring_id};
+
+/// Nombre del archivo de configuración dentro del directorio de la app
+const SETTINGS_FILE_NAME: &str = "osc_settings.json";
+
+// Destino UDP de los mensajes OSC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OscDestination {
+    pub label: String,
+    pub host: String,
+    pub port: u16,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// Patrones de dirección por tipo de evento; un patrón vacío desactiva ese evento.
+// `{fighter_id}` y `{limb}` (left_hand, right_foot...) se sustituyen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OscAddresses {
+    pub hit: String,                 // Argumentos: fuerza, velocidad, confianza
+    pub record: String,              // Argumentos: fuerza, velocidad, aceleración máximas y récords batidos
+    pub round_start: String,         // Argumentos: round, rounds totales
+    pub round_end: String,           // Argumentos: round, rounds totales
+}
+
+impl Default for OscAddresses {
+    fn default() -> Self {
+        Self {
+            hit: "/beathard/hit/{fighter_id}/{limb}".to_string(),
+            record: "/beathard/record/{fighter_id}".to_string(),
+            round_start: "/beathard/round/start".to_string(),
+            round_end: "/beathard/round/end".to_string(),
+        }
+    }
+}
+
+// Configuración de la salida OSC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OscSettings {
+    pub enabled: bool,
+    pub ring_id: String,             // Ring cuyo feed se envía
+    pub destinations: Vec<OscDestination>,
+    pub addresses: OscAddresses,
+}
+
+impl Default for OscSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ring_id: default_ring_id(),
+            destinations: vec![OscDestination {
+                label: "QLab".to_string(),
+                host: "127.0.0.1".to_string(),
+                port: 53000,
+                enabled: true,
+            }],
+            addresses: OscAddresses::default(),
+        }
+    }
+}
+
+impl OscSettings {
+    /// Verifica que los valores sean utilizables
+    pub fn validate(&self) -> Result<(), String> {
+        validate_ring_id(&self.ring_id)?;
+        for destination in &self.destinations {
+            if destination.host.trim().is_empty() {
+                return Err(format!("El destino OSC '{}' no tiene host", destination.label));
+            }
+            if destination.port == 0 {
+                return Err(format!("El destino OSC '{}' no tiene puerto", destination.label));
+            }
+        }
+
+        let addresses = [
+            &self.addresses.hit,
+            &self.addresses.record,
+            &self.addresses.round_start,
+            &self.addresses.round_end,
+        ];
+        for address in addresses {
+            if !address.is_empty() && !address.starts_with('/') {
+                return Err(format!("La dirección OSC '{}' debe empezar por '/'", address));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Carga la configuración guardada o la de por defecto
+pub fn load_osc_settings() -> OscSettings {
+    let settings = read_config_file::<OscSettings>(SETTINGS_FILE_NAME)
+        .and_then(|settings| match settings {
+            Some(settings) => settings.validate().map(|_| Some(settings)),
+            None => Ok(None),
+        });
+
+    match settings {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(e) => {
+            warn!(error = %e, "Configuración OSC inválida, usando valores por defecto");
+            OscSettings::default()
+        }
+    }
+}
+
+/// Guarda la configuración en disco
+pub fn save_osc_settings(settings: &OscSettings) -> Result<(), String> {
+    settings.validate()?;
+    let path = write_config_file(SETTINGS_FILE_NAME, settings)?;
+    info!(path = %path.display(), "💾 Configuración OSC guardada");
+    Ok(())
+}
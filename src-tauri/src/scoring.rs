@@ -0,0 +1,7 @@
+//! Módulo de puntuación - Aplica reglamentos configurables a los eventos de combate
+
+pub mod types;
+pub mod engine;
+pub mod state;
+#[cfg(feature = "desktop")]
+pub mod commands;
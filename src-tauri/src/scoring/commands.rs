@@ -0,0 +1,75 @@
+//! Comandos Tauri para el sistema de puntuación
+
+use std::path::PathBuf;
+use tauri::State;
+use tracing::info;
+
+use crate::scoring::types::ScoringRuleset;
+use crate::scoring::state::{
+    active_ruleset, clear_active_ruleset, get_score_tallies_state, set_active_ruleset, clear_score_tallies
+};
+use crate::bout::ring::ring_or_default;
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::broadcast_ws::protocol::ServerMessage;
+
+/// Carga un reglamento de puntuación desde un archivo TOML o JSON para un ring
+#[tauri::command]
+pub fn load_scoring_ruleset(path: String, ring_id: Option<String>) -> Result<ScoringRuleset, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    info!(path = %path, ring_id = %ring_id, "📜 Comando: Cargar reglamento de puntuación");
+
+    let ruleset = ScoringRuleset::from_file(&PathBuf::from(&path))?;
+    set_active_ruleset(&ring_id, ruleset.clone());
+
+    Ok(ruleset)
+}
+
+/// Restablece el reglamento de puntuación por defecto en un ring
+#[tauri::command]
+pub fn reset_scoring_ruleset(ring_id: Option<String>) -> Result<ScoringRuleset, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    clear_active_ruleset(&ring_id);
+    Ok(active_ruleset(&ring_id))
+}
+
+/// Obtiene el reglamento de puntuación activo de un ring
+#[tauri::command]
+pub fn get_scoring_ruleset(ring_id: Option<String>) -> Result<ScoringRuleset, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    Ok(active_ruleset(&ring_id))
+}
+
+/// Obtiene el marcador actual de un ring, de un peleador o de todos
+#[tauri::command]
+pub fn get_score_tally(ring_id: Option<String>, fighter_id: Option<String>) -> Result<serde_json::Value, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    let tallies = get_score_tallies_state();
+    let tallies = tallies.lock()
+        .map_err(|e| format!("Error accediendo al marcador: {}", e))?;
+
+    match fighter_id {
+        Some(id) => {
+            let score = tallies.get(&(ring_id, id.clone()))
+                .ok_or_else(|| format!("No se encontró marcador para el peleador {}", id))?;
+            serde_json::to_value(score).map_err(|e| e.to_string())
+        }
+        None => {
+            let all_scores: Vec<_> = tallies.values()
+                .filter(|score| score.ring_id == ring_id)
+                .collect();
+            serde_json::to_value(all_scores).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Resetea el marcador de los peleadores de un ring
+#[tauri::command]
+pub fn reset_score_tally(hub: State<'_, BroadcastHub>, ring_id: Option<String>) -> Result<String, String> {
+    let ring_id = ring_or_default(ring_id)?;
+    clear_score_tallies(&ring_id);
+
+    hub.broadcast(&ring_id, &ServerMessage::ScoreReset);
+
+    info!(ring_id = %ring_id, "🔄 Marcador reseteado");
+    Ok("Marcador reseteado exitosamente".to_string())
+}
@@ -0,0 +1,289 @@
+//! Motor de puntuación: evalúa golpes contra el reglamento y actualiza el marcador
+
+use tracing::{info, debug, error};
+
+use crate::ble::types::SimpleCombatEvent;
+use crate::scoring::types::{
+    FighterScore, ScoreEntry, ScoreEntryKind, ScoringResult, ScoringRuleset, StrikeScore
+};
+use crate::scoring::state::{
+    default_ruleset, get_active_rulesets_state, get_score_ledger_state, get_score_tallies_state
+};
+use crate::broadcast_ws::hub::BroadcastHub;
+use crate::broadcast_ws::protocol::ServerMessage;
+
+/// Evalúa un golpe contra el reglamento sin modificar el marcador
+pub fn evaluate_strike(ruleset: &ScoringRuleset, event: &SimpleCombatEvent) -> StrikeScore {
+    let not_scored = StrikeScore { scored: false, points: 0, bonus_points: 0 };
+
+    let force = event.force.unwrap_or(0.0);
+    if force < ruleset.min_force || event.confidence < ruleset.min_confidence {
+        return not_scored;
+    }
+
+    // La regla específica de la extremidad tiene prioridad sobre la genérica
+    let rule = ruleset.points.iter()
+        .filter(|rule| rule.event_type == event.event_type)
+        .filter(|rule| rule.limb.is_none_or(|limb| limb == event.limb_type))
+        .max_by_key(|rule| rule.limb.is_some());
+
+    let Some(rule) = rule else {
+        return not_scored;
+    };
+
+    let bonus_points = match &ruleset.bonus {
+        Some(bonus) if force >= bonus.force_threshold => bonus.points,
+        _ => 0,
+    };
+
+    StrikeScore { scored: true, points: rule.points, bonus_points }
+}
+
+/// Aplica el reglamento activo del ring a un evento y emite el marcador actualizado
+pub fn apply_scoring_event(event: &SimpleCombatEvent, hub: &BroadcastHub) {
+    let strike = {
+        let rulesets = get_active_rulesets_state();
+        let rulesets = match rulesets.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!(error = %e, "Error accediendo al reglamento de puntuación");
+                return;
+            }
+        };
+        evaluate_strike(rulesets.get(&event.ring_id).unwrap_or(default_ruleset()), event)
+    };
+
+    let entry = ScoreEntry {
+        id: event.id.clone(),
+        ring_id: event.ring_id.clone(),
+        fighter_id: event.fighter_id.clone(),
+        kind: ScoreEntryKind::Strike,
+        scored: strike.scored,
+        points: strike.points,
+        bonus_points: strike.bonus_points,
+        issued_by: None,
+        reason: None,
+        voided: false,
+        timestamp: event.timestamp,
+    };
+
+    let score = match record_score_entry(entry.clone(), &event.competitor_name) {
+        Ok(score) => score,
+        Err(e) => {
+            error!(error = %e, "Error registrando golpe en el marcador");
+            return;
+        }
+    };
+
+    if strike.scored {
+        info!(fighter_id = %event.fighter_id, points = strike.total(), total = score.points,
+              "🎯 Golpe puntuado");
+    } else {
+        debug!(fighter_id = %event.fighter_id, "Golpe por debajo de los mínimos del reglamento");
+    }
+
+    emit_score_update(&score, Some(&entry), hub);
+}
+
+/// Añade una entrada al registro y acumula su puntuación en el marcador del peleador
+///
+/// El marcador se actualiza sobre el total existente; solo anular o reconstruir
+/// recorre el registro completo.
+pub fn record_score_entry(entry: ScoreEntry, competitor_name: &str) -> ScoringResult<FighterScore> {
+    // Mismo orden de bloqueo que el recálculo, para que no cuente la entrada dos veces
+    let tallies = get_score_tallies_state();
+    let mut tallies = tallies.lock()
+        .map_err(|e| format!("Error accediendo al marcador: {}", e))?;
+
+    let key = (entry.ring_id.clone(), entry.fighter_id.clone());
+    let score = tallies.entry(key)
+        .or_insert_with(|| FighterScore::new(&entry.ring_id, &entry.fighter_id, competitor_name));
+
+    // Conservar el nombre conocido si la entrada no lo aporta
+    if !competitor_name.is_empty() {
+        score.competitor_name = competitor_name.to_string();
+    }
+    score.apply_entry(&entry);
+    let score = score.clone();
+
+    let ledger = get_score_ledger_state();
+    let mut ledger = ledger.lock()
+        .map_err(|e| format!("Error accediendo al registro de puntuación: {}", e))?;
+    ledger.entry(entry.ring_id.clone()).or_default().push(entry);
+
+    Ok(score)
+}
+
+/// Cambia el estado de anulación de una entrada de un ring y recalcula el marcador del peleador
+pub fn set_score_entry_voided(ring_id: &str, entry_id: &str, voided: bool) -> ScoringResult<(ScoreEntry, FighterScore)> {
+    let entry = {
+        let ledger = get_score_ledger_state();
+        let mut ledger = ledger.lock()
+            .map_err(|e| format!("Error accediendo al registro de puntuación: {}", e))?;
+        let entry = ledger.get_mut(ring_id)
+            .and_then(|entries| entries.iter_mut().find(|entry| entry.id == entry_id))
+            .ok_or_else(|| format!("No existe la entrada de puntuación {}", entry_id))?;
+
+        if entry.voided == voided {
+            let state = if voided { "anulada" } else { "vigente" };
+            return Err(format!("La entrada {} ya está {}", entry_id, state));
+        }
+        entry.voided = voided;
+        entry.clone()
+    };
+
+    let score = recompute_fighter_score(&entry.ring_id, &entry.fighter_id, "")?;
+    Ok((entry, score))
+}
+
+/// Recalcula el marcador de un peleador a partir del registro
+fn recompute_fighter_score(ring_id: &str, fighter_id: &str, competitor_name: &str) -> ScoringResult<FighterScore> {
+    let tallies = get_score_tallies_state();
+    let mut tallies = tallies.lock()
+        .map_err(|e| format!("Error accediendo al marcador: {}", e))?;
+    let key = (ring_id.to_string(), fighter_id.to_string());
+
+    // Conservar el nombre conocido si la entrada no lo aporta
+    let name = match tallies.get(&key) {
+        Some(existing) if competitor_name.is_empty() => existing.competitor_name.clone(),
+        _ => competitor_name.to_string(),
+    };
+
+    let mut score = FighterScore::new(ring_id, fighter_id, &name);
+    {
+        let ledger = get_score_ledger_state();
+        let ledger = ledger.lock()
+            .map_err(|e| format!("Error accediendo al registro de puntuación: {}", e))?;
+        ledger.get(ring_id)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.fighter_id == fighter_id)
+            .for_each(|entry| score.apply_entry(entry));
+    }
+
+    tallies.insert(key, score.clone());
+    Ok(score)
+}
+
+/// Construye el mensaje de actualización de marcador
+pub fn score_update_message(score: &FighterScore, entry: Option<&ScoreEntry>) -> ServerMessage {
+    ServerMessage::ScoreUpdate {
+        fighter_id: score.fighter_id.clone(),
+        data: score.clone(),
+        entry: entry.cloned(),
+    }
+}
+
+/// Emite el marcador de un peleador al frontend y al feed de su ring
+pub fn emit_score_update(score: &FighterScore, entry: Option<&ScoreEntry>, hub: &BroadcastHub) {
+    let message = score_update_message(score, entry);
+    hub.emit_frontend("score-update", &message);
+    hub.broadcast(&score.ring_id, &message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::types::LimbType;
+    use crate::scoring::state::{active_ruleset, clear_active_ruleset, set_active_ruleset};
+    use crate::scoring::types::{ForceBonus, PointRule};
+
+    fn strike(event_type: &str, limb_type: LimbType, force: f32, confidence: f32) -> SimpleCombatEvent {
+        SimpleCombatEvent {
+            id: "evento-prueba".to_string(),
+            sequence: 1,
+            ring_id: "default".to_string(),
+            event_type: event_type.to_string(),
+            limb_name: limb_type.name().to_string(),
+            limb_type,
+            fighter_id: "fighter_1".to_string(),
+            competitor_name: "Ana".to_string(),
+            velocity: None,
+            acceleration: None,
+            force: Some(force),
+            timestamp: 0,
+            confidence,
+        }
+    }
+
+    // Bofetada a 1 punto en general y a 3 con la mano derecha; bonus de 2 desde 500 N
+    fn league_ruleset() -> ScoringRuleset {
+        ScoringRuleset {
+            name: "Liga".to_string(),
+            min_force: 100.0,
+            min_confidence: 0.5,
+            points: vec![
+                PointRule { event_type: "slap".to_string(), limb: Some(LimbType::RightHand), points: 3 },
+                PointRule { event_type: "slap".to_string(), limb: None, points: 1 },
+            ],
+            bonus: Some(ForceBonus { force_threshold: 500.0, points: 2 }),
+        }
+    }
+
+    #[test]
+    fn limb_specific_rule_takes_precedence_over_generic_rule() {
+        let ruleset = league_ruleset();
+
+        let right = evaluate_strike(&ruleset, &strike("slap", LimbType::RightHand, 200.0, 0.9));
+        assert!(right.scored);
+        assert_eq!(right.points, 3);
+
+        // La otra mano solo tiene la regla genérica
+        let left = evaluate_strike(&ruleset, &strike("slap", LimbType::LeftHand, 200.0, 0.9));
+        assert!(left.scored);
+        assert_eq!(left.points, 1);
+
+        // Sin regla para el tipo de golpe no puntúa
+        let kick = evaluate_strike(&ruleset, &strike("low_kick", LimbType::LeftFoot, 200.0, 0.9));
+        assert!(!kick.scored);
+        assert_eq!(kick.total(), 0);
+    }
+
+    #[test]
+    fn strikes_below_force_or_confidence_minimums_do_not_score() {
+        let ruleset = league_ruleset();
+
+        assert!(!evaluate_strike(&ruleset, &strike("slap", LimbType::LeftHand, 99.9, 0.9)).scored);
+        assert!(!evaluate_strike(&ruleset, &strike("slap", LimbType::LeftHand, 200.0, 0.49)).scored);
+
+        // Los mínimos son inclusivos
+        assert!(evaluate_strike(&ruleset, &strike("slap", LimbType::LeftHand, 100.0, 0.5)).scored);
+
+        // Un golpe sin fuerza medida cuenta como fuerza 0
+        let mut no_force = strike("slap", LimbType::LeftHand, 0.0, 0.9);
+        no_force.force = None;
+        assert!(!evaluate_strike(&ruleset, &no_force).scored);
+    }
+
+    #[test]
+    fn force_bonus_applies_from_its_threshold() {
+        let ruleset = league_ruleset();
+
+        let below = evaluate_strike(&ruleset, &strike("slap", LimbType::RightHand, 499.0, 0.9));
+        assert_eq!(below.bonus_points, 0);
+        assert_eq!(below.total(), 3);
+
+        let at_threshold = evaluate_strike(&ruleset, &strike("slap", LimbType::RightHand, 500.0, 0.9));
+        assert_eq!(at_threshold.bonus_points, 2);
+        assert_eq!(at_threshold.total(), 5);
+    }
+
+    #[test]
+    fn each_ring_scores_with_its_own_ruleset() {
+        let league_ring = "test-scoring-league";
+        let default_ring = "test-scoring-default";
+        let event = strike("slap", LimbType::RightHand, 50.0, 0.9);
+
+        set_active_ruleset(league_ring, league_ruleset());
+
+        // 50 N no llega al mínimo de la liga, pero puntúa con el reglamento por defecto
+        assert!(!evaluate_strike(&active_ruleset(league_ring), &event).scored);
+        let default_score = evaluate_strike(&active_ruleset(default_ring), &event);
+        assert!(default_score.scored);
+        assert_eq!(default_score.points, 1);
+
+        // Al volver al reglamento por defecto, el ring puntúa igual que los demás
+        clear_active_ruleset(league_ring);
+        assert!(evaluate_strike(&active_ruleset(league_ring), &event).scored);
+    }
+}
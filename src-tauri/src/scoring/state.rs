@@ -0,0 +1,110 @@
+//! Gestión de estado global del sistema de puntuación
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use tracing::info;
+
+use crate::scoring::types::{FighterScore, ScoreEntry, ScoringRuleset};
+
+/// Reglamentos thread-safe por ring (ring_id -> reglamento)
+type RulesetStore = Arc<Mutex<HashMap<String, ScoringRuleset>>>;
+
+/// Marcador thread-safe por peleador ((ring_id, fighter_id) -> puntuación)
+type ScoreTallyStore = Arc<Mutex<HashMap<(String, String), FighterScore>>>;
+
+/// Registro thread-safe de entradas de puntuación por ring (ring_id -> entradas en orden)
+type ScoreLedger = Arc<Mutex<HashMap<String, Vec<ScoreEntry>>>>;
+
+// Reglamento de puntuación en uso en cada ring; los rings sin entrada usan el de por defecto
+static ACTIVE_RULESETS: Lazy<RulesetStore> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+// Reglamento de los rings que no cargaron uno propio
+static DEFAULT_RULESET: Lazy<ScoringRuleset> = Lazy::new(ScoringRuleset::default);
+
+// Marcador en vivo (usando ring y fighter_id como clave)
+static SCORE_TALLIES: Lazy<ScoreTallyStore> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+// Registro de entradas de puntuación del combate en curso de cada ring (golpes y ajustes de jueces)
+static SCORE_LEDGER: Lazy<ScoreLedger> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Función para obtener los reglamentos cargados por ring
+pub fn get_active_rulesets_state() -> RulesetStore {
+    ACTIVE_RULESETS.clone()
+}
+
+/// Reglamento por defecto, el de los rings sin reglamento propio
+pub fn default_ruleset() -> &'static ScoringRuleset {
+    &DEFAULT_RULESET
+}
+
+/// Función para obtener el marcador en vivo
+pub fn get_score_tallies_state() -> ScoreTallyStore {
+    SCORE_TALLIES.clone()
+}
+
+/// Función para obtener el registro de puntuación
+pub fn get_score_ledger_state() -> ScoreLedger {
+    SCORE_LEDGER.clone()
+}
+
+/// Reglamento activo de un ring
+pub fn active_ruleset(ring_id: &str) -> ScoringRuleset {
+    let rulesets = get_active_rulesets_state();
+    let guard = rulesets.lock().unwrap_or_else(|e| e.into_inner());
+    guard.get(ring_id).unwrap_or(default_ruleset()).clone()
+}
+
+/// Reglamento cargado expresamente para un ring, si lo hay
+pub fn ring_ruleset(ring_id: &str) -> Option<ScoringRuleset> {
+    let rulesets = get_active_rulesets_state();
+    let guard = rulesets.lock().unwrap_or_else(|e| e.into_inner());
+    guard.get(ring_id).cloned()
+}
+
+/// Reemplaza el reglamento activo de un ring
+pub fn set_active_ruleset(ring_id: &str, ruleset: ScoringRuleset) {
+    let rulesets = get_active_rulesets_state();
+    let mut guard = rulesets.lock().unwrap_or_else(|e| e.into_inner());
+    info!(ring_id = %ring_id, ruleset = %ruleset.name, "📜 Reglamento de puntuación activado");
+    guard.insert(ring_id.to_string(), ruleset);
+}
+
+/// Vuelve al reglamento por defecto en un ring
+pub fn clear_active_ruleset(ring_id: &str) {
+    let rulesets = get_active_rulesets_state();
+    let mut guard = rulesets.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.remove(ring_id).is_some() {
+        info!(ring_id = %ring_id, "📜 Reglamento por defecto restablecido");
+    }
+}
+
+/// Marcador de los peleadores de un ring
+pub fn ring_score_tallies(ring_id: &str) -> Vec<FighterScore> {
+    let tallies = get_score_tallies_state();
+    let guard = tallies.lock().unwrap_or_else(|e| e.into_inner());
+    guard.values()
+        .filter(|score| score.ring_id == ring_id)
+        .cloned()
+        .collect()
+}
+
+/// Limpia el marcador y el registro de los peleadores de un ring
+pub fn clear_score_tallies(ring_id: &str) {
+    let tallies = get_score_tallies_state();
+    let mut guard = tallies.lock().unwrap_or_else(|e| e.into_inner());
+    guard.retain(|(ring, _), _| ring != ring_id);
+    drop(guard);
+
+    clear_score_ledger(ring_id);
+}
+
+/// Vacía el registro de puntuación de un ring (el marcador se conserva)
+pub fn clear_score_ledger(ring_id: &str) {
+    let ledger = get_score_ledger_state();
+    let mut guard = ledger.lock().unwrap_or_else(|e| e.into_inner());
+    guard.remove(ring_id);
+}
@@ -0,0 +1,189 @@
+//! Tipos y estructuras para el sistema de puntuación
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::ble::types::LimbType;
+use crate::bout::ring::default_ring_id;
+
+// Reglamento de puntuación cargable desde archivos TOML/JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoringRuleset {
+    pub name: String,                // Nombre de la liga o reglamento
+    #[serde(default)]
+    pub min_force: f32,              // Fuerza mínima para puntuar (N)
+    #[serde(default)]
+    pub min_confidence: f32,         // Confianza mínima para puntuar (0.0 - 1.0)
+    #[serde(default)]
+    pub points: Vec<PointRule>,      // Puntos por tipo de golpe y extremidad
+    #[serde(default)]
+    pub bonus: Option<ForceBonus>,   // Bonus por golpes por encima de un umbral de fuerza
+}
+
+// Puntos otorgados por un tipo de golpe, opcionalmente restringido a una extremidad
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointRule {
+    pub event_type: String,          // "slap", "low_kick"
+    #[serde(default)]
+    pub limb: Option<LimbType>,      // None = cualquier extremidad
+    pub points: i32,
+}
+
+// Bonus por fuerza
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceBonus {
+    pub force_threshold: f32,        // Fuerza a partir de la cual se otorga el bonus (N)
+    pub points: i32,
+}
+
+impl Default for ScoringRuleset {
+    fn default() -> Self {
+        Self {
+            name: "Estándar".to_string(),
+            min_force: 0.0,
+            min_confidence: 0.0,
+            points: vec![
+                PointRule { event_type: "slap".to_string(), limb: None, points: 1 },
+                PointRule { event_type: "low_kick".to_string(), limb: None, points: 1 },
+            ],
+            bonus: None,
+        }
+    }
+}
+
+impl ScoringRuleset {
+    /// Carga un reglamento desde un archivo `.toml` o `.json`
+    pub fn from_file(path: &Path) -> ScoringResult<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Error leyendo reglamento {}: {}", path.display(), e))?;
+
+        let ruleset: ScoringRuleset = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&content)
+                .map_err(|e| format!("Error parseando reglamento TOML: {}", e))?,
+            Some("json") => serde_json::from_str(&content)
+                .map_err(|e| format!("Error parseando reglamento JSON: {}", e))?,
+            _ => return Err(format!("Formato de reglamento no soportado: {}", path.display())),
+        };
+
+        ruleset.validate()?;
+        Ok(ruleset)
+    }
+
+    /// Verifica que los valores del reglamento sean coherentes
+    pub fn validate(&self) -> ScoringResult<()> {
+        if self.min_force < 0.0 {
+            return Err("La fuerza mínima no puede ser negativa".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err("La confianza mínima debe estar entre 0.0 y 1.0".to_string());
+        }
+        if let Some(bonus) = &self.bonus {
+            if bonus.force_threshold <= 0.0 {
+                return Err("El umbral de fuerza del bonus debe ser positivo".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+// Resultado de evaluar un golpe contra el reglamento
+#[derive(Debug, Clone, Serialize)]
+pub struct StrikeScore {
+    pub scored: bool,                // false si no supera los mínimos o no hay regla
+    pub points: i32,                 // Puntos base de la regla
+    pub bonus_points: i32,           // Puntos extra por fuerza
+}
+
+impl StrikeScore {
+    pub fn total(&self) -> i32 {
+        self.points + self.bonus_points
+    }
+}
+
+// Tipo de entrada en el registro de puntuación
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreEntryKind {
+    Strike,                          // Golpe detectado por los sensores
+    ManualPoint,                     // Punto añadido o quitado por un juez
+    Foul,                            // Falta señalada por un juez (sin puntos)
+    Penalty,                         // Deducción de puntos por un juez
+}
+
+// Entrada del registro de puntuación, base para recalcular el marcador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub id: String,                  // Identificador de la entrada (UUID)
+    #[serde(default = "default_ring_id")]
+    pub ring_id: String,
+    pub fighter_id: String,
+    pub kind: ScoreEntryKind,
+    pub scored: bool,                // false para golpes por debajo de los mínimos
+    pub points: i32,                 // Puntos base (negativos en deducciones)
+    pub bonus_points: i32,           // Puntos extra por fuerza
+    pub issued_by: Option<String>,   // Juez que originó la entrada
+    pub reason: Option<String>,
+    pub voided: bool,                // Entrada anulada (no cuenta en el marcador)
+    pub timestamp: u64,
+}
+
+impl ScoreEntry {
+    pub fn total(&self) -> i32 {
+        self.points + self.bonus_points
+    }
+}
+
+// Marcador en vivo por peleador
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FighterScore {
+    #[serde(default = "default_ring_id")]
+    pub ring_id: String,
+    pub fighter_id: String,          // "fighter_1", "fighter_2", etc.
+    pub competitor_name: String,
+    pub points: i32,                 // Total de puntos (incluye bonus y ajustes)
+    pub scoring_strikes: u32,        // Golpes que puntuaron
+    pub rejected_strikes: u32,       // Golpes por debajo de los mínimos
+    pub bonus_points: i32,           // Puntos obtenidos por bonus de fuerza
+    pub manual_points: i32,          // Ajustes manuales de los jueces
+    pub penalty_points: i32,         // Puntos deducidos por penalizaciones
+    pub fouls: u32,                  // Faltas señaladas
+}
+
+impl FighterScore {
+    pub fn new(ring_id: &str, fighter_id: &str, competitor_name: &str) -> Self {
+        Self {
+            ring_id: ring_id.to_string(),
+            fighter_id: fighter_id.to_string(),
+            competitor_name: competitor_name.to_string(),
+            points: 0,
+            scoring_strikes: 0,
+            rejected_strikes: 0,
+            bonus_points: 0,
+            manual_points: 0,
+            penalty_points: 0,
+            fouls: 0,
+        }
+    }
+
+    /// Acumula una entrada del registro en el marcador
+    pub fn apply_entry(&mut self, entry: &ScoreEntry) {
+        if entry.voided {
+            return;
+        }
+
+        match entry.kind {
+            ScoreEntryKind::Strike if entry.scored => {
+                self.scoring_strikes += 1;
+                self.bonus_points += entry.bonus_points;
+            }
+            ScoreEntryKind::Strike => self.rejected_strikes += 1,
+            ScoreEntryKind::ManualPoint => self.manual_points += entry.points,
+            ScoreEntryKind::Foul => self.fouls += 1,
+            ScoreEntryKind::Penalty => self.penalty_points -= entry.points,
+        }
+        self.points += entry.total();
+    }
+}
+
+// Tipo de resultado para operaciones de puntuación
+pub type ScoringResult<T> = Result<T, String>;
@@ -0,0 +1,36 @@
+//! Cierre ordenado de la app
+//!
+//! Al salir se cortan los enlaces BLE para que los sensores vuelvan a
+//! anunciarse en la siguiente sesión, se detiene el servidor cerrando cada
+//! WebSocket con su frame de cierre y se vuelca a disco lo pendiente.
+
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::ble::connection::shutdown_devices;
+use crate::ble::state::BleManager;
+use crate::bout::recovery::flush_recovery_snapshot;
+use crate::broadcast_ws::server::stop_ws_server;
+use crate::webhooks::delivery::persist_queue;
+
+/// Tiempo máximo que el cierre puede retrasar la salida
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Libera sensores y servidor y guarda el estado antes de terminar el proceso
+pub async fn shutdown_app(ble: &BleManager) {
+    info!("👋 Cerrando la app de forma ordenada");
+
+    let release = async {
+        shutdown_devices(ble).await;
+        stop_ws_server(ble.hub()).await;
+    };
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, release).await.is_err() {
+        warn!("El cierre de sensores y servidor tardó demasiado; se sale igualmente");
+    }
+
+    // Lo pendiente se guarda aunque el hardware no haya respondido
+    flush_recovery_snapshot(ble);
+    persist_queue();
+
+    info!("✅ Cierre ordenado completado");
+}
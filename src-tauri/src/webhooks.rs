@@ -0,0 +1,132 @@
+//! Módulo de webhooks - Notificaciones firmadas a sistemas externos
+//!
+//! Envía por POST los hitos del combate (inicio y fin, fin de round, nuevos
+//! récords y resultados finales) a las URLs suscritas. El ciclo de vida se
+//! deduce de los cambios de vista y de round que envía la app, por separado
+//! en cada ring; todas las notificaciones llevan el `ring_id`.
+
+pub mod types;
+pub mod state;
+pub mod delivery;
+#[cfg(feature = "desktop")]
+pub mod commands;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use crate::ble::state::BleManager;
+use crate::ble::types::CompetitorMaxStats;
+use crate::broadcast_ws::api::fighter_stats;
+use crate::webhooks::delivery::enqueue_webhook;
+use crate::webhooks::types::WebhookEvent;
+
+// Progreso del combate según los webhooks ya emitidos
+#[derive(Default)]
+struct BoutProgress {
+    active: bool,
+    last_ended_round: Option<u32>,   // Evita notificar dos veces el mismo fin de round
+}
+
+// Progreso del combate de cada ring (ring_id -> progreso)
+static BOUT_PROGRESS: Lazy<Mutex<HashMap<String, BoutProgress>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Traduce un cambio de vista de un ring a eventos del ciclo de vida del combate
+pub fn notify_view_change(ble: &BleManager, ring_id: &str, view_type: &str, data: &serde_json::Value) {
+    match view_type {
+        "cover" => {
+            {
+                let Ok(mut progress) = BOUT_PROGRESS.lock() else {
+                    return;
+                };
+                let progress = progress.entry(ring_id.to_string()).or_default();
+                // Volver a mostrar la portada no reinicia un combate en curso
+                if progress.active {
+                    return;
+                }
+                *progress = BoutProgress { active: true, last_ended_round: None };
+            }
+            let mut payload = data.clone();
+            if let Some(payload) = payload.as_object_mut() {
+                payload.insert("ring_id".to_string(), ring_id.into());
+            }
+            enqueue_webhook(WebhookEvent::BoutStarted, payload);
+        }
+        "combat_finished" => notify_bout_end(ble, ring_id, "finished", true),
+        "combat_cancelled" => notify_bout_end(ble, ring_id, "cancelled", false),
+        _ => {}
+    }
+}
+
+/// Retoma un combate recuperado tras un cierre inesperado sin volver a notificar su inicio
+pub fn resume_bout_progress(ring_id: &str, last_ended_round: Option<u32>) {
+    if let Ok(mut progress) = BOUT_PROGRESS.lock() {
+        progress.insert(ring_id.to_string(), BoutProgress { active: true, last_ended_round });
+    }
+}
+
+/// Notifica el fin de un round si aún no se había notificado
+pub fn notify_round_end(ble: &BleManager, ring_id: &str, round: u32, rounds: Option<u32>) {
+    {
+        let Ok(mut progress) = BOUT_PROGRESS.lock() else {
+            return;
+        };
+        let progress = progress.entry(ring_id.to_string()).or_default();
+        if progress.last_ended_round.is_some_and(|last| last >= round) {
+            return;
+        }
+        progress.last_ended_round = Some(round);
+    }
+
+    enqueue_webhook(WebhookEvent::RoundEnded, serde_json::json!({
+        "ring_id": ring_id,
+        "round": round,
+        "rounds": rounds,
+        "stats": fighter_stats(ble, ring_id),
+    }));
+}
+
+/// Notifica un nuevo récord máximo de un peleador
+pub fn notify_new_max_record(stats: &CompetitorMaxStats, records: &[&str]) {
+    enqueue_webhook(WebhookEvent::NewMaxRecord, serde_json::json!({
+        "ring_id": stats.ring_id,
+        "fighter_id": stats.fighter_id,
+        "competitor_name": stats.competitor_name,
+        "records": records,
+        "stats": stats,
+    }));
+}
+
+/// Cierra el combate; los resultados finales solo se envían si terminó normalmente
+fn notify_bout_end(ble: &BleManager, ring_id: &str, reason: &str, with_results: bool) {
+    {
+        let Ok(mut progress) = BOUT_PROGRESS.lock() else {
+            return;
+        };
+        // La app también envía `combat_finished` al limpiar datos viejos al arrancar
+        match progress.get_mut(ring_id) {
+            Some(progress) if progress.active => progress.active = false,
+            _ => return,
+        }
+    }
+
+    // El último round no tiene un cambio de round que lo cierre: se cierra con el combate
+    let battle_config = ble.hub().ring(ring_id).views().last_battle_config();
+    if let Some(config) = &battle_config {
+        notify_round_end(ble, ring_id, config.current_round, Some(config.rounds));
+    }
+
+    enqueue_webhook(WebhookEvent::BoutEnded, serde_json::json!({
+        "ring_id": ring_id,
+        "reason": reason,
+        "battle_config": battle_config,
+    }));
+
+    if with_results {
+        enqueue_webhook(WebhookEvent::FinalResults, serde_json::json!({
+            "ring_id": ring_id,
+            "battle_config": battle_config,
+            "fighters": fighter_stats(ble, ring_id),
+        }));
+    }
+}
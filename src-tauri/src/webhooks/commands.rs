@@ -0,0 +1,129 @@
+//! Comandos Tauri para gestionar las suscripciones de webhooks
+
+use tracing::info;
+
+use crate::webhooks::delivery::send_test_webhook;
+use crate::webhooks::state::{
+    find_subscription, get_delivery_queue_state, get_webhook_subscriptions_state,
+    save_webhook_subscriptions,
+};
+use crate::webhooks::types::{PendingDelivery, WebhookEvent, WebhookResult, WebhookSubscription};
+
+fn validate_url(url: &str) -> WebhookResult<()> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err("La URL del webhook debe empezar por http:// o https://".to_string());
+    }
+    Ok(())
+}
+
+/// Lista las suscripciones configuradas
+#[tauri::command]
+pub fn list_webhooks() -> WebhookResult<Vec<WebhookSubscription>> {
+    let subscriptions = get_webhook_subscriptions_state();
+    let subscriptions = subscriptions.lock()
+        .map_err(|e| format!("Error accediendo a los webhooks: {}", e))?;
+    Ok(subscriptions.clone())
+}
+
+/// Crea una suscripción; si no se indica secreto se genera uno
+#[tauri::command]
+pub fn create_webhook(
+    url: String,
+    label: String,
+    events: Vec<WebhookEvent>,
+    secret: Option<String>,
+) -> WebhookResult<WebhookSubscription> {
+    let url = url.trim().to_string();
+    validate_url(&url)?;
+
+    let subscription = WebhookSubscription {
+        id: uuid::Uuid::new_v4().to_string(),
+        url,
+        secret: secret
+            .filter(|secret| !secret.trim().is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()),
+        events,
+        enabled: true,
+        label: label.trim().to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    };
+
+    let subscriptions = get_webhook_subscriptions_state();
+    let mut subscriptions = subscriptions.lock()
+        .map_err(|e| format!("Error accediendo a los webhooks: {}", e))?;
+    subscriptions.push(subscription.clone());
+    save_webhook_subscriptions(&subscriptions)?;
+
+    info!(url = %subscription.url, events = ?subscription.events, "🪝 Webhook creado");
+    Ok(subscription)
+}
+
+/// Actualiza URL, eventos o estado de una suscripción (el secreto se conserva)
+#[tauri::command]
+pub fn update_webhook(
+    id: String,
+    url: Option<String>,
+    events: Option<Vec<WebhookEvent>>,
+    enabled: Option<bool>,
+) -> WebhookResult<WebhookSubscription> {
+    let subscriptions = get_webhook_subscriptions_state();
+    let mut subscriptions = subscriptions.lock()
+        .map_err(|e| format!("Error accediendo a los webhooks: {}", e))?;
+    let subscription = subscriptions.iter_mut()
+        .find(|subscription| subscription.id == id)
+        .ok_or_else(|| "No existe ese webhook".to_string())?;
+
+    if let Some(url) = url {
+        let url = url.trim().to_string();
+        validate_url(&url)?;
+        subscription.url = url;
+    }
+    if let Some(events) = events {
+        subscription.events = events;
+    }
+    if let Some(enabled) = enabled {
+        subscription.enabled = enabled;
+    }
+
+    let updated = subscription.clone();
+    save_webhook_subscriptions(&subscriptions)?;
+    Ok(updated)
+}
+
+/// Elimina una suscripción; sus entregas pendientes se descartan
+#[tauri::command]
+pub fn delete_webhook(id: String) -> WebhookResult<String> {
+    let subscriptions = get_webhook_subscriptions_state();
+    let mut subscriptions = subscriptions.lock()
+        .map_err(|e| format!("Error accediendo a los webhooks: {}", e))?;
+    let index = subscriptions.iter()
+        .position(|subscription| subscription.id == id)
+        .ok_or_else(|| "No existe ese webhook".to_string())?;
+
+    let removed = subscriptions.remove(index);
+    save_webhook_subscriptions(&subscriptions)?;
+
+    info!(url = %removed.url, "🪝 Webhook eliminado");
+    Ok(format!("Webhook '{}' eliminado", removed.label))
+}
+
+/// Entregas pendientes o en reintento
+#[tauri::command]
+pub fn list_pending_webhook_deliveries() -> WebhookResult<Vec<PendingDelivery>> {
+    let queue = get_delivery_queue_state();
+    let queue = queue.lock()
+        .map_err(|e| format!("Error accediendo a la cola de webhooks: {}", e))?;
+    Ok(queue.clone())
+}
+
+/// Envía un evento de prueba firmado a una suscripción
+#[tauri::command]
+pub async fn test_webhook(id: String) -> WebhookResult<String> {
+    let subscription = find_subscription(&id)
+        .ok_or_else(|| "No existe ese webhook".to_string())?;
+    send_test_webhook(&subscription).await?;
+    Ok(format!("Webhook de prueba entregado a {}", subscription.url))
+}
@@ -0,0 +1,302 @@
+//! Entrega firmada de webhooks con reintentos
+//!
+//! Cada entrega se firma con HMAC-SHA256 sobre `<timestamp>.<cuerpo>` usando
+//! el secreto de la suscripción. La firma va en `X-BeatHard-Signature` como
+//! `sha256=<hex>` y el timestamp en `X-BeatHard-Timestamp`, para que el
+//! receptor pueda rechazar reenvíos antiguos. Las entregas fallidas se
+//! reintentan con espera exponencial y sobreviven a un reinicio de la app.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::{debug, info, warn};
+
+use crate::webhooks::state::{
+    delivery_notify, find_subscription, get_delivery_queue_state, get_webhook_subscriptions_state,
+    save_delivery_queue, MAX_PENDING_DELIVERIES,
+};
+use crate::webhooks::types::{PendingDelivery, WebhookEvent, WebhookResult, WebhookSubscription};
+
+/// Intentos antes de abandonar una entrega
+const MAX_DELIVERY_ATTEMPTS: u32 = 12;
+
+/// Espera del primer reintento y máxima entre reintentos
+const INITIAL_RETRY_DELAY_MS: u64 = 5_000;
+const MAX_RETRY_DELAY_MS: u64 = 15 * 60 * 1000;
+
+/// Tiempo máximo de respuesta del receptor
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Revisión periódica de la cola aunque no haya avisos
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Receptores a los que se entrega a la vez
+const MAX_CONCURRENT_ENDPOINTS: usize = 8;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Ordena las escrituras de la cola en disco sin bloquear a quien encola
+static PERSIST_LOCK: Mutex<()> = Mutex::new(());
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Encola el evento para todas las suscripciones interesadas
+pub fn enqueue_webhook(event: WebhookEvent, data: serde_json::Value) {
+    let subscribers: Vec<String> = get_webhook_subscriptions_state().lock()
+        .map(|subscriptions| subscriptions.iter()
+            .filter(|subscription| subscription.accepts(event))
+            .map(|subscription| subscription.id.clone())
+            .collect())
+        .unwrap_or_default();
+    if subscribers.is_empty() {
+        return;
+    }
+
+    let now = now_ms();
+    let body = serde_json::json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "event": event,
+        "timestamp": now,
+        "data": data,
+    }).to_string();
+
+    let queue = get_delivery_queue_state();
+    let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+    queue.extend(subscribers.into_iter().map(|subscription_id| PendingDelivery {
+        id: uuid::Uuid::new_v4().to_string(),
+        subscription_id,
+        event,
+        body: body.clone(),
+        attempts: 0,
+        next_attempt_at: now,
+        created_at: now,
+        last_error: None,
+    }));
+
+    if queue.len() > MAX_PENDING_DELIVERIES {
+        let overflow = queue.len() - MAX_PENDING_DELIVERIES;
+        queue.drain(..overflow);
+        warn!(discarded = overflow, "🪝 Cola de webhooks llena, entregas antiguas descartadas");
+    }
+    drop(queue);
+
+    debug!(event = event.as_str(), "🪝 Webhook encolado");
+    delivery_notify().notify_one();
+}
+
+// Resultado de un intento de entrega
+enum DeliveryOutcome {
+    Delivered,
+    Failed(String),
+    Discarded,                       // La suscripción ya no existe o está desactivada
+}
+
+/// Repartidor de entregas; se ejecuta durante toda la vida de la app
+pub async fn run_delivery_worker() {
+    let client = match http_client() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, "No se pudo crear el cliente HTTP de webhooks");
+            return;
+        }
+    };
+
+    loop {
+        let now = now_ms();
+        let due: Vec<PendingDelivery> = get_delivery_queue_state().lock()
+            .map(|queue| queue.iter().filter(|delivery| delivery.next_attempt_at <= now).cloned().collect())
+            .unwrap_or_default();
+
+        if !due.is_empty() {
+            // Agrupadas por receptor: uno lento o caído no retrasa a los demás
+            let mut by_endpoint: HashMap<String, Vec<PendingDelivery>> = HashMap::new();
+            for delivery in due {
+                by_endpoint.entry(delivery.subscription_id.clone()).or_default().push(delivery);
+            }
+            futures::stream::iter(by_endpoint.into_values())
+                .for_each_concurrent(MAX_CONCURRENT_ENDPOINTS, |deliveries| deliver_to_endpoint(&client, deliveries))
+                .await;
+            persist_queue();
+            continue;
+        }
+
+        // Dormir hasta el próximo reintento o hasta que llegue algo nuevo
+        let wait = get_delivery_queue_state().lock()
+            .ok()
+            .and_then(|queue| queue.iter().map(|delivery| delivery.next_attempt_at).min())
+            .map(|next| Duration::from_millis(next.saturating_sub(now_ms())).min(IDLE_POLL_INTERVAL))
+            .unwrap_or(IDLE_POLL_INTERVAL);
+
+        tokio::select! {
+            // Guardar lo recién encolado antes de intentar entregarlo
+            _ = delivery_notify().notified() => persist_queue(),
+            _ = tokio::time::sleep(wait) => {}
+        }
+    }
+}
+
+/// Entrega en orden lo pendiente de un receptor; tras un fallo, el resto espera al siguiente reintento
+async fn deliver_to_endpoint(client: &reqwest::Client, deliveries: Vec<PendingDelivery>) {
+    let Some(first) = deliveries.first() else {
+        return;
+    };
+    let subscription = find_subscription(&first.subscription_id).filter(|subscription| subscription.enabled);
+
+    for (index, delivery) in deliveries.iter().enumerate() {
+        let outcome = match &subscription {
+            Some(subscription) => {
+                match send_signed(client, subscription, delivery.event.as_str(), &delivery.id, &delivery.body).await {
+                    Ok(()) => DeliveryOutcome::Delivered,
+                    Err(e) => DeliveryOutcome::Failed(e),
+                }
+            }
+            None => DeliveryOutcome::Discarded,
+        };
+        let failed = matches!(outcome, DeliveryOutcome::Failed(_));
+        record_outcome(&delivery.id, outcome);
+
+        if failed {
+            postpone_deliveries(&deliveries[index + 1..], now_ms() + INITIAL_RETRY_DELAY_MS);
+            return;
+        }
+    }
+}
+
+/// Aplaza entregas sin contarlo como intento (su receptor acaba de fallar)
+fn postpone_deliveries(deliveries: &[PendingDelivery], until: u64) {
+    if deliveries.is_empty() {
+        return;
+    }
+    let queue = get_delivery_queue_state();
+    let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+    for pending in queue.iter_mut() {
+        if deliveries.iter().any(|delivery| delivery.id == pending.id) {
+            pending.next_attempt_at = pending.next_attempt_at.max(until);
+        }
+    }
+}
+
+/// Envía un evento de prueba a una suscripción sin pasar por la cola
+pub async fn send_test_webhook(subscription: &WebhookSubscription) -> WebhookResult<()> {
+    let client = http_client()?;
+    let body = serde_json::json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "event": "test",
+        "timestamp": now_ms(),
+        "data": { "message": "Webhook de prueba de Beat Hard Combat" },
+    }).to_string();
+
+    let delivery_id = uuid::Uuid::new_v4().to_string();
+    send_signed(&client, subscription, "test", &delivery_id, &body).await
+}
+
+fn http_client() -> WebhookResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .user_agent(concat!("BeatHardCombat-Webhooks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+async fn send_signed(
+    client: &reqwest::Client,
+    subscription: &WebhookSubscription,
+    event: &str,
+    delivery_id: &str,
+    body: &str,
+) -> WebhookResult<()> {
+    let timestamp = (now_ms() / 1000).to_string();
+    let signature = sign_payload(&subscription.secret, &timestamp, body)?;
+
+    let response = client.post(&subscription.url)
+        .header("Content-Type", "application/json")
+        .header("X-BeatHard-Event", event)
+        .header("X-BeatHard-Delivery", delivery_id)
+        .header("X-BeatHard-Timestamp", &timestamp)
+        .header("X-BeatHard-Signature", format!("sha256={}", signature))
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Error de conexión: {}", e))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(format!("El receptor respondió {}", status))
+    }
+}
+
+/// Firma HMAC-SHA256 de `<timestamp>.<cuerpo>` en hexadecimal
+fn sign_payload(secret: &str, timestamp: &str, body: &str) -> WebhookResult<String> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|e| format!("Secreto de webhook inválido: {}", e))?;
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    Ok(mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Espera antes del reintento número `attempts`
+fn retry_delay_ms(attempts: u32) -> u64 {
+    INITIAL_RETRY_DELAY_MS
+        .saturating_mul(1u64 << attempts.saturating_sub(1).min(20))
+        .min(MAX_RETRY_DELAY_MS)
+}
+
+fn record_outcome(delivery_id: &str, outcome: DeliveryOutcome) {
+    let queue = get_delivery_queue_state();
+    let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(index) = queue.iter().position(|delivery| delivery.id == delivery_id) else {
+        return;
+    };
+
+    match outcome {
+        DeliveryOutcome::Delivered => {
+            let delivery = queue.remove(index);
+            info!(event = delivery.event.as_str(), attempts = delivery.attempts + 1, "🪝 Webhook entregado");
+        }
+        DeliveryOutcome::Discarded => {
+            let delivery = queue.remove(index);
+            debug!(event = delivery.event.as_str(), "Webhook descartado: suscripción inexistente o desactivada");
+        }
+        DeliveryOutcome::Failed(error) => {
+            let delivery = &mut queue[index];
+            delivery.attempts += 1;
+            if delivery.attempts >= MAX_DELIVERY_ATTEMPTS {
+                warn!(event = delivery.event.as_str(), attempts = delivery.attempts, error = %error,
+                      "🪝 Webhook abandonado tras agotar los reintentos");
+                queue.remove(index);
+            } else {
+                let delay = retry_delay_ms(delivery.attempts);
+                warn!(event = delivery.event.as_str(), attempts = delivery.attempts, retry_in_ms = delay, error = %error,
+                      "🪝 Entrega de webhook fallida, se reintentará");
+                delivery.next_attempt_at = now_ms() + delay;
+                delivery.last_error = Some(error);
+            }
+        }
+    }
+}
+
+/// Guarda la cola tal como está (también al salir, para no perder lo recién encolado)
+///
+/// La cola se copia y se suelta antes de escribir, para que encolar desde la
+/// ruta de los golpes nunca espere al disco.
+pub fn persist_queue() {
+    let _persisting = PERSIST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let queue = get_delivery_queue_state().lock().unwrap_or_else(|e| e.into_inner()).clone();
+    save_delivery_queue(&queue);
+}
@@ -0,0 +1,97 @@
+//! Suscripciones y cola de entregas de webhooks, persistidas en disco
+
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use crate::broadcast_ws::settings::{config_file_path, read_config_file, write_config_file};
+use crate::webhooks::types::{PendingDelivery, WebhookResult, WebhookSubscription};
+
+/// Archivos de suscripciones y de entregas pendientes
+const SUBSCRIPTIONS_FILE_NAME: &str = "webhooks.json";
+const QUEUE_FILE_NAME: &str = "webhook_queue.json";
+
+/// Entregas pendientes máximas; al superarlas se descartan las más antiguas
+pub const MAX_PENDING_DELIVERIES: usize = 1000;
+
+/// Lista thread-safe de suscripciones
+type WebhookSubscriptions = Arc<Mutex<Vec<WebhookSubscription>>>;
+
+/// Cola thread-safe de entregas pendientes
+type DeliveryQueue = Arc<Mutex<Vec<PendingDelivery>>>;
+
+// Suscripciones configuradas
+static WEBHOOK_SUBSCRIPTIONS: Lazy<WebhookSubscriptions> =
+    Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
+
+// Entregas pendientes o en reintento
+static DELIVERY_QUEUE: Lazy<DeliveryQueue> =
+    Lazy::new(|| Arc::new(Mutex::new(Vec::new())));
+
+// Despierta al repartidor cuando hay entregas nuevas
+static DELIVERY_NOTIFY: Lazy<Notify> = Lazy::new(Notify::new);
+
+/// Función para obtener las suscripciones
+pub fn get_webhook_subscriptions_state() -> WebhookSubscriptions {
+    WEBHOOK_SUBSCRIPTIONS.clone()
+}
+
+/// Función para obtener la cola de entregas
+pub fn get_delivery_queue_state() -> DeliveryQueue {
+    DELIVERY_QUEUE.clone()
+}
+
+/// Aviso al repartidor de entregas
+pub fn delivery_notify() -> &'static Notify {
+    &DELIVERY_NOTIFY
+}
+
+/// Carga suscripciones y entregas pendientes guardadas
+pub fn load_webhooks() {
+    match read_config_file::<Vec<WebhookSubscription>>(SUBSCRIPTIONS_FILE_NAME) {
+        Ok(Some(subscriptions)) => {
+            info!(count = subscriptions.len(), "🪝 Suscripciones de webhooks cargadas");
+            *get_webhook_subscriptions_state().lock().unwrap_or_else(|e| e.into_inner()) = subscriptions;
+        }
+        Ok(None) => {}
+        Err(e) => warn!(error = %e, "Archivo de webhooks inválido, ignorando"),
+    }
+
+    match read_config_file::<Vec<PendingDelivery>>(QUEUE_FILE_NAME) {
+        Ok(Some(deliveries)) => {
+            if !deliveries.is_empty() {
+                info!(count = deliveries.len(), "🪝 Entregas de webhooks pendientes recuperadas");
+            }
+            *get_delivery_queue_state().lock().unwrap_or_else(|e| e.into_inner()) = deliveries;
+        }
+        Ok(None) => {}
+        Err(e) => warn!(error = %e, "Cola de webhooks inválida, ignorando"),
+    }
+}
+
+/// Guarda las suscripciones en disco
+pub fn save_webhook_subscriptions(subscriptions: &[WebhookSubscription]) -> WebhookResult<()> {
+    if config_file_path(SUBSCRIPTIONS_FILE_NAME).is_none() {
+        return Ok(());
+    }
+    write_config_file(SUBSCRIPTIONS_FILE_NAME, &subscriptions).map(|_| ())
+}
+
+/// Guarda la cola de entregas en disco
+pub fn save_delivery_queue(queue: &[PendingDelivery]) {
+    if config_file_path(QUEUE_FILE_NAME).is_none() {
+        return;
+    }
+    if let Err(e) = write_config_file(QUEUE_FILE_NAME, &queue) {
+        warn!(error = %e, "No se pudo guardar la cola de webhooks");
+    }
+}
+
+/// Copia de una suscripción por id
+pub fn find_subscription(subscription_id: &str) -> Option<WebhookSubscription> {
+    get_webhook_subscriptions_state().lock().ok()?
+        .iter()
+        .find(|subscription| subscription.id == subscription_id)
+        .cloned()
+}
@@ -0,0 +1,61 @@
+//! Tipos de las suscripciones y entregas de webhooks
+
+use serde::{Deserialize, Serialize};
+
+// Eventos que pueden disparar un webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    BoutStarted,
+    BoutEnded,
+    RoundEnded,
+    NewMaxRecord,
+    FinalResults,
+}
+
+impl WebhookEvent {
+    /// Nombre del evento en la cabecera `X-BeatHard-Event`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::BoutStarted => "bout_started",
+            WebhookEvent::BoutEnded => "bout_ended",
+            WebhookEvent::RoundEnded => "round_ended",
+            WebhookEvent::NewMaxRecord => "new_max_record",
+            WebhookEvent::FinalResults => "final_results",
+        }
+    }
+}
+
+// Suscripción de un sistema externo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub url: String,
+    pub secret: String,              // Clave HMAC-SHA256 para firmar el cuerpo
+    pub events: Vec<WebhookEvent>,   // Vacío = todos los eventos
+    pub enabled: bool,
+    pub label: String,
+    pub created_at: u64,
+}
+
+impl WebhookSubscription {
+    pub fn accepts(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+// Entrega pendiente; se guarda en disco hasta que el receptor responde 2xx
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingDelivery {
+    pub id: String,
+    pub subscription_id: String,
+    pub event: WebhookEvent,
+    pub body: String,                // JSON firmado tal cual se envía
+    pub attempts: u32,
+    pub next_attempt_at: u64,        // Timestamp en ms
+    pub created_at: u64,
+    pub last_error: Option<String>,
+}
+
+// Tipo de resultado para operaciones de webhooks
+pub type WebhookResult<T> = Result<T, String>;
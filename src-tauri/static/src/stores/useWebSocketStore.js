@@ -1,6 +1,22 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 
+// Versión del protocolo WebSocket que entiende este cliente
+const PROTOCOL_VERSION = 1;
+
+// Servidor por defecto cuando las pantallas se sirven desde el dev server de Vite
+const DEV_SERVER_HOST = '127.0.0.1:8080';
+
+// Las pantallas las sirve el mismo servidor del WebSocket, así que se usa su host y puerto
+const getWebSocketUrl = () => {
+  const host = import.meta.env.DEV ? DEV_SERVER_HOST : window.location.host;
+  const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+  // El token de acceso llega en la URL de la pantalla (?token=...) y se reenvía al WebSocket
+  const token = new URLSearchParams(window.location.search).get('token');
+  const query = token ? `?token=${encodeURIComponent(token)}` : '';
+  return `${protocol}//${host}/ws${query}`;
+};
+
 const useWebSocketStore = create(
   persist(
     (set, get) => ({
@@ -211,10 +227,11 @@ const useWebSocketStore = create(
 
       // Inicializar WebSocket
       initWebSocket: () => {
-        const ws = new WebSocket('ws://127.0.0.1:8080/ws');
+        const ws = new WebSocket(getWebSocketUrl());
         
         ws.onopen = () => {
           console.info('WebSocket connected');
+          ws.send(JSON.stringify({ type: 'hello', protocol_version: PROTOCOL_VERSION }));
           set({ ws, isConnected: true });
         };
 
@@ -231,114 +248,133 @@ const useWebSocketStore = create(
         ws.onmessage = (event) => {
           const receivedData = JSON.parse(event.data);
 
-          // Si es finalización o cancelación de combate, limpiar datos persistentes
-          if (receivedData.viewType === 'combat_finished' || 
-              receivedData.viewType === 'combat_cancelled') {
-            get().clearPersistedData();
-            return;
-          }
-
-          // Si es cambio a cover, solo cambiar vista sin limpiar datos
-          if (receivedData.viewType === 'cover') {
-            set({
-              currentView: 'cover',
-              viewData: receivedData
-            });
-            return;
-          }
-
-          // Si es round_advance, solo actualizar el round sin cambiar vista
-          if (receivedData.viewType === 'round_advance') {
-            get().advanceToNextRound();
+          if (receivedData.protocol_version !== PROTOCOL_VERSION) {
+            console.warn('Unsupported protocol version:', receivedData.protocol_version);
             return;
           }
 
-          // Si es live-combat, actualizar datos y cambiar vista si es necesario
-          if (receivedData.viewType === 'live-combat') {
-            const currentState = get();
-            const fighterData = receivedData.data;
-            
-            // Si no hay fighter_id, es solo un cambio de vista (no un evento de combate)
-            if (!fighterData || !fighterData.fighter_id) {
-              // Solo cambiar vista si no estamos ya en live-combat
-              if (currentState.currentView !== 'live-combat') {
-                set({
-                  currentView: 'live-combat',
-                  viewData: receivedData
-                });
-              }
+          switch (receivedData.type) {
+            case 'snapshot':
+              get().applySnapshot(receivedData.data);
+              return;
+            case 'view_change':
+              get().handleViewChange(receivedData);
+              return;
+            case 'combat_event':
+              get().handleCombatEvent(receivedData);
+              return;
+            case 'max_stats_update':
+              get().updateMaxStats(receivedData.data);
+              return;
+            case 'max_stats_reset':
+              set({ maxStatsData: {} });
+              return;
+            default:
+              // hello, battle_config, score_update, etc. no afectan a la vista
               return;
-            }
-            
-            // Preparar actualización de combatData
-            const currentFighter = currentState.combatData[fighterData.fighter_id] || { 
-              name: fighterData.competitor_name, 
-              hitHistory: [],
-              totalHits: 0
-            };
-            
-            const newHit = {
-              force: fighterData.force,
-              velocity: fighterData.velocity,
-              acceleration: fighterData.acceleration,
-              timestamp: fighterData.timestamp,
-              id: Date.now() + Math.random(),
-              expiresAt: Date.now() + 5000
-            };
-            
-            const now = Date.now();
-            const validHits = currentFighter.hitHistory.filter(hit => hit.expiresAt > now);
-            const updatedHistory = [newHit, ...validHits].slice(0, 3);
-            
-            const updatedCombatData = {
-              ...currentState.combatData,
-              [fighterData.fighter_id]: {
-                name: fighterData.competitor_name,
-                lastHit: newHit,
-                hitHistory: updatedHistory,
-                totalHits: (currentFighter.totalHits || 0) + 1
-              }
-            };
-            
-            // Actualización atómica única
-            if (currentState.currentView !== 'live-combat') {
-              set({
-                combatData: updatedCombatData,
-                currentView: 'live-combat',
-                viewData: receivedData
-              });
-            } else {
-              // Solo actualizar combatData si ya estamos en live-combat
-              set({
-                combatData: updatedCombatData
-              });
-            }
-            
-            return;
           }
+        };
 
-          // Si es resumen, solo cambiar vista sin limpiar datos
-          if (receivedData.viewType === 'summary-stats') {
-            set({
-              viewData: receivedData,
-              currentView: 'summary-stats',
-            });
-            return;
-          }
+        set({ ws });
+      },
+
+      // Estado actual recibido al (re)conectar a mitad de combate
+      applySnapshot: (snapshot) => {
+        const maxStatsData = {};
+        snapshot.max_stats.forEach(stats => {
+          maxStatsData[stats.fighter_id] = stats;
+        });
+        set({ maxStatsData });
 
-          // Para otros tipos de mensaje, manejar cambios de vista normalmente
+        // Solo restaurar vistas de pantalla, no acciones como round_advance
+        const view = snapshot.active_view;
+        const controlViews = ['combat_finished', 'combat_cancelled', 'round_advance'];
+        if (view && !controlViews.includes(view.view_type)) {
           set({
-            viewData: receivedData,
-            currentView: receivedData.viewType,
-          })
+            currentView: view.view_type,
+            viewData: { type: 'view_change', ...view },
+          });
+        }
+      },
+
+      // Cambio de vista enviado por el operador
+      handleViewChange: (receivedData) => {
+        const viewType = receivedData.view_type;
+
+        // Si es finalización o cancelación de combate, limpiar datos persistentes
+        if (viewType === 'combat_finished' || viewType === 'combat_cancelled') {
+          get().clearPersistedData();
+          return;
+        }
+
+        // Si es round_advance, solo actualizar el round sin cambiar vista
+        if (viewType === 'round_advance') {
+          get().advanceToNextRound();
+          return;
+        }
+
+        // live-combat sin evento: solo cambiar vista si no estamos ya en ella
+        if (viewType === 'live-combat' && get().currentView === 'live-combat') {
+          return;
+        }
+
+        set({
+          currentView: viewType,
+          viewData: receivedData,
+        });
+      },
+
+      // Evento de combate detectado por los sensores
+      handleCombatEvent: (receivedData) => {
+        const currentState = get();
+        const fighterData = receivedData.data;
+
+        if (!fighterData || !fighterData.fighter_id) {
+          console.warn('fighter_id is undefined or null, skipping update');
+          return;
+        }
+
+        const currentFighter = currentState.combatData[fighterData.fighter_id] || {
+          name: fighterData.competitor_name,
+          hitHistory: [],
+          totalHits: 0
+        };
 
-          // Si es max_stats_update, actualizar solo ese peleador
-          if (receivedData.type === 'max_stats_update') {
-            get().updateMaxStats(receivedData.data);
+        const newHit = {
+          force: fighterData.force,
+          velocity: fighterData.velocity,
+          acceleration: fighterData.acceleration,
+          timestamp: fighterData.timestamp,
+          id: fighterData.id,
+          expiresAt: Date.now() + 5000
+        };
+
+        const now = Date.now();
+        const validHits = currentFighter.hitHistory.filter(hit => hit.expiresAt > now);
+        const updatedHistory = [newHit, ...validHits].slice(0, 3);
+
+        const updatedCombatData = {
+          ...currentState.combatData,
+          [fighterData.fighter_id]: {
+            name: fighterData.competitor_name,
+            lastHit: newHit,
+            hitHistory: updatedHistory,
+            totalHits: (currentFighter.totalHits || 0) + 1
           }
         };
 
-        set({ ws });
+        // Actualización atómica única
+        if (currentState.currentView !== 'live-combat') {
+          set({
+            combatData: updatedCombatData,
+            currentView: 'live-combat',
+            viewData: receivedData
+          });
+        } else {
+          set({
+            combatData: updatedCombatData
+          });
+        }
       },
 
       // Cerrar WebSocket
@@ -19,7 +19,7 @@ interface Props {
 
 const BroadcastControls: React.FC<Props> = ({ currentRound, totalRounds }) => {
   const [currentView, setCurrentView] = React.useState<string | null>(null);
-  const { broadcastViewChange, wsPort, wsHost, openBroadcastUrl } =
+  const { broadcastViewChange, serverUrl, serverStatus, openBroadcastUrl } =
     useWebSocketBroadcast();
 
   const battleConfig = useBattleStore(state => state.battleConfig);
@@ -78,10 +78,19 @@ const BroadcastControls: React.FC<Props> = ({ currentRound, totalRounds }) => {
               }}
               className="inline-flex cursor-pointer items-center gap-1 font-mono text-blue-400 underline decoration-dotted transition-colors hover:text-blue-300 hover:decoration-solid"
             >
-              http://{wsHost}:{wsPort}
+              {serverUrl}
               <ExternalLink size={12} className="opacity-70" />
             </button>
           </div>
+
+          {serverStatus?.certificate_fingerprint && (
+            <div className="mt-1 text-xs text-zinc-500">
+              🔒 Huella del certificado:{' '}
+              <span className="font-mono break-all text-zinc-400">
+                {serverStatus.certificate_fingerprint}
+              </span>
+            </div>
+          )}
         </div>
 
         <div className="grid grid-cols-2 gap-3">
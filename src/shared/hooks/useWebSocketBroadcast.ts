@@ -1,16 +1,59 @@
+import { useCallback, useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { devErrorLog, devInfoLog } from '@utils/devLog';
 import { Err, Ok, Result } from 'ts-results';
 
-const WS_PORT = 8080;
-const DEFAULT_HOST = '127.0.0.1';
+const DEFAULT_SERVER_URL = 'http://127.0.0.1:8080';
+
+/**
+ * Estado del servidor de transmisión reportado por el backend
+ */
+export interface BroadcastServerStatus {
+  running: boolean;
+  url: string | null;
+  bind_address: string;
+  port: number | null;
+  requested_port: number;
+  serve_static: boolean;
+  started_at: number | null;
+  connected_clients: number;
+  tls: boolean;
+  certificate_fingerprint: string | null;
+}
 
 /**
  * Hook para manejar la funcionalidad de WebSocket broadcast
  * Proporciona funciones para iniciar el servidor WS y abrir páginas de transmisión
  */
 export const useWebSocketBroadcast = () => {
+  const [serverStatus, setServerStatus] =
+    useState<BroadcastServerStatus | null>(null);
+
+  /**
+   * Consulta el estado del servidor (el puerto puede variar si el preferido estaba ocupado)
+   */
+  const refreshServerStatus = useCallback(async (): Promise<
+    Result<BroadcastServerStatus, Error>
+  > => {
+    try {
+      const status = await invoke<BroadcastServerStatus>(
+        'get_broadcast_server_status',
+      );
+      setServerStatus(status);
+      return Ok(status);
+    } catch (error: unknown) {
+      devErrorLog('Error fetching broadcast server status:', error);
+      return Err(error instanceof Error ? error : new Error(String(error)));
+    }
+  }, []);
+
+  useEffect(() => {
+    void refreshServerStatus();
+  }, [refreshServerStatus]);
+
+  const serverUrl = serverStatus?.url ?? DEFAULT_SERVER_URL;
+
   /**
    * Abre una URL en el navegador del sistema
    * Intenta usar el plugin de Tauri, fallback a window.open
@@ -19,7 +62,7 @@ export const useWebSocketBroadcast = () => {
     path: string,
   ): Promise<Result<void, Error>> => {
     try {
-      const url = `http://${DEFAULT_HOST}:${WS_PORT}${path}`;
+      const url = `${serverUrl}${path}`;
 
       try {
         await openUrl(url);
@@ -112,8 +155,9 @@ export const useWebSocketBroadcast = () => {
     broadcastBattleConfig,
     broadcastViewChange,
     broadcastRoutes,
-    wsPort: WS_PORT,
-    wsHost: DEFAULT_HOST,
+    serverStatus,
+    serverUrl,
+    refreshServerStatus,
   };
 };
 